@@ -45,7 +45,7 @@ builder = (
     .with_data(stacked_data)
     .with_rtol(1e-6)
     .with_atol(1e-8)
-    .with_parameter("L", 10.0)
+    .with_parameter("L", 10.0, bounds=(0.5, 20.0))
     .with_cost(cost)
     .with_optimiser(optimiser)
 )
@@ -1,4 +1,4 @@
-"""Model evidence example using the dynamic nested sampler."""
+"""Model evidence example comparing dynamic nested sampling with sequential Monte Carlo."""
 
 from __future__ import annotations
 
@@ -13,8 +13,8 @@ def rosenbrock(x: list[float]) -> float:
 builder = (
     chron.ScalarBuilder()
     .with_callable(rosenbrock)
-    .with_parameter("x", initial_value=1.2)
-    .with_parameter("y", initial_value=1.4)
+    .with_parameter("x", initial_value=1.2, bounds=(-4.0, 6.0))
+    .with_parameter("y", initial_value=1.4, bounds=(-4.0, 6.0))
     .with_optimiser(chron.NelderMead().with_max_iter(2000))
 )
 problem = builder.build()
@@ -28,3 +28,10 @@ print("time       :", samples.time)
 print("log(Z)      :", samples.log_evidence)
 print("information :", samples.information)
 print("evaluations :", samples.draws)
+
+smc = chron.sampler.SequentialMonteCarlo().with_particles(1024).with_seed(1234)
+particles = smc.run(problem)
+
+print("SMC time    :", particles.time)
+print("SMC log(Z)  :", particles.log_evidence)
+print("SMC stages  :", len(particles.schedule) - 1)
@@ -22,8 +22,9 @@ builder = (
     chron.DiffsolBuilder()
     .with_diffsl(ds)
     .with_data(stacked_data)
-    .with_parameter("r", initial_value=1.2)
-    .with_parameter("k", initial_value=1.4)
+    .with_parameter("r", initial_value=1.2, bounds=(0.1, 3.0))
+    .with_parameter("k", initial_value=1.4, bounds=(0.5, 2.0))
+    .with_cost(chron.GaussianNLL(0.01))
     .with_parallel(True)
 )
 problem = builder.build()
@@ -5,11 +5,22 @@ from __future__ import annotations
 from chronopt import plotting
 from chronopt._chronopt import (
     Adam,
+    AdamState,
     CMAES,
+    CMAESState,
     CostMetric,
+    DifferentialEvolution,
+    IterationProgress,
+    LBFGSB,
+    LevenbergMarquardt,
     NelderMead,
+    NelderMeadState,
+    OptimisationHistory,
     OptimisationResults,
+    PSO,
+    Prior,
     Problem,
+    RestartRecord,
 )
 
 from . import builder, cost, sampler
@@ -22,7 +33,18 @@ from .builder import (
     VectorProblemBuilder,
 )
 from .cost import RMSE, SSE, GaussianNLL
-from .sampler import DynamicNestedSampler, MetropolisHastings, NestedSamples, Samples
+from .sampler import (
+    DynamicNestedSampler,
+    EnsembleSampler,
+    HamiltonianMonteCarlo,
+    MetropolisHastings,
+    NestedSamples,
+    ParallelTempering,
+    ParticleSamples,
+    Samples,
+    SequentialMonteCarlo,
+    TemperedSamples,
+)
 
 __all__ = [
     "builder",
@@ -36,15 +58,32 @@ __all__ = [
     "ScalarProblemBuilder",
     "VectorProblemBuilder",
     "DynamicNestedSampler",
+    "EnsembleSampler",
+    "HamiltonianMonteCarlo",
     "MetropolisHastings",
     "NestedSamples",
+    "ParallelTempering",
+    "ParticleSamples",
     "Samples",
+    "SequentialMonteCarlo",
+    "TemperedSamples",
     "Adam",
+    "AdamState",
     "CMAES",
+    "CMAESState",
     "CostMetric",
+    "DifferentialEvolution",
+    "IterationProgress",
+    "LBFGSB",
+    "LevenbergMarquardt",
     "NelderMead",
+    "NelderMeadState",
+    "OptimisationHistory",
     "OptimisationResults",
+    "PSO",
+    "Prior",
     "Problem",
+    "RestartRecord",
     "SSE",
     "RMSE",
     "GaussianNLL",
@@ -3,6 +3,8 @@
 
 import builtins
 import datetime
+import os
+import pathlib
 import typing
 
 import numpy
@@ -41,6 +43,22 @@ class Adam:
         r"""
         Abort the run once the patience window has elapsed.
         """
+    def with_history(self, enabled: builtins.bool) -> Adam:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> Adam:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def initialise(
+        self,
+        initial: typing.Sequence[builtins.float],
+        bounds: typing.Sequence[tuple[builtins.float, builtins.float]] | None = None,
+    ) -> AdamState:
+        r"""
+        Start an ask/tell session from `initial`, optionally clamped to per-parameter bounds.
+        """
     def run(
         self, problem: Problem, initial: typing.Sequence[builtins.float]
     ) -> OptimisationResults:
@@ -48,6 +66,33 @@ class Adam:
         Optimise the given problem using Adam starting from the provided point.
         """
 
+@typing.final
+class AdamState:
+    r"""
+    In-progress Adam descent driven through `ask` and `tell`.
+    """
+    def ask(self) -> builtins.list[builtins.list[builtins.float]]:
+        r"""
+        The current iterate to evaluate; empty once the descent has finished.
+        """
+    def tell(
+        self,
+        points: typing.Sequence[typing.Sequence[builtins.float]],
+        values: typing.Sequence[builtins.float],
+        gradients: typing.Sequence[typing.Sequence[builtins.float]],
+    ) -> None:
+        r"""
+        Report objective values and gradients for the points returned by `ask`.
+        """
+    def is_finished(self) -> builtins.bool:
+        r"""
+        Whether the descent has met a termination criterion.
+        """
+    def result(self) -> OptimisationResults:
+        r"""
+        Snapshot of the descent so far.
+        """
+
 @typing.final
 class CMAES:
     r"""
@@ -81,6 +126,49 @@ class CMAES:
         r"""
         Initialise the internal RNG for reproducible runs.
         """
+    def with_restarts(self, strategy: builtins.str) -> CMAES:
+        r"""
+        Restart stalled runs with growing ('ipop') or alternating ('bipop') population sizes.
+
+        Each restart begins from a fresh starting point, and the sequence runs until
+        `max_restarts` is reached or patience elapses.
+        """
+    def with_max_restarts(self, max_restarts: builtins.int) -> CMAES:
+        r"""
+        Limit the number of restarts performed after the initial run.
+        """
+    def with_population_increase(self, factor: builtins.float) -> CMAES:
+        r"""
+        Set the factor by which large-population restarts grow the population size.
+        """
+    def with_history(self, enabled: builtins.bool) -> CMAES:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> CMAES:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def initialise(
+        self,
+        initial: typing.Sequence[builtins.float],
+        bounds: typing.Sequence[tuple[builtins.float, builtins.float]] | None = None,
+    ) -> CMAESState:
+        r"""
+        Start an ask/tell session from `initial`, optionally clamped to per-parameter bounds.
+        """
+    def with_checkpoint(
+        self, path: builtins.str | os.PathLike | pathlib.Path, every: builtins.int
+    ) -> CMAES:
+        r"""
+        Write a checkpoint to `path` every `every` iterations; continue with `resume_from`.
+        """
+    def resume_from(
+        self, problem: Problem, path: builtins.str | os.PathLike | pathlib.Path
+    ) -> OptimisationResults:
+        r"""
+        Continue an interrupted run from the checkpoint at `path`.
+        """
     def run(
         self, problem: Problem, initial: typing.Sequence[builtins.float]
     ) -> OptimisationResults:
@@ -88,6 +176,32 @@ class CMAES:
         Optimise the given problem starting from the provided mean vector.
         """
 
+@typing.final
+class CMAESState:
+    r"""
+    In-progress CMA-ES search driven through `ask` and `tell`.
+    """
+    def ask(self) -> builtins.list[builtins.list[builtins.float]]:
+        r"""
+        Candidate points to evaluate next; empty once the search has finished.
+        """
+    def tell(
+        self,
+        points: typing.Sequence[typing.Sequence[builtins.float]],
+        values: typing.Sequence[builtins.float],
+    ) -> None:
+        r"""
+        Report objective values for the points returned by the previous `ask`.
+        """
+    def is_finished(self) -> builtins.bool:
+        r"""
+        Whether the search has met a termination criterion.
+        """
+    def result(self) -> OptimisationResults:
+        r"""
+        Snapshot of the search so far.
+        """
+
 @typing.final
 class CostMetric:
     @property
@@ -97,6 +211,68 @@ class CostMetric:
         """
     def __repr__(self) -> builtins.str: ...
 
+@typing.final
+class DifferentialEvolution:
+    r"""
+    Differential evolution optimiser evaluating each generation as a batch.
+    """
+    def __new__(cls) -> DifferentialEvolution:
+        r"""
+        Create a differential evolution optimiser with library defaults.
+        """
+    def with_max_iter(self, max_iter: builtins.int) -> DifferentialEvolution:
+        r"""
+        Limit the number of generations before termination.
+        """
+    def with_threshold(self, threshold: builtins.float) -> DifferentialEvolution:
+        r"""
+        Set the stopping threshold on the population's objective and parameter spread.
+        """
+    def with_sigma0(self, sigma0: builtins.float) -> DifferentialEvolution:
+        r"""
+        Set the initial spread used for parameters without finite bounds.
+        """
+    def with_patience(self, patience_seconds: builtins.float) -> DifferentialEvolution:
+        r"""
+        Abort the run once the patience window has elapsed.
+        """
+    def with_population_size(
+        self, population_size: builtins.int
+    ) -> DifferentialEvolution:
+        r"""
+        Specify the number of population members (at least four).
+        """
+    def with_mutation(self, mutation: builtins.float) -> DifferentialEvolution:
+        r"""
+        Set the differential weight `F` applied to difference vectors.
+        """
+    def with_crossover(self, crossover: builtins.float) -> DifferentialEvolution:
+        r"""
+        Set the binomial crossover probability `CR`.
+        """
+    def with_strategy(self, strategy: builtins.str) -> DifferentialEvolution:
+        r"""
+        Choose the mutation scheme: 'rand1bin' or 'best1bin'.
+        """
+    def with_seed(self, seed: builtins.int) -> DifferentialEvolution:
+        r"""
+        Initialise the internal RNG for reproducible runs.
+        """
+    def with_history(self, enabled: builtins.bool) -> DifferentialEvolution:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> DifferentialEvolution:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def run(
+        self, problem: Problem, initial: typing.Sequence[builtins.float]
+    ) -> OptimisationResults:
+        r"""
+        Optimise the given problem, seeding the population with the provided point.
+        """
+
 @typing.final
 class DiffsolBuilder:
     r"""
@@ -127,6 +303,55 @@ class DiffsolBuilder:
         r"""
         Remove any previously attached data along with its time span.
         """
+    def with_mask(self, mask: numpy.typing.NDArray[numpy.bool_]) -> DiffsolBuilder:
+        r"""
+        Mark which observations are present (`True`) or missing (`False`).
+
+        The mask has the shape of the data without its time column. Missing
+        observations, like NaN entries in the data, are left out of the residuals and
+        every cost metric.
+        """
+    def remove_mask(self) -> DiffsolBuilder:
+        r"""
+        Treat every non-NaN observation as present again.
+        """
+    def with_experiment(
+        self,
+        data: numpy.typing.NDArray[numpy.float64],
+        inputs: typing.Mapping[builtins.str, builtins.float] | None = None,
+        initial_conditions: typing.Mapping[builtins.str, builtins.float] | None = None,
+        observations: typing.Sequence[builtins.int | builtins.str] | None = None,
+        mask: numpy.typing.NDArray[numpy.bool_] | None = None,
+        cost: CostMetric | None = None,
+    ) -> DiffsolBuilder:
+        r"""
+        Add an experiment fitted jointly with the data and other experiments.
+
+        `data` follows the layout of `with_data`. `inputs` fixes DiffSL inputs and
+        `initial_conditions` overrides named states of the `u_i` block for this
+        experiment only; inputs fixed in every experiment leave the parameter vector.
+        `observations` and `cost` replace the builder's observation mapping and cost
+        metric for this experiment, and `mask` marks its missing observations as in
+        `with_mask`.
+        """
+    def remove_experiments(self) -> DiffsolBuilder:
+        r"""
+        Remove all experiments added with `with_experiment`.
+        """
+    def with_observations(
+        self, outputs: typing.Sequence[builtins.int | builtins.str]
+    ) -> DiffsolBuilder:
+        r"""
+        Compare each data column against a selected DiffSL output.
+
+        Column `j` after the time column is fitted to `outputs[j]`, given either as an
+        index into `out_i` or as the name of an output (or state when the program has
+        no `out_i` block), so unobserved states need no data columns.
+        """
+    def remove_observations(self) -> DiffsolBuilder:
+        r"""
+        Compare the full model output against the data again.
+        """
     def with_backend(self, backend: builtins.str) -> DiffsolBuilder:
         r"""
         Choose whether to use dense or sparse diffusion solvers.
@@ -153,14 +378,34 @@ class DiffsolBuilder:
         name: builtins.str,
         initial_value: builtins.float,
         bounds: tuple[builtins.float, builtins.float] | None = None,
+        prior: Prior | None = None,
     ) -> DiffsolBuilder:
         r"""
         Register a named optimisation variable in the order it appears in vectors.
+
+        An optional `prior` is used by the samplers to target the posterior.
         """
     def clear_parameters(self) -> DiffsolBuilder:
         r"""
         Remove previously provided parameter defaults.
         """
+    def with_estimated_initial_state(
+        self,
+        state: builtins.str,
+        name: builtins.str,
+        initial_value: builtins.float,
+        bounds: tuple[builtins.float, builtins.float] | None = None,
+        prior: Prior | None = None,
+    ) -> DiffsolBuilder:
+        r"""
+        Estimate the initial value of a DiffSL state alongside the model parameters.
+
+        Estimated initial states follow the registered parameters in vectors.
+        """
+    def remove_estimated_initial_states(self) -> DiffsolBuilder:
+        r"""
+        Keep every initial state at the value written in the DiffSL program.
+        """
     def with_cost(self, cost: CostMetric) -> DiffsolBuilder:
         r"""
         Select the error metric used to compare simulated and observed data.
@@ -169,7 +414,16 @@ class DiffsolBuilder:
         r"""
         Reset the cost metric to the default sum of squared errors.
         """
-    def with_optimiser(self, optimiser: NelderMead | CMAES | Adam) -> DiffsolBuilder:
+    def with_optimiser(
+        self,
+        optimiser: NelderMead
+        | CMAES
+        | Adam
+        | LBFGSB
+        | LevenbergMarquardt
+        | DifferentialEvolution
+        | PSO,
+    ) -> DiffsolBuilder:
         r"""
         Configure the default optimiser used when `Problem.optimize` omits one.
         """
@@ -178,6 +432,130 @@ class DiffsolBuilder:
         Create a `Problem` representing the differential solver model.
         """
 
+@typing.final
+class IterationProgress:
+    r"""
+    Optimiser progress passed to observer callables after each iteration.
+    """
+    @property
+    def iteration(self) -> builtins.int:
+        r"""
+        Iteration number, starting at 1.
+        """
+    @property
+    def nfev(self) -> builtins.int:
+        r"""
+        Objective evaluations performed so far.
+        """
+    @property
+    def x(self) -> builtins.list[builtins.float]:
+        r"""
+        Best decision vector found so far.
+        """
+    @property
+    def fun(self) -> builtins.float:
+        r"""
+        Objective value at `x`.
+        """
+    @property
+    def step_size(self) -> builtins.float:
+        r"""
+        CMA-ES step-size, Nelder-Mead simplex radius or length of the latest Adam update.
+        """
+    @property
+    def elapsed(self) -> datetime.timedelta:
+        r"""
+        Wall-clock time since the run started.
+        """
+    def __repr__(self) -> builtins.str: ...
+
+@typing.final
+class LBFGSB:
+    r"""
+    Limited-memory BFGS quasi-Newton optimiser with projected bound handling.
+    """
+    def __new__(cls) -> LBFGSB:
+        r"""
+        Create an L-BFGS-B optimiser with library defaults.
+        """
+    def with_max_iter(self, max_iter: builtins.int) -> LBFGSB:
+        r"""
+        Limit the maximum number of optimisation iterations.
+        """
+    def with_threshold(self, threshold: builtins.float) -> LBFGSB:
+        r"""
+        Set the stopping threshold on the projected gradient norm and cost change.
+        """
+    def with_memory(self, memory: builtins.int) -> LBFGSB:
+        r"""
+        Set the number of curvature pairs retained for the Hessian approximation.
+        """
+    def with_max_line_search(self, max_line_search: builtins.int) -> LBFGSB:
+        r"""
+        Limit the number of backtracking steps attempted per line search.
+        """
+    def with_patience(self, patience_seconds: builtins.float) -> LBFGSB:
+        r"""
+        Abort the run once the patience window has elapsed.
+        """
+    def with_history(self, enabled: builtins.bool) -> LBFGSB:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> LBFGSB:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def run(
+        self, problem: Problem, initial: typing.Sequence[builtins.float]
+    ) -> OptimisationResults:
+        r"""
+        Optimise the given problem using L-BFGS-B starting from the provided point.
+        """
+
+@typing.final
+class LevenbergMarquardt:
+    r"""
+    Levenberg-Marquardt least-squares optimiser for residual-based problems.
+
+    Residuals are weighted by the problem's cost metric, which must be SSE or
+    Gaussian NLL. Sparse Diffsol backends use a finite-difference Jacobian.
+    """
+    def __new__(cls) -> LevenbergMarquardt:
+        r"""
+        Create a Levenberg-Marquardt optimiser with library defaults.
+        """
+    def with_max_iter(self, max_iter: builtins.int) -> LevenbergMarquardt:
+        r"""
+        Limit the maximum number of accepted steps.
+        """
+    def with_threshold(self, threshold: builtins.float) -> LevenbergMarquardt:
+        r"""
+        Set the stopping threshold on the gradient norm, relative cost change and step size.
+        """
+    def with_initial_damping(self, damping: builtins.float) -> LevenbergMarquardt:
+        r"""
+        Set the initial damping factor blending Gauss-Newton and gradient descent steps.
+        """
+    def with_patience(self, patience_seconds: builtins.float) -> LevenbergMarquardt:
+        r"""
+        Abort the run once the patience window has elapsed.
+        """
+    def with_history(self, enabled: builtins.bool) -> LevenbergMarquardt:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> LevenbergMarquardt:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def run(
+        self, problem: Problem, initial: typing.Sequence[builtins.float]
+    ) -> OptimisationResults:
+        r"""
+        Fit the residuals of the given problem starting from the provided point.
+        """
+
 @typing.final
 class NelderMead:
     r"""
@@ -217,6 +595,34 @@ class NelderMead:
         r"""
         Abort if the objective fails to improve within the allotted time.
         """
+    def with_history(self, enabled: builtins.bool) -> NelderMead:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> NelderMead:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def initialise(
+        self,
+        initial: typing.Sequence[builtins.float],
+        bounds: typing.Sequence[tuple[builtins.float, builtins.float]] | None = None,
+    ) -> NelderMeadState:
+        r"""
+        Start an ask/tell session from `initial`, optionally clamped to per-parameter bounds.
+        """
+    def with_checkpoint(
+        self, path: builtins.str | os.PathLike | pathlib.Path, every: builtins.int
+    ) -> NelderMead:
+        r"""
+        Write a checkpoint to `path` every `every` iterations; continue with `resume_from`.
+        """
+    def resume_from(
+        self, problem: Problem, path: builtins.str | os.PathLike | pathlib.Path
+    ) -> OptimisationResults:
+        r"""
+        Continue an interrupted run from the checkpoint at `path`.
+        """
     def run(
         self, problem: Problem, initial: typing.Sequence[builtins.float]
     ) -> OptimisationResults:
@@ -224,6 +630,65 @@ class NelderMead:
         Optimise the given problem starting from the provided initial simplex centre.
         """
 
+@typing.final
+class NelderMeadState:
+    r"""
+    In-progress Nelder-Mead search driven through `ask` and `tell`.
+    """
+    def ask(self) -> builtins.list[builtins.list[builtins.float]]:
+        r"""
+        Candidate points to evaluate next; empty once the search has finished.
+        """
+    def tell(
+        self,
+        points: typing.Sequence[typing.Sequence[builtins.float]],
+        values: typing.Sequence[builtins.float],
+    ) -> None:
+        r"""
+        Report objective values for the points returned by the previous `ask`.
+        """
+    def is_finished(self) -> builtins.bool:
+        r"""
+        Whether the search has met a termination criterion.
+        """
+    def result(self) -> OptimisationResults:
+        r"""
+        Snapshot of the search so far.
+        """
+
+@typing.final
+class OptimisationHistory:
+    r"""
+    Per-iteration optimisation trace returned as NumPy arrays.
+    """
+    @property
+    def fun(self) -> numpy.typing.NDArray[numpy.float64]:
+        r"""
+        Best objective value after each iteration.
+        """
+    @property
+    def x(self) -> numpy.typing.NDArray[numpy.float64]:
+        r"""
+        Best decision vector after each iteration, one row per iteration.
+        """
+    @property
+    def nfev(self) -> numpy.typing.NDArray[numpy.uint64]:
+        r"""
+        Cumulative objective evaluations after each iteration.
+        """
+    @property
+    def step_size(self) -> numpy.typing.NDArray[numpy.float64]:
+        r"""
+        CMA-ES step-size, Nelder-Mead simplex radius or Adam update length per iteration.
+        """
+    @property
+    def gradient_norm(self) -> numpy.typing.NDArray[numpy.float64] | None:
+        r"""
+        Gradient norm per iteration, recorded by gradient-based optimisers only.
+        """
+    def __len__(self) -> builtins.int: ...
+    def __repr__(self) -> builtins.str: ...
+
 @typing.final
 class OptimisationResults:
     r"""
@@ -284,13 +749,153 @@ class OptimisationResults:
         self,
     ) -> builtins.list[builtins.list[builtins.float]] | None:
         r"""
-        Estimated covariance of the search distribution, if available.
+        Estimated covariance of the search distribution or fitted parameters, if available.
+        """
+    @property
+    def restarts(self) -> builtins.list[RestartRecord]:
+        r"""
+        Per-run summaries recorded by CMA-ES restart strategies.
+        """
+    @property
+    def history(self) -> OptimisationHistory | None:
+        r"""
+        Per-iteration trace, present when the optimiser was built `with_history(True)`.
         """
     def __repr__(self) -> builtins.str:
         r"""
         Render a concise summary of the optimisation outcome.
         """
 
+@typing.final
+class PSO:
+    r"""
+    Particle swarm optimiser evaluating the whole swarm as a batch each iteration.
+    """
+    def __new__(cls) -> PSO:
+        r"""
+        Create a particle swarm optimiser with library defaults.
+        """
+    def with_max_iter(self, max_iter: builtins.int) -> PSO:
+        r"""
+        Limit the number of swarm iterations before termination.
+        """
+    def with_threshold(self, threshold: builtins.float) -> PSO:
+        r"""
+        Set the stopping threshold on the spread of the particles' best positions and values.
+        """
+    def with_sigma0(self, sigma0: builtins.float) -> PSO:
+        r"""
+        Set the initial spread of positions and velocities for parameters without finite bounds.
+        """
+    def with_patience(self, patience_seconds: builtins.float) -> PSO:
+        r"""
+        Abort the run once the patience window has elapsed.
+        """
+    def with_population_size(self, population_size: builtins.int) -> PSO:
+        r"""
+        Specify the number of particles in the swarm.
+        """
+    def with_inertia(self, inertia: builtins.float) -> PSO:
+        r"""
+        Set the weight applied to each particle's previous velocity.
+        """
+    def with_cognitive(self, cognitive: builtins.float) -> PSO:
+        r"""
+        Set the attraction towards each particle's own best position.
+        """
+    def with_social(self, social: builtins.float) -> PSO:
+        r"""
+        Set the attraction towards the best position found by the swarm.
+        """
+    def with_seed(self, seed: builtins.int) -> PSO:
+        r"""
+        Initialise the internal RNG for reproducible runs.
+        """
+    def with_history(self, enabled: builtins.bool) -> PSO:
+        r"""
+        Record a per-iteration trace in `OptimisationResults.history`.
+        """
+    def with_observer(self, observer: typing.Any) -> PSO:
+        r"""
+        Call `observer(progress)` after every iteration; a truthy return value stops the run.
+        """
+    def run(
+        self, problem: Problem, initial: typing.Sequence[builtins.float]
+    ) -> OptimisationResults:
+        r"""
+        Optimise the given problem, seeding the swarm with the provided point.
+        """
+
+@typing.final
+class Prior:
+    r"""
+    Prior distribution over a single parameter.
+    """
+    @staticmethod
+    def uniform(lower: builtins.float, upper: builtins.float) -> Prior:
+        r"""
+        Uniform prior on `[lower, upper]`.
+        """
+    @staticmethod
+    def normal(mean: builtins.float, std: builtins.float) -> Prior:
+        r"""
+        Normal prior with the given mean and standard deviation.
+        """
+    @staticmethod
+    def log_normal(mean: builtins.float, std: builtins.float) -> Prior:
+        r"""
+        Log-normal prior; `mean` and `std` describe the logarithm of the parameter.
+        """
+    @staticmethod
+    def truncated_normal(
+        mean: builtins.float,
+        std: builtins.float,
+        lower: builtins.float,
+        upper: builtins.float,
+    ) -> Prior:
+        r"""
+        Normal prior restricted to `[lower, upper]`; either limit may be infinite.
+        """
+    @staticmethod
+    def half_cauchy(scale: builtins.float) -> Prior:
+        r"""
+        Half-Cauchy prior on `[0, inf)` with the given scale.
+        """
+    @staticmethod
+    def beta(alpha: builtins.float, beta: builtins.float) -> Prior:
+        r"""
+        Beta prior on `[0, 1]`.
+        """
+    @staticmethod
+    def gamma(shape: builtins.float, rate: builtins.float) -> Prior:
+        r"""
+        Gamma prior parameterised by shape and rate.
+        """
+    @property
+    def support(self) -> tuple[builtins.float, builtins.float]:
+        r"""
+        Interval containing all of the prior's probability mass.
+        """
+    def log_density(self, x: builtins.float) -> builtins.float:
+        r"""
+        Natural logarithm of the density at `x`.
+        """
+    def to_unit(self, x: builtins.float) -> builtins.float:
+        r"""
+        Map `x` onto the unit interval using the prior's CDF.
+        """
+    def from_unit(self, u: builtins.float) -> builtins.float:
+        r"""
+        Map a unit-interval coordinate onto the support using the inverse CDF.
+        """
+    def sample(
+        self, size: builtins.int = 1, seed: builtins.int | None = None
+    ) -> builtins.list[builtins.float]:
+        r"""
+        Draw `size` independent values from the prior.
+        """
+    def __repr__(self) -> builtins.str: ...
+
 @typing.final
 class Problem:
     r"""
@@ -306,10 +911,31 @@ class Problem:
         r"""
         Evaluate the gradient of the objective function at `x` if available.
         """
+    def residuals(
+        self, x: typing.Sequence[builtins.float]
+    ) -> builtins.list[builtins.float]:
+        r"""
+        Return the unreduced residuals (prediction minus observation) at `x`.
+        """
+    def residuals_with_jacobian(
+        self, x: typing.Sequence[builtins.float]
+    ) -> tuple[
+        builtins.list[builtins.float], builtins.list[builtins.list[builtins.float]]
+    ]:
+        r"""
+        Return the residuals at `x` together with their Jacobian (one row per residual).
+        """
     def optimize(
         self,
         initial: typing.Sequence[builtins.float] | None = None,
-        optimiser: NelderMead | CMAES | Adam | None = None,
+        optimiser: NelderMead
+        | CMAES
+        | Adam
+        | LBFGSB
+        | LevenbergMarquardt
+        | DifferentialEvolution
+        | PSO
+        | None = None,
     ) -> OptimisationResults:
         r"""
         Solve the problem starting from `initial` using the supplied optimiser.
@@ -340,6 +966,53 @@ class Problem:
         Return a copy of the problem configuration dictionary.
         """
 
+@typing.final
+class RestartRecord:
+    r"""
+    Summary of a single CMA-ES run within a restart sequence.
+    """
+    @property
+    def population_size(self) -> builtins.int:
+        r"""
+        Population size used by this run.
+        """
+    @property
+    def sigma0(self) -> builtins.float:
+        r"""
+        Initial step-size used by this run.
+        """
+    @property
+    def x0(self) -> builtins.list[builtins.float]:
+        r"""
+        Starting point of this run.
+        """
+    @property
+    def x(self) -> builtins.list[builtins.float]:
+        r"""
+        Best decision vector found during this run.
+        """
+    @property
+    def fun(self) -> builtins.float:
+        r"""
+        Objective value at `x`.
+        """
+    @property
+    def nit(self) -> builtins.int:
+        r"""
+        Number of generations performed by this run.
+        """
+    @property
+    def nfev(self) -> builtins.int:
+        r"""
+        Number of objective evaluations performed by this run.
+        """
+    @property
+    def termination_reason(self) -> builtins.str:
+        r"""
+        Reason this run stopped.
+        """
+    def __repr__(self) -> builtins.str: ...
+
 @typing.final
 class ScalarBuilder:
     r"""
@@ -349,7 +1022,16 @@ class ScalarBuilder:
         r"""
         Create an empty builder with no objective, parameters, or default optimiser.
         """
-    def with_optimiser(self, optimiser: NelderMead | CMAES | Adam) -> ScalarBuilder:
+    def with_optimiser(
+        self,
+        optimiser: NelderMead
+        | CMAES
+        | Adam
+        | LBFGSB
+        | LevenbergMarquardt
+        | DifferentialEvolution
+        | PSO,
+    ) -> ScalarBuilder:
         r"""
         Configure the default optimiser used when `Problem.optimize` omits one.
         """
@@ -366,9 +1048,12 @@ class ScalarBuilder:
         name: builtins.str,
         initial_value: builtins.float,
         bounds: tuple[builtins.float, builtins.float] | None = None,
+        prior: Prior | None = None,
     ) -> ScalarBuilder:
         r"""
         Register a named optimisation variable in the order it appears in vectors.
+
+        An optional `prior` is used by the samplers to target the posterior.
         """
     def build(self) -> Problem:
         r"""
@@ -398,6 +1083,17 @@ class VectorBuilder:
         The data should be a 1D numpy array. The shape will be inferred
         from the data length.
         """
+    def with_mask(self, mask: numpy.typing.NDArray[numpy.bool_]) -> VectorBuilder:
+        r"""
+        Mark which observations are present (`True`) or missing (`False`).
+
+        Missing observations, like NaN entries in the data, are left out of the
+        residuals and every cost metric.
+        """
+    def remove_mask(self) -> VectorBuilder:
+        r"""
+        Treat every non-NaN observation as present again.
+        """
     def with_config(self, key: builtins.str, value: builtins.float) -> VectorBuilder:
         r"""
         Stores an optimisation configuration value keyed by name.
@@ -407,9 +1103,12 @@ class VectorBuilder:
         name: builtins.str,
         initial_value: builtins.float,
         bounds: tuple[builtins.float, builtins.float] | None = None,
+        prior: Prior | None = None,
     ) -> VectorBuilder:
         r"""
         Register a named optimisation variable in the order it appears in vectors.
+
+        An optional `prior` is used by the samplers to target the posterior.
         """
     def clear_parameters(self) -> VectorBuilder:
         r"""
@@ -423,7 +1122,16 @@ class VectorBuilder:
         r"""
         Reset the cost metric to the default sum of squared errors.
         """
-    def with_optimiser(self, optimiser: NelderMead | CMAES | Adam) -> VectorBuilder:
+    def with_optimiser(
+        self,
+        optimiser: NelderMead
+        | CMAES
+        | Adam
+        | LBFGSB
+        | LevenbergMarquardt
+        | DifferentialEvolution
+        | PSO,
+    ) -> VectorBuilder:
         r"""
         Configure the default optimiser used when `Problem.optimize` omits one.
         """
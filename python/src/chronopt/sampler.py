@@ -4,9 +4,26 @@ from __future__ import annotations
 
 from chronopt._chronopt import (
     DynamicNestedSampler,
+    EnsembleSampler,
+    HamiltonianMonteCarlo,
     MetropolisHastings,
     NestedSamples,
+    ParallelTempering,
+    ParticleSamples,
     Samples,
+    SequentialMonteCarlo,
+    TemperedSamples,
 )
 
-__all__ = ["MetropolisHastings", "DynamicNestedSampler", "Samples", "NestedSamples"]
+__all__ = [
+    "MetropolisHastings",
+    "DynamicNestedSampler",
+    "HamiltonianMonteCarlo",
+    "EnsembleSampler",
+    "ParallelTempering",
+    "SequentialMonteCarlo",
+    "Samples",
+    "NestedSamples",
+    "TemperedSamples",
+    "ParticleSamples",
+]
@@ -3,6 +3,8 @@
 
 import builtins
 import datetime
+import os
+import pathlib
 import typing
 
 from chronopt._chronopt import Problem
@@ -20,13 +22,98 @@ class DynamicNestedSampler:
     def with_termination_tolerance(
         self, tolerance: builtins.float
     ) -> DynamicNestedSampler: ...
+    def with_bounding(self, bounding: builtins.str) -> DynamicNestedSampler:
+        r"""
+        Bound new live points with 'perturbation', 'ellipsoid' or 'multi_ellipsoid' regions.
+        """
+    def with_random_walk(self, steps: builtins.int) -> DynamicNestedSampler:
+        r"""
+        Generate live points by constrained random walks of `steps` moves.
+        """
+    def with_slice_sampling(self, slices: builtins.int) -> DynamicNestedSampler:
+        r"""
+        Generate live points by `slices` rounds of slice sampling along principal directions.
+        """
     def with_seed(self, seed: builtins.int) -> DynamicNestedSampler: ...
     def run(
         self,
         problem: Problem,
         initial: typing.Sequence[builtins.float] | None = None,
+    ) -> NestedSamples:
+        r"""
+        Sample the posterior and its evidence; raises if a parameter has neither a prior
+        nor bounds or the problem's costs are not a negative log-likelihood.
+        """
+    def with_checkpoint(
+        self, path: builtins.str | os.PathLike | pathlib.Path, every: builtins.int
+    ) -> DynamicNestedSampler: ...
+    def resume_from(
+        self, problem: Problem, path: builtins.str | os.PathLike | pathlib.Path
     ) -> NestedSamples: ...
 
+@typing.final
+class EnsembleSampler:
+    r"""
+    Affine-invariant ensemble sampler (emcee-style stretch and differential-evolution moves).
+    """
+    def __new__(cls) -> EnsembleSampler: ...
+    def with_walkers(self, walkers: builtins.int) -> EnsembleSampler: ...
+    def with_iterations(self, iterations: builtins.int) -> EnsembleSampler: ...
+    def with_stretch_scale(self, scale: builtins.float) -> EnsembleSampler: ...
+    def with_differential_evolution(
+        self, probability: builtins.float
+    ) -> EnsembleSampler:
+        r"""
+        Probability that an update uses a differential-evolution move instead of a stretch.
+        """
+    def with_initial_spread(self, spread: builtins.float) -> EnsembleSampler: ...
+    def with_seed(self, seed: builtins.int) -> EnsembleSampler: ...
+    def run(
+        self,
+        problem: Problem,
+        initial: typing.Sequence[builtins.float] | None = None,
+    ) -> Samples:
+        r"""
+        Draw one chain per walker; raises if there are too few walkers or the
+        problem's costs are not a negative log-likelihood.
+        """
+
+@typing.final
+class HamiltonianMonteCarlo:
+    r"""
+    Hamiltonian Monte Carlo sampler driven by problem gradients, NUTS by default.
+    """
+    def __new__(cls) -> HamiltonianMonteCarlo: ...
+    def with_num_chains(self, num_chains: builtins.int) -> HamiltonianMonteCarlo: ...
+    def with_iterations(self, iterations: builtins.int) -> HamiltonianMonteCarlo: ...
+    def with_warmup(self, warmup: builtins.int) -> HamiltonianMonteCarlo: ...
+    def with_step_size(self, step_size: builtins.float) -> HamiltonianMonteCarlo: ...
+    def with_target_acceptance(
+        self, target: builtins.float
+    ) -> HamiltonianMonteCarlo: ...
+    def with_leapfrog_steps(self, steps: builtins.int) -> HamiltonianMonteCarlo:
+        r"""
+        Use classic HMC with a fixed number of leapfrog steps per transition.
+        """
+    def with_max_tree_depth(self, max_depth: builtins.int) -> HamiltonianMonteCarlo:
+        r"""
+        Use the No-U-Turn sampler with trajectories of at most `2**max_depth` steps.
+        """
+    def with_mass_matrix(self, mass_matrix: builtins.str) -> HamiltonianMonteCarlo:
+        r"""
+        Adapt a 'unit', 'diagonal' or 'dense' mass matrix during warmup.
+        """
+    def with_seed(self, seed: builtins.int) -> HamiltonianMonteCarlo: ...
+    def run(
+        self,
+        problem: Problem,
+        initial: typing.Sequence[builtins.float] | None = None,
+    ) -> Samples:
+        r"""
+        Draw post-warmup samples; raises if the problem provides no gradient or
+        its costs are not a negative log-likelihood.
+        """
+
 @typing.final
 class MetropolisHastings:
     r"""
@@ -38,10 +125,24 @@ class MetropolisHastings:
     def with_iterations(self, iterations: builtins.int) -> MetropolisHastings: ...
     def with_num_steps(self, steps: builtins.int) -> MetropolisHastings: ...
     def with_step_size(self, step_size: builtins.float) -> MetropolisHastings: ...
+    def with_proposal_covariance(
+        self,
+        covariance: typing.Sequence[typing.Sequence[builtins.float]],
+    ) -> MetropolisHastings: ...
+    def with_adaptation(self, warmup: builtins.int) -> MetropolisHastings: ...
+    def with_target_acceptance(
+        self, target: builtins.float
+    ) -> MetropolisHastings: ...
     def with_seed(self, seed: builtins.int) -> MetropolisHastings: ...
+    def with_checkpoint(
+        self, path: builtins.str | os.PathLike | pathlib.Path, every: builtins.int
+    ) -> MetropolisHastings: ...
     def run(
         self, problem: Problem, initial: typing.Sequence[builtins.float]
     ) -> Samples: ...
+    def resume_from(
+        self, problem: Problem, path: builtins.str | os.PathLike | pathlib.Path
+    ) -> Samples: ...
 
 @typing.final
 class NestedSamples:
@@ -61,10 +162,106 @@ class NestedSamples:
     @property
     def log_evidence(self) -> builtins.float: ...
     @property
+    def log_evidence_error(self) -> builtins.float: ...
+    def simulated_log_evidence_error(
+        self, simulations: builtins.int = 200, seed: builtins.int = 0
+    ) -> builtins.float:
+        r"""
+        Log-evidence error from replaying the run with randomly drawn shrinkage factors.
+        """
+    @property
     def information(self) -> builtins.float: ...
     @property
+    def effective_sample_size(self) -> builtins.float: ...
+    @property
     def time(self) -> datetime.timedelta: ...
+    @property
+    def proposals(self) -> builtins.int: ...
+    @property
+    def accepted_proposals(self) -> builtins.int: ...
+    @property
+    def efficiency(self) -> builtins.float: ...
     def to_samples(self) -> Samples: ...
+    def resample(self, seed: builtins.int = 0) -> Samples:
+        r"""
+        Equal-weight posterior draws resampled from the importance weights.
+        """
+    def __repr__(self) -> builtins.str: ...
+
+@typing.final
+class ParallelTempering:
+    r"""
+    Parallel-tempering (replica exchange) sampler with an adaptive temperature ladder.
+    """
+    def __new__(cls) -> ParallelTempering: ...
+    def with_temperatures(
+        self, temperatures: typing.Sequence[builtins.float]
+    ) -> ParallelTempering:
+        r"""
+        Explicit temperature ladder; it must start at 1 and increase strictly.
+        """
+    def with_num_temperatures(
+        self, num_temperatures: builtins.int
+    ) -> ParallelTempering: ...
+    def with_max_temperature(
+        self, max_temperature: builtins.float
+    ) -> ParallelTempering: ...
+    def with_iterations(self, iterations: builtins.int) -> ParallelTempering: ...
+    def with_warmup(self, warmup: builtins.int) -> ParallelTempering: ...
+    def with_step_size(self, step_size: builtins.float) -> ParallelTempering: ...
+    def with_target_acceptance(self, target: builtins.float) -> ParallelTempering: ...
+    def with_ladder_adaptation(self, adapt: builtins.bool) -> ParallelTempering:
+        r"""
+        Whether warmup adapts the spacing of the interior temperatures.
+        """
+    def with_seed(self, seed: builtins.int) -> ParallelTempering: ...
+    def run(
+        self,
+        problem: Problem,
+        initial: typing.Sequence[builtins.float] | None = None,
+    ) -> TemperedSamples:
+        r"""
+        Sample the cold chain; raises if the ladder is invalid, the starting point has no
+        finite log-posterior density or the problem's costs are not a negative
+        log-likelihood.
+        """
+
+@typing.final
+class ParticleSamples:
+    r"""
+    Weighted particle population of a Sequential Monte Carlo run with its evidence estimate.
+    """
+    @property
+    def particles(self) -> builtins.list[builtins.list[builtins.float]]: ...
+    @property
+    def log_weights(self) -> builtins.list[builtins.float]:
+        r"""
+        Normalised log-weight of each particle.
+        """
+    @property
+    def log_likelihoods(self) -> builtins.list[builtins.float]: ...
+    @property
+    def mean(self) -> builtins.list[builtins.float]: ...
+    @property
+    def draws(self) -> builtins.int: ...
+    @property
+    def log_evidence(self) -> builtins.float: ...
+    @property
+    def schedule(self) -> builtins.list[builtins.float]:
+        r"""
+        Inverse temperatures visited by the run, from 0 (prior) to 1 (posterior).
+        """
+    @property
+    def acceptance_rates(self) -> builtins.list[builtins.float]:
+        r"""
+        Acceptance rate of the rejuvenation moves at each intermediate stage.
+        """
+    @property
+    def time(self) -> datetime.timedelta: ...
+    def to_samples(self) -> Samples:
+        r"""
+        Equal-weight view of the particles as a single chain.
+        """
     def __repr__(self) -> builtins.str: ...
 
 @typing.final
@@ -80,4 +277,101 @@ class Samples:
     def draws(self) -> builtins.int: ...
     @property
     def time(self) -> datetime.timedelta: ...
+    @property
+    def acceptance_rates(self) -> builtins.list[builtins.float] | None:
+        r"""
+        Mean acceptance rate of each chain, or None when the sampler does not record one.
+        """
+    def rhat(self) -> builtins.list[builtins.float]:
+        r"""
+        Rank-normalised split R-hat of each parameter.
+        """
+    def ess_bulk(self) -> builtins.list[builtins.float]:
+        r"""
+        Bulk effective sample size of each parameter.
+        """
+    def ess_tail(self) -> builtins.list[builtins.float]:
+        r"""
+        Tail effective sample size of each parameter (5% and 95% quantiles).
+        """
+    def mcse(self) -> builtins.list[builtins.float]:
+        r"""
+        Monte Carlo standard error of each parameter's posterior mean.
+        """
+    def autocorrelation_time(self) -> builtins.list[builtins.float]:
+        r"""
+        Integrated autocorrelation time of each parameter, in iterations.
+        """
+    def quantiles(
+        self, probabilities: typing.Sequence[builtins.float] = [0.05, 0.5, 0.95]
+    ) -> builtins.list[builtins.list[builtins.float]]:
+        r"""
+        Quantiles of the pooled draws with shape ``(len(probabilities), n_parameters)``.
+        """
+    def covariance(self) -> builtins.list[builtins.list[builtins.float]]:
+        r"""
+        Sample covariance of the pooled draws.
+        """
+    def burn_in(self, n: builtins.int) -> Samples:
+        r"""
+        New samples without the first `n` draws of every chain.
+        """
+    def thin(self, k: builtins.int) -> Samples:
+        r"""
+        New samples keeping every `k`-th draw of every chain.
+        """
+    def __repr__(self) -> builtins.str: ...
+
+@typing.final
+class SequentialMonteCarlo:
+    r"""
+    Adaptive-tempering Sequential Monte Carlo sampler.
+    """
+    def __new__(cls) -> SequentialMonteCarlo: ...
+    def with_particles(self, particles: builtins.int) -> SequentialMonteCarlo: ...
+    def with_target_ess(self, fraction: builtins.float) -> SequentialMonteCarlo:
+        r"""
+        Fraction of the population kept as effective sample size by each tempering step.
+        """
+    def with_mcmc_steps(self, steps: builtins.int) -> SequentialMonteCarlo:
+        r"""
+        Number of Metropolis moves applied to every particle after each resampling.
+        """
+    def with_seed(self, seed: builtins.int) -> SequentialMonteCarlo: ...
+    def run(self, problem: Problem) -> ParticleSamples:
+        r"""
+        Move particles from the prior to the posterior; raises if a parameter has neither
+        a prior nor bounds or the problem's costs are not a negative log-likelihood.
+        """
+
+@typing.final
+class TemperedSamples:
+    r"""
+    Parallel-tempering results: the cold chain plus ladder and evidence statistics.
+    """
+    @property
+    def samples(self) -> Samples:
+        r"""
+        Draws of the cold (T = 1) replica.
+        """
+    @property
+    def temperatures(self) -> builtins.list[builtins.float]:
+        r"""
+        Temperature ladder after warmup, coldest first.
+        """
+    @property
+    def swap_acceptance_rates(self) -> builtins.list[builtins.float]:
+        r"""
+        Fraction of accepted swaps between each pair of neighbouring temperatures.
+        """
+    @property
+    def mean_log_likelihoods(self) -> builtins.list[builtins.float]:
+        r"""
+        Mean log-likelihood of the recorded draws at each temperature.
+        """
+    @property
+    def log_evidence(self) -> builtins.float:
+        r"""
+        Thermodynamic-integration estimate of the log-evidence.
+        """
     def __repr__(self) -> builtins.str: ...
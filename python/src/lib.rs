@@ -1,26 +1,31 @@
 use nalgebra::DMatrix;
-use numpy::{PyArray1, PyReadonlyArray1, PyReadonlyArrayDyn};
+use numpy::{PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArrayDyn};
 use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyType};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "stubgen")]
 use std::env;
 
-#[cfg(feature = "stubgen")]
-use std::path::PathBuf;
-
 use chronopt_core::cost::{CostMetric, GaussianNll, RootMeanSquaredError, SumSquaredError};
 use chronopt_core::prelude::*;
 use chronopt_core::problem::{
     DiffsolBackend, DiffsolProblemBuilder, ScalarProblemBuilder, VectorProblemBuilder,
 };
 use chronopt_core::sampler::{
-    DynamicNestedSampler as CoreDynamicNestedSampler, MetropolisHastings as CoreMetropolisHastings,
-    NestedSamples as CoreNestedSamples, Samples as CoreSamples,
+    Bounding, DynamicNestedSampler as CoreDynamicNestedSampler,
+    EnsembleSampler as CoreEnsembleSampler, HamiltonianMonteCarlo as CoreHamiltonianMonteCarlo,
+    MassMatrix, MetropolisHastings as CoreMetropolisHastings, NestedProposal,
+    NestedSamples as CoreNestedSamples, ParallelTempering as CoreParallelTempering,
+    ParticleSamples as CoreParticleSamples, Samples as CoreSamples,
+    SequentialMonteCarlo as CoreSequentialMonteCarlo, TemperedSamples as CoreTemperedSamples,
+    Trajectory,
 };
 
 #[cfg(feature = "stubgen")]
@@ -32,7 +37,10 @@ use pyo3_stub_gen::{
 type ParameterSpecEntry = (String, f64, Option<(f64, f64)>);
 
 // Helper function to convert numpy arrays to DMatrix
-fn convert_array_to_dmatrix(data: &PyReadonlyArrayDyn<'_, f64>) -> PyResult<DMatrix<f64>> {
+fn convert_array_to_dmatrix<T>(data: &PyReadonlyArrayDyn<'_, T>) -> PyResult<DMatrix<T>>
+where
+    T: numpy::Element + nalgebra::Scalar,
+{
     let array = data.as_array();
     let array_2d = array
         .into_dimensionality::<numpy::ndarray::Ix2>()
@@ -44,7 +52,7 @@ fn convert_array_to_dmatrix(data: &PyReadonlyArrayDyn<'_, f64>) -> PyResult<DMat
     // nalgebra uses column-major storage
     for col in 0..ncols {
         for row in 0..nrows {
-            column_major.push(array_2d[[row, col]]);
+            column_major.push(array_2d[[row, col]].clone());
         }
     }
 
@@ -52,7 +60,15 @@ fn convert_array_to_dmatrix(data: &PyReadonlyArrayDyn<'_, f64>) -> PyResult<DMat
 }
 
 #[cfg(feature = "stubgen")]
-pyo3_stub_gen::impl_stub_type!(Optimiser = PyNelderMead | PyCMAES | PyAdam);
+pyo3_stub_gen::impl_stub_type!(
+    Optimiser = PyNelderMead
+        | PyCMAES
+        | PyAdam
+        | PyLBFGSB
+        | PyLevenbergMarquardt
+        | PyDifferentialEvolution
+        | PyPSO
+);
 
 // ============================================================================
 // Optimiser Enum for Polymorphic Types
@@ -63,6 +79,10 @@ enum Optimiser {
     NelderMead(NelderMead),
     Cmaes(CMAES),
     Adam(Adam),
+    Lbfgsb(LBFGSB),
+    LevenbergMarquardt(LevenbergMarquardt),
+    DifferentialEvolution(DifferentialEvolution),
+    Pso(PSO),
 }
 
 // ============================================================================
@@ -99,6 +119,62 @@ impl PySamples {
         self.inner.time()
     }
 
+    /// Mean acceptance rate of each chain, or None when the sampler does not record one.
+    #[getter]
+    fn acceptance_rates(&self) -> Option<Vec<f64>> {
+        self.inner.acceptance_rates().map(<[f64]>::to_vec)
+    }
+
+    /// Rank-normalised split R-hat of each parameter.
+    fn rhat(&self) -> Vec<f64> {
+        self.inner.rhat()
+    }
+
+    /// Bulk effective sample size of each parameter.
+    fn ess_bulk(&self) -> Vec<f64> {
+        self.inner.ess_bulk()
+    }
+
+    /// Tail effective sample size of each parameter (5% and 95% quantiles).
+    fn ess_tail(&self) -> Vec<f64> {
+        self.inner.ess_tail()
+    }
+
+    /// Monte Carlo standard error of each parameter's posterior mean.
+    fn mcse(&self) -> Vec<f64> {
+        self.inner.mcse()
+    }
+
+    /// Integrated autocorrelation time of each parameter, in iterations.
+    fn autocorrelation_time(&self) -> Vec<f64> {
+        self.inner.autocorrelation_time()
+    }
+
+    /// Quantiles of the pooled draws with shape ``(len(probabilities), n_parameters)``.
+    #[pyo3(signature = (probabilities=vec![0.05, 0.5, 0.95]))]
+    fn quantiles(&self, probabilities: Vec<f64>) -> Vec<Vec<f64>> {
+        self.inner.quantiles(&probabilities)
+    }
+
+    /// Sample covariance of the pooled draws.
+    fn covariance(&self) -> Vec<Vec<f64>> {
+        self.inner.covariance()
+    }
+
+    /// New samples without the first `n` draws of every chain.
+    fn burn_in(&self, n: usize) -> PySamples {
+        PySamples {
+            inner: self.inner.burn_in(n),
+        }
+    }
+
+    /// New samples keeping every `k`-th draw of every chain.
+    fn thin(&self, k: usize) -> PySamples {
+        PySamples {
+            inner: self.inner.thin(k),
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Samples(draws={}, mean_x={:?}, chains={}, time={:?})",
@@ -151,27 +227,67 @@ impl PyNestedSamples {
         self.inner.log_evidence()
     }
 
+    #[getter]
+    fn log_evidence_error(&self) -> f64 {
+        self.inner.log_evidence_error()
+    }
+
+    /// Log-evidence error from replaying the run with randomly drawn shrinkage factors.
+    #[pyo3(signature = (simulations=200, seed=0))]
+    fn simulated_log_evidence_error(&self, simulations: usize, seed: u64) -> f64 {
+        self.inner.simulated_log_evidence_error(simulations, seed)
+    }
+
     #[getter]
     fn information(&self) -> f64 {
         self.inner.information()
     }
 
+    #[getter]
+    fn effective_sample_size(&self) -> f64 {
+        self.inner.effective_sample_size()
+    }
+
     #[getter]
     fn time(&self) -> Duration {
         self.inner.time()
     }
 
+    #[getter]
+    fn proposals(&self) -> usize {
+        self.inner.proposals()
+    }
+
+    #[getter]
+    fn accepted_proposals(&self) -> usize {
+        self.inner.accepted_proposals()
+    }
+
+    #[getter]
+    fn efficiency(&self) -> f64 {
+        self.inner.efficiency()
+    }
+
     fn to_samples(&self) -> PySamples {
         PySamples {
             inner: self.inner.to_samples(),
         }
     }
 
+    /// Equal-weight posterior draws resampled from the importance weights.
+    #[pyo3(signature = (seed=0))]
+    fn resample(&self, seed: u64) -> PySamples {
+        PySamples {
+            inner: self.inner.resample(seed),
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
-            "NestedSamples(draws={}, log_evidence={:.3}, information={:.3})",
+            "NestedSamples(draws={}, log_evidence={:.3} ± {:.3}, information={:.3})",
             self.inner.draws(),
             self.inner.log_evidence(),
+            self.inner.log_evidence_error(),
             self.inner.information()
         )
     }
@@ -220,14 +336,50 @@ impl PyMetropolisHastings {
         slf
     }
 
+    fn with_proposal_covariance(
+        mut slf: PyRefMut<'_, Self>,
+        covariance: Vec<Vec<f64>>,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_proposal_covariance(covariance);
+        slf
+    }
+
+    fn with_adaptation(mut slf: PyRefMut<'_, Self>, warmup: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_adaptation(warmup);
+        slf
+    }
+
+    fn with_target_acceptance(mut slf: PyRefMut<'_, Self>, target: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_target_acceptance(target);
+        slf
+    }
+
     fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
         slf
     }
 
-    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PySamples {
-        let samples = self.inner.run(&problem.inner, initial);
-        PySamples { inner: samples }
+    fn with_checkpoint(
+        mut slf: PyRefMut<'_, Self>,
+        path: PathBuf,
+        every: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_checkpoint(path, every);
+        slf
+    }
+
+    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyResult<PySamples> {
+        self.inner
+            .run(&problem.inner, initial)
+            .map(|inner| PySamples { inner })
+            .map_err(PyValueError::new_err)
+    }
+
+    fn resume_from(&self, problem: &PyProblem, path: PathBuf) -> PyResult<PySamples> {
+        self.inner
+            .resume_from(&problem.inner, path)
+            .map(|inner| PySamples { inner })
+            .map_err(PyValueError::new_err)
     }
 }
 
@@ -270,776 +422,2373 @@ impl PyDynamicNestedSampler {
         slf
     }
 
+    /// Bound new live points with 'perturbation', 'ellipsoid' or 'multi_ellipsoid' regions.
+    fn with_bounding(
+        mut slf: PyRefMut<'_, Self>,
+        bounding: String,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let bounding = match bounding.to_ascii_lowercase().as_str() {
+            "perturbation" => Bounding::Perturbation,
+            "ellipsoid" => Bounding::Ellipsoid,
+            "multi_ellipsoid" => Bounding::MultiEllipsoid,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown bounding '{}'. Expected perturbation, ellipsoid or multi_ellipsoid",
+                    other
+                )))
+            }
+        };
+        slf.inner = std::mem::take(&mut slf.inner).with_bounding(bounding);
+        Ok(slf)
+    }
+
+    /// Generate live points by constrained random walks of `steps` moves.
+    fn with_random_walk(mut slf: PyRefMut<'_, Self>, steps: usize) -> PyRefMut<'_, Self> {
+        slf.inner =
+            std::mem::take(&mut slf.inner).with_proposal(NestedProposal::RandomWalk { steps });
+        slf
+    }
+
+    /// Generate live points by `slices` rounds of slice sampling along principal directions.
+    fn with_slice_sampling(mut slf: PyRefMut<'_, Self>, slices: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_proposal(NestedProposal::Slice { slices });
+        slf
+    }
+
     fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
         slf
     }
 
+    /// Sample the posterior and its evidence; raises if a parameter has neither a prior
+    /// nor bounds or the problem's costs are not a negative log-likelihood.
     #[pyo3(signature = (problem, initial=None))]
-    fn run(&self, problem: &PyProblem, initial: Option<Vec<f64>>) -> PyNestedSamples {
+    fn run(&self, problem: &PyProblem, initial: Option<Vec<f64>>) -> PyResult<PyNestedSamples> {
         let initial = initial.unwrap_or_else(|| problem.inner.default_parameters());
-        let nested = self.inner.run_nested(&problem.inner, initial);
-        PyNestedSamples { inner: nested }
+        self.inner
+            .run_nested(&problem.inner, initial)
+            .map(|inner| PyNestedSamples { inner })
+            .map_err(PyValueError::new_err)
     }
-}
-
-#[cfg(feature = "stubgen")]
-#[allow(dead_code)]
-fn optimiser_type_info() -> TypeInfo {
-    TypeInfo::unqualified("chronopt._chronopt.NelderMead")
-        | TypeInfo::unqualified("chronopt._chronopt.CMAES")
-        | TypeInfo::unqualified("chronopt._chronopt.Adam")
-}
 
-impl FromPyObject<'_, '_> for Optimiser {
-    type Error = PyErr;
+    fn with_checkpoint(
+        mut slf: PyRefMut<'_, Self>,
+        path: PathBuf,
+        every: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_checkpoint(path, every);
+        slf
+    }
 
-    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
-        if let Ok(nm) = obj.extract::<PyRef<PyNelderMead>>() {
-            Ok(Optimiser::NelderMead((*nm).inner.clone()))
-        } else if let Ok(cma) = obj.extract::<PyRef<PyCMAES>>() {
-            Ok(Optimiser::Cmaes((*cma).inner.clone()))
-        } else if let Ok(adam) = obj.extract::<PyRef<PyAdam>>() {
-            Ok(Optimiser::Adam((*adam).inner.clone()))
-        } else {
-            Err(PyTypeError::new_err(
-                "Optimiser must be an instance of NelderMead, CMAES, or Adam",
-            ))
-        }
+    fn resume_from(&self, problem: &PyProblem, path: PathBuf) -> PyResult<PyNestedSamples> {
+        self.inner
+            .resume_from(&problem.inner, path)
+            .map(|inner| PyNestedSamples { inner })
+            .map_err(PyValueError::new_err)
     }
 }
 
-// ============================================================================
-// Cost Metrics
-// ============================================================================
-
+/// Hamiltonian Monte Carlo sampler driven by problem gradients, NUTS by default.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "CostMetric")]
+#[pyclass(module = "chronopt.sampler", name = "HamiltonianMonteCarlo")]
 #[derive(Clone)]
-pub struct PyCostMetric {
-    inner: Arc<dyn CostMetric>,
-    name: &'static str,
+pub struct PyHamiltonianMonteCarlo {
+    inner: CoreHamiltonianMonteCarlo,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyCostMetric {
-    /// Name of the cost metric.
-    #[getter]
-    fn name(&self) -> &'static str {
-        self.name
-    }
-
-    fn __repr__(&self) -> String {
-        format!("CostMetric(name='{}')", self.name)
-    }
-}
-
-impl PyCostMetric {
-    fn from_metric<M>(metric: M, name: &'static str) -> Self
-    where
-        M: CostMetric + 'static,
-    {
+impl PyHamiltonianMonteCarlo {
+    #[new]
+    fn new() -> Self {
         Self {
-            inner: Arc::new(metric),
-            name,
+            inner: CoreHamiltonianMonteCarlo::new(),
         }
     }
 
-    fn metric_arc(&self) -> Arc<dyn CostMetric> {
-        Arc::clone(&self.inner)
+    fn with_num_chains(mut slf: PyRefMut<'_, Self>, num_chains: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_num_chains(num_chains);
+        slf
     }
-}
-
-#[cfg_attr(feature = "stubgen", gen_stub_pyfunction)]
-#[pyfunction(name = "SSE")]
-#[pyo3(signature = (weight = 1.0))]
-fn sse(weight: f64) -> PyCostMetric {
-    PyCostMetric::from_metric(SumSquaredError::new(Some(weight)), "sse")
-}
-
-#[cfg_attr(feature = "stubgen", gen_stub_pyfunction)]
-#[pyfunction(name = "RMSE")]
-#[pyo3(signature = (weight = 1.0))]
-fn rmse(weight: f64) -> PyCostMetric {
-    PyCostMetric::from_metric(RootMeanSquaredError::new(Some(weight)), "rmse")
-}
 
-#[cfg_attr(feature = "stubgen", gen_stub_pyfunction)]
-#[pyfunction(name = "GaussianNLL")]
-#[pyo3(signature = (variance, weight = 1.0))]
-fn gaussian_nll(variance: f64, weight: f64) -> PyResult<PyCostMetric> {
-    if !variance.is_finite() || variance <= 0.0 {
-        return Err(PyValueError::new_err(
-            "variance must be positive and finite",
-        ));
+    fn with_iterations(mut slf: PyRefMut<'_, Self>, iterations: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_iterations(iterations);
+        slf
     }
-    Ok(PyCostMetric::from_metric(
-        GaussianNll::new(Some(weight), variance),
-        "gaussian_nll",
-    ))
-}
-
-// ============================================================================
-// Python Objective Function Wrapper
-// ============================================================================
 
-struct PyObjectiveFn {
-    callable: Py<PyAny>,
-}
+    fn with_warmup(mut slf: PyRefMut<'_, Self>, warmup: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_warmup(warmup);
+        slf
+    }
 
-impl PyObjectiveFn {
-    fn new(callable: Py<PyAny>) -> Self {
-        Self { callable }
+    fn with_step_size(mut slf: PyRefMut<'_, Self>, step_size: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_step_size(step_size);
+        slf
     }
 
-    fn call(&self, x: &[f64]) -> PyResult<f64> {
-        Python::attach(|py| {
-            let callable = self.callable.bind(py);
-            let input = PyArray1::from_slice(py, x);
-            let result = callable.call1((input,))?;
+    fn with_target_acceptance(mut slf: PyRefMut<'_, Self>, target: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_target_acceptance(target);
+        slf
+    }
 
-            if let Ok(output) = result.extract::<PyReadonlyArray1<f64>>() {
-                let array = output.as_array();
-                return match array.len() {
-                    1 => Ok(array[0]),
-                    n => Err(PyValueError::new_err(format!(
-                        "Objective array must contain exactly one element, got {}",
-                        n
-                    ))),
-                };
-            }
+    /// Use classic HMC with a fixed number of leapfrog steps per transition.
+    fn with_leapfrog_steps(mut slf: PyRefMut<'_, Self>, steps: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_trajectory(Trajectory::Static { steps });
+        slf
+    }
 
-            if let Ok(values) = result.extract::<Vec<f64>>() {
-                return match values.len() {
-                    1 => Ok(values[0]),
-                    n => Err(PyValueError::new_err(format!(
-                        "Objective sequence must contain exactly one element, got {}",
-                        n
-                    ))),
-                };
-            }
+    /// Use the No-U-Turn sampler with trajectories of at most `2**max_depth` steps.
+    fn with_max_tree_depth(mut slf: PyRefMut<'_, Self>, max_depth: usize) -> PyRefMut<'_, Self> {
+        slf.inner =
+            std::mem::take(&mut slf.inner).with_trajectory(Trajectory::NoUTurn { max_depth });
+        slf
+    }
 
-            if let Ok(value) = result.extract::<f64>() {
-                return Ok(value);
+    /// Adapt a 'unit', 'diagonal' or 'dense' mass matrix during warmup.
+    fn with_mass_matrix(
+        mut slf: PyRefMut<'_, Self>,
+        mass_matrix: String,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let mass_matrix = match mass_matrix.to_ascii_lowercase().as_str() {
+            "unit" => MassMatrix::Unit,
+            "diagonal" => MassMatrix::Diagonal,
+            "dense" => MassMatrix::Dense,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown mass matrix '{}'. Expected 'unit', 'diagonal' or 'dense'",
+                    other
+                )))
             }
+        };
+        slf.inner = std::mem::take(&mut slf.inner).with_mass_matrix(mass_matrix);
+        Ok(slf)
+    }
 
-            let ty_name = result
-                .get_type()
-                .name()
-                .map(|n| n.to_string())
-                .unwrap_or_else(|_| "unknown".to_string());
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
+        slf
+    }
 
-            Err(PyTypeError::new_err(format!(
-                "Objective callable must return a float, numpy array, or single-element sequence; got {}",
-                ty_name
-            )))
-        })
+    /// Draw post-warmup samples; raises if the problem provides no gradient or its
+    /// costs are not a negative log-likelihood.
+    #[pyo3(signature = (problem, initial=None))]
+    fn run(&self, problem: &PyProblem, initial: Option<Vec<f64>>) -> PyResult<PySamples> {
+        let initial = initial.unwrap_or_else(|| problem.inner.default_parameters());
+        self.inner
+            .run(&problem.inner, initial)
+            .map(|inner| PySamples { inner })
+            .map_err(PyValueError::new_err)
     }
 }
 
-struct PyGradientFn {
-    callable: Py<PyAny>,
-}
-
-impl PyGradientFn {
-    fn new(callable: Py<PyAny>) -> Self {
-        Self { callable }
-    }
-
-    fn call(&self, x: &[f64]) -> PyResult<Vec<f64>> {
-        Python::attach(|py| {
-            let callable = self.callable.bind(py);
-            let input = PyArray1::from_slice(py, x);
-            let result = callable.call1((input,))?;
-
-            if let Ok(output) = result.extract::<PyReadonlyArray1<f64>>() {
-                return Ok(output.as_array().to_vec());
-            }
-
-            result.extract::<Vec<f64>>()
-        })
-    }
-}
-
-// ============================================================================
-// Builder
-// ============================================================================
-
-/// High-level builder for optimisation `Problem` instances exposed to Python.
+/// Affine-invariant ensemble sampler (emcee-style stretch and differential-evolution moves).
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "ScalarBuilder")]
-pub struct PyScalarBuilder {
-    inner: ScalarProblemBuilder,
-    py_callable: Option<Arc<PyObjectiveFn>>,
-    py_gradient: Option<Arc<PyGradientFn>>,
-    default_optimiser: Option<Optimiser>,
+#[pyclass(module = "chronopt.sampler", name = "EnsembleSampler")]
+#[derive(Clone)]
+pub struct PyEnsembleSampler {
+    inner: CoreEnsembleSampler,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyScalarBuilder {
-    /// Create an empty builder with no objective, parameters, or default optimiser.
+impl PyEnsembleSampler {
     #[new]
     fn new() -> Self {
         Self {
-            inner: ScalarProblemBuilder::new(),
-            py_callable: None,
-            py_gradient: None,
-            default_optimiser: None,
+            inner: CoreEnsembleSampler::new(),
         }
     }
 
-    /// Configure the default optimiser used when `Problem.optimize` omits one.
-    fn with_optimiser(mut slf: PyRefMut<'_, Self>, optimiser: Optimiser) -> PyRefMut<'_, Self> {
-        match &optimiser {
-            Optimiser::NelderMead(nm) => {
-                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(nm.clone());
-            }
-            Optimiser::Cmaes(cma) => {
-                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(cma.clone());
-            }
-            Optimiser::Adam(adam) => {
-                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(adam.clone());
-            }
-        }
-
-        slf.default_optimiser = Some(optimiser);
+    fn with_walkers(mut slf: PyRefMut<'_, Self>, walkers: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_walkers(walkers);
         slf
     }
 
-    /// Attach the objective function callable executed during optimisation.
-    fn with_callable(mut slf: PyRefMut<'_, Self>, obj: Py<PyAny>) -> PyResult<PyRefMut<'_, Self>> {
-        Python::attach(|py| {
-            if !obj.bind(py).is_callable() {
-                return Err(PyTypeError::new_err("Object must be callable"));
-            }
-            Ok(())
-        })?;
-
-        let py_fn = Arc::new(PyObjectiveFn::new(obj));
-        let objective = Arc::clone(&py_fn);
-
-        slf.inner = std::mem::take(&mut slf.inner)
-            .with_objective(move |x: &[f64]| objective.call(x).unwrap_or(f64::INFINITY));
-        slf.py_callable = Some(py_fn);
-        Ok(slf)
+    fn with_iterations(mut slf: PyRefMut<'_, Self>, iterations: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_iterations(iterations);
+        slf
     }
 
-    /// Attach the gradient callable returning derivatives of the objective.
-    fn with_gradient(mut slf: PyRefMut<'_, Self>, obj: Py<PyAny>) -> PyResult<PyRefMut<'_, Self>> {
-        Python::attach(|py| {
-            if !obj.bind(py).is_callable() {
-                return Err(PyTypeError::new_err("Object must be callable"));
-            }
-            Ok(())
-        })?;
-
-        let py_grad = Arc::new(PyGradientFn::new(obj));
-        let grad = Arc::clone(&py_grad);
-
-        slf.inner = std::mem::take(&mut slf.inner).with_gradient(move |x: &[f64]| {
-            grad.call(x).unwrap_or_else(|_| vec![f64::NAN; x.len()])
-        });
-        slf.py_gradient = Some(py_grad);
-        Ok(slf)
+    fn with_stretch_scale(mut slf: PyRefMut<'_, Self>, scale: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_stretch_scale(scale);
+        slf
     }
 
-    /// Register a named optimisation variable in the order it appears in vectors.
-    #[pyo3(signature = (name, initial_value, bounds=None))]
-    fn with_parameter(
+    /// Probability that an update uses a differential-evolution move instead of a stretch.
+    fn with_differential_evolution(
         mut slf: PyRefMut<'_, Self>,
-        name: String,
-        initial_value: f64,
-        bounds: Option<(f64, f64)>,
+        probability: f64,
     ) -> PyRefMut<'_, Self> {
-        let spec = ParameterSpec::new(name.clone(), initial_value, bounds);
-        slf.inner = std::mem::take(&mut slf.inner).with_parameter(spec);
+        slf.inner = std::mem::take(&mut slf.inner).with_differential_evolution(probability);
         slf
     }
 
-    /// Finalize the builder into an executable `Problem`.
-    fn build(&mut self) -> PyResult<PyProblem> {
-        let problem = self.inner.build().map_err(PyValueError::new_err)?;
-        Ok(PyProblem {
-            inner: problem,
-            default_optimiser: self.default_optimiser.clone(),
-        })
+    fn with_initial_spread(mut slf: PyRefMut<'_, Self>, spread: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_initial_spread(spread);
+        slf
     }
-}
 
-// ============================================================================
-// Diffsol Builder
-// ============================================================================
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
+        slf
+    }
 
-/// Differential equation solver builder.
+    /// Draw one chain per walker; raises if there are too few walkers or the problem's
+    /// costs are not a negative log-likelihood.
+    #[pyo3(signature = (problem, initial=None))]
+    fn run(&self, problem: &PyProblem, initial: Option<Vec<f64>>) -> PyResult<PySamples> {
+        let initial = initial.unwrap_or_else(|| problem.inner.default_parameters());
+        self.inner
+            .run(&problem.inner, initial)
+            .map(|inner| PySamples { inner })
+            .map_err(PyValueError::new_err)
+    }
+}
+
+/// Parallel-tempering results: the cold chain plus ladder and evidence statistics.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "DiffsolBuilder")]
-pub struct PyDiffsolBuilder {
-    inner: DiffsolProblemBuilder,
-    default_optimiser: Option<Optimiser>,
+#[pyclass(module = "chronopt.sampler", name = "TemperedSamples")]
+#[derive(Clone)]
+pub struct PyTemperedSamples {
+    inner: CoreTemperedSamples,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyDiffsolBuilder {
-    /// Create an empty differential solver builder.
-    #[new]
-    fn new() -> Self {
-        Self {
-            inner: DiffsolProblemBuilder::new(),
-            default_optimiser: None,
+impl PyTemperedSamples {
+    /// Draws of the cold (T = 1) replica.
+    #[getter]
+    fn samples(&self) -> PySamples {
+        PySamples {
+            inner: self.inner.samples().clone(),
         }
     }
 
-    fn __copy__(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
-            default_optimiser: self.default_optimiser.clone(),
-        }
+    /// Temperature ladder after warmup, coldest first.
+    #[getter]
+    fn temperatures(&self) -> Vec<f64> {
+        self.inner.temperatures().to_vec()
     }
 
-    fn __deepcopy__(&self, _memo: &Bound<'_, PyDict>) -> Self {
-        self.__copy__()
+    /// Fraction of accepted swaps between each pair of neighbouring temperatures.
+    #[getter]
+    fn swap_acceptance_rates(&self) -> Vec<f64> {
+        self.inner.swap_acceptance_rates().to_vec()
     }
 
-    /// Register the DiffSL program describing the system dynamics.
-    fn with_diffsl(mut slf: PyRefMut<'_, Self>, dsl: String) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_diffsl(dsl);
-        slf
+    /// Mean log-likelihood of the recorded draws at each temperature.
+    #[getter]
+    fn mean_log_likelihoods(&self) -> Vec<f64> {
+        self.inner.mean_log_likelihoods().to_vec()
     }
 
-    /// Remove any registered DiffSL program.
-    fn remove_diffsl(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).remove_diffsl();
-        slf
+    /// Thermodynamic-integration estimate of the log-evidence.
+    #[getter]
+    fn log_evidence(&self) -> f64 {
+        self.inner.log_evidence()
     }
 
-    /// Attach observed data used to fit the differential equation.
-    ///
-    /// The first column must contain the time samples (t_span) and the remaining
-    /// columns the observed trajectories.
-    fn with_data<'py>(
-        mut slf: PyRefMut<'py, Self>,
-        data: PyReadonlyArrayDyn<'py, f64>,
-    ) -> PyResult<PyRefMut<'py, Self>> {
-        let data_matrix = convert_array_to_dmatrix(&data)?;
-        if data_matrix.ncols() < 2 {
-            return Err(PyValueError::new_err(
-                "Data must include at least two columns with t_span in the first column",
-            ));
-        }
-        slf.inner = std::mem::take(&mut slf.inner).with_data(data_matrix);
-        Ok(slf)
+    fn __repr__(&self) -> String {
+        format!(
+            "TemperedSamples(draws={}, temperatures={}, log_evidence={:.3})",
+            self.inner.samples().draws(),
+            self.inner.temperatures().len(),
+            self.inner.log_evidence()
+        )
     }
+}
 
-    /// Remove any previously attached data along with its time span.
-    fn remove_data(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).remove_data();
-        slf
+/// Parallel-tempering (replica exchange) sampler with an adaptive temperature ladder.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(module = "chronopt.sampler", name = "ParallelTempering")]
+#[derive(Clone)]
+pub struct PyParallelTempering {
+    inner: CoreParallelTempering,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyParallelTempering {
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: CoreParallelTempering::new(),
+        }
     }
 
-    /// Choose whether to use dense or sparse diffusion solvers.
-    fn with_backend(mut slf: PyRefMut<'_, Self>, backend: String) -> PyResult<PyRefMut<'_, Self>> {
-        let backend_enum = match backend.as_str() {
-            "dense" => DiffsolBackend::Dense,
-            "sparse" => DiffsolBackend::Sparse,
-            other => {
-                return Err(PyValueError::new_err(format!(
-                    "Unknown backend '{}'. Expected 'dense' or 'sparse'",
-                    other
-                )))
-            }
-        };
-        slf.inner = std::mem::take(&mut slf.inner).with_backend(backend_enum);
-        Ok(slf)
+    /// Explicit temperature ladder; it must start at 1 and increase strictly.
+    fn with_temperatures(
+        mut slf: PyRefMut<'_, Self>,
+        temperatures: Vec<f64>,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_temperatures(temperatures);
+        slf
     }
 
-    /// Opt into parallel proposal generation when supported by the backend.
-    #[pyo3(signature = (parallel=None))]
-    fn with_parallel(mut slf: PyRefMut<'_, Self>, parallel: Option<bool>) -> PyRefMut<'_, Self> {
-        let parallel = parallel.unwrap_or(true);
-        slf.inner = std::mem::take(&mut slf.inner).with_parallel(parallel);
+    fn with_num_temperatures(
+        mut slf: PyRefMut<'_, Self>,
+        num_temperatures: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_num_temperatures(num_temperatures);
         slf
     }
 
-    fn with_config(
+    fn with_max_temperature(
         mut slf: PyRefMut<'_, Self>,
-        config: HashMap<String, f64>,
+        max_temperature: f64,
     ) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_config(config);
+        slf.inner = std::mem::take(&mut slf.inner).with_max_temperature(max_temperature);
         slf
     }
 
-    /// Adjust the relative integration tolerance.
-    fn with_rtol(mut slf: PyRefMut<'_, Self>, rtol: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_rtol(rtol);
+    fn with_iterations(mut slf: PyRefMut<'_, Self>, iterations: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_iterations(iterations);
         slf
     }
 
-    /// Adjust the absolute integration tolerance.
-    fn with_atol(mut slf: PyRefMut<'_, Self>, atol: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_atol(atol);
+    fn with_warmup(mut slf: PyRefMut<'_, Self>, warmup: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_warmup(warmup);
         slf
     }
 
-    /// Register a named optimisation variable in the order it appears in vectors.
-    #[pyo3(signature = (name, initial_value, bounds=None))]
-    fn with_parameter(
-        mut slf: PyRefMut<'_, Self>,
-        name: String,
-        initial_value: f64,
-        bounds: Option<(f64, f64)>,
-    ) -> PyRefMut<'_, Self> {
-        let spec = ParameterSpec::new(name.clone(), initial_value, bounds);
-        slf.inner = std::mem::take(&mut slf.inner).with_parameter(spec);
+    fn with_step_size(mut slf: PyRefMut<'_, Self>, step_size: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_step_size(step_size);
         slf
     }
 
-    /// Remove previously provided parameter defaults.
-    fn clear_parameters(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
-        slf.inner.clear_parameters();
+    fn with_target_acceptance(mut slf: PyRefMut<'_, Self>, target: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_target_acceptance(target);
         slf
     }
 
-    /// Select the error metric used to compare simulated and observed data.
-    fn with_cost<'py>(
-        mut slf: PyRefMut<'py, Self>,
-        cost: PyRef<'py, PyCostMetric>,
-    ) -> PyResult<PyRefMut<'py, Self>> {
-        let metric = cost.metric_arc();
-        slf.inner = std::mem::take(&mut slf.inner).with_cost_metric_arc(metric);
-        Ok(slf)
+    /// Whether warmup adapts the spacing of the interior temperatures.
+    fn with_ladder_adaptation(mut slf: PyRefMut<'_, Self>, adapt: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_ladder_adaptation(adapt);
+        slf
     }
 
-    /// Reset the cost metric to the default sum of squared errors.
-    fn remove_cost(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).remove_cost();
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
         slf
     }
 
-    /// Configure the default optimiser used when `Problem.optimize` omits one.
-    fn with_optimiser(mut slf: PyRefMut<'_, Self>, optimiser: Optimiser) -> PyRefMut<'_, Self> {
-        let mut inner = std::mem::take(&mut slf.inner);
-        match &optimiser {
-            Optimiser::NelderMead(nm) => {
-                inner = inner.with_optimiser(nm.clone());
-            }
+    /// Sample the cold chain; raises if the ladder is invalid, the starting point has no
+    /// finite log-posterior density or the problem's costs are not a negative
+    /// log-likelihood.
+    #[pyo3(signature = (problem, initial=None))]
+    fn run(&self, problem: &PyProblem, initial: Option<Vec<f64>>) -> PyResult<PyTemperedSamples> {
+        let initial = initial.unwrap_or_else(|| problem.inner.default_parameters());
+        self.inner
+            .run_tempered(&problem.inner, initial)
+            .map(|inner| PyTemperedSamples { inner })
+            .map_err(PyValueError::new_err)
+    }
+}
+
+/// Weighted particle population of a Sequential Monte Carlo run with its evidence estimate.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(module = "chronopt.sampler", name = "ParticleSamples")]
+#[derive(Clone)]
+pub struct PyParticleSamples {
+    inner: CoreParticleSamples,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyParticleSamples {
+    #[getter]
+    fn particles(&self) -> Vec<Vec<f64>> {
+        self.inner.particles().to_vec()
+    }
+
+    /// Normalised log-weight of each particle.
+    #[getter]
+    fn log_weights(&self) -> Vec<f64> {
+        self.inner.log_weights().to_vec()
+    }
+
+    #[getter]
+    fn log_likelihoods(&self) -> Vec<f64> {
+        self.inner.log_likelihoods().to_vec()
+    }
+
+    #[getter]
+    fn mean(&self) -> Vec<f64> {
+        self.inner.mean().to_vec()
+    }
+
+    #[getter]
+    fn draws(&self) -> usize {
+        self.inner.draws()
+    }
+
+    #[getter]
+    fn log_evidence(&self) -> f64 {
+        self.inner.log_evidence()
+    }
+
+    /// Inverse temperatures visited by the run, from 0 (prior) to 1 (posterior).
+    #[getter]
+    fn schedule(&self) -> Vec<f64> {
+        self.inner.schedule().to_vec()
+    }
+
+    /// Acceptance rate of the rejuvenation moves at each intermediate stage.
+    #[getter]
+    fn acceptance_rates(&self) -> Vec<f64> {
+        self.inner.acceptance_rates().to_vec()
+    }
+
+    #[getter]
+    fn time(&self) -> Duration {
+        self.inner.time()
+    }
+
+    /// Equal-weight view of the particles as a single chain.
+    fn to_samples(&self) -> PySamples {
+        PySamples {
+            inner: self.inner.to_samples(),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParticleSamples(draws={}, stages={}, log_evidence={:.3})",
+            self.inner.draws(),
+            self.inner.schedule().len() - 1,
+            self.inner.log_evidence()
+        )
+    }
+}
+
+/// Adaptive-tempering Sequential Monte Carlo sampler.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(module = "chronopt.sampler", name = "SequentialMonteCarlo")]
+#[derive(Clone)]
+pub struct PySequentialMonteCarlo {
+    inner: CoreSequentialMonteCarlo,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PySequentialMonteCarlo {
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: CoreSequentialMonteCarlo::new(),
+        }
+    }
+
+    fn with_particles(mut slf: PyRefMut<'_, Self>, particles: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_particles(particles);
+        slf
+    }
+
+    /// Fraction of the population kept as effective sample size by each tempering step.
+    fn with_target_ess(mut slf: PyRefMut<'_, Self>, fraction: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_target_ess(fraction);
+        slf
+    }
+
+    /// Number of Metropolis moves applied to every particle after each resampling.
+    fn with_mcmc_steps(mut slf: PyRefMut<'_, Self>, steps: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_mcmc_steps(steps);
+        slf
+    }
+
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
+        slf
+    }
+
+    /// Move particles from the prior to the posterior; raises if a parameter has neither
+    /// a prior nor bounds or the problem's costs are not a negative log-likelihood.
+    fn run(&self, problem: &PyProblem) -> PyResult<PyParticleSamples> {
+        self.inner
+            .run_particles(&problem.inner)
+            .map(|inner| PyParticleSamples { inner })
+            .map_err(PyValueError::new_err)
+    }
+}
+
+#[cfg(feature = "stubgen")]
+#[allow(dead_code)]
+fn optimiser_type_info() -> TypeInfo {
+    TypeInfo::unqualified("chronopt._chronopt.NelderMead")
+        | TypeInfo::unqualified("chronopt._chronopt.CMAES")
+        | TypeInfo::unqualified("chronopt._chronopt.Adam")
+        | TypeInfo::unqualified("chronopt._chronopt.LBFGSB")
+        | TypeInfo::unqualified("chronopt._chronopt.LevenbergMarquardt")
+        | TypeInfo::unqualified("chronopt._chronopt.DifferentialEvolution")
+        | TypeInfo::unqualified("chronopt._chronopt.PSO")
+}
+
+impl FromPyObject<'_, '_> for Optimiser {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        if let Ok(nm) = obj.extract::<PyRef<PyNelderMead>>() {
+            Ok(Optimiser::NelderMead((*nm).inner.clone()))
+        } else if let Ok(cma) = obj.extract::<PyRef<PyCMAES>>() {
+            Ok(Optimiser::Cmaes((*cma).inner.clone()))
+        } else if let Ok(adam) = obj.extract::<PyRef<PyAdam>>() {
+            Ok(Optimiser::Adam((*adam).inner.clone()))
+        } else if let Ok(lbfgsb) = obj.extract::<PyRef<PyLBFGSB>>() {
+            Ok(Optimiser::Lbfgsb((*lbfgsb).inner.clone()))
+        } else if let Ok(lm) = obj.extract::<PyRef<PyLevenbergMarquardt>>() {
+            Ok(Optimiser::LevenbergMarquardt((*lm).inner.clone()))
+        } else if let Ok(de) = obj.extract::<PyRef<PyDifferentialEvolution>>() {
+            Ok(Optimiser::DifferentialEvolution((*de).inner.clone()))
+        } else if let Ok(pso) = obj.extract::<PyRef<PyPSO>>() {
+            Ok(Optimiser::Pso((*pso).inner.clone()))
+        } else {
+            Err(PyTypeError::new_err(
+                "Optimiser must be an instance of NelderMead, CMAES, Adam, LBFGSB, LevenbergMarquardt, DifferentialEvolution, or PSO",
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// Cost Metrics
+// ============================================================================
+
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "CostMetric")]
+#[derive(Clone)]
+pub struct PyCostMetric {
+    inner: Arc<dyn CostMetric>,
+    name: &'static str,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyCostMetric {
+    /// Name of the cost metric.
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CostMetric(name='{}')", self.name)
+    }
+}
+
+impl PyCostMetric {
+    fn from_metric<M>(metric: M, name: &'static str) -> Self
+    where
+        M: CostMetric + 'static,
+    {
+        Self {
+            inner: Arc::new(metric),
+            name,
+        }
+    }
+
+    fn metric_arc(&self) -> Arc<dyn CostMetric> {
+        Arc::clone(&self.inner)
+    }
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pyfunction)]
+#[pyfunction(name = "SSE")]
+#[pyo3(signature = (weight = 1.0))]
+fn sse(weight: f64) -> PyCostMetric {
+    PyCostMetric::from_metric(SumSquaredError::new(Some(weight)), "sse")
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pyfunction)]
+#[pyfunction(name = "RMSE")]
+#[pyo3(signature = (weight = 1.0))]
+fn rmse(weight: f64) -> PyCostMetric {
+    PyCostMetric::from_metric(RootMeanSquaredError::new(Some(weight)), "rmse")
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pyfunction)]
+#[pyfunction(name = "GaussianNLL")]
+#[pyo3(signature = (variance, weight = 1.0))]
+fn gaussian_nll(variance: f64, weight: f64) -> PyResult<PyCostMetric> {
+    if !variance.is_finite() || variance <= 0.0 {
+        return Err(PyValueError::new_err(
+            "variance must be positive and finite",
+        ));
+    }
+    Ok(PyCostMetric::from_metric(
+        GaussianNll::new(Some(weight), variance),
+        "gaussian_nll",
+    ))
+}
+
+// ============================================================================
+// Priors
+// ============================================================================
+
+/// Prior distribution over a single parameter.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "Prior")]
+#[derive(Clone)]
+pub struct PyPrior {
+    inner: Prior,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyPrior {
+    /// Uniform prior on `[lower, upper]`.
+    #[staticmethod]
+    fn uniform(lower: f64, upper: f64) -> PyResult<Self> {
+        Self::from_result(Prior::uniform(lower, upper))
+    }
+
+    /// Normal prior with the given mean and standard deviation.
+    #[staticmethod]
+    fn normal(mean: f64, std: f64) -> PyResult<Self> {
+        Self::from_result(Prior::normal(mean, std))
+    }
+
+    /// Log-normal prior; `mean` and `std` describe the logarithm of the parameter.
+    #[staticmethod]
+    fn log_normal(mean: f64, std: f64) -> PyResult<Self> {
+        Self::from_result(Prior::log_normal(mean, std))
+    }
+
+    /// Normal prior restricted to `[lower, upper]`; either limit may be infinite.
+    #[staticmethod]
+    fn truncated_normal(mean: f64, std: f64, lower: f64, upper: f64) -> PyResult<Self> {
+        Self::from_result(Prior::truncated_normal(mean, std, lower, upper))
+    }
+
+    /// Half-Cauchy prior on `[0, inf)` with the given scale.
+    #[staticmethod]
+    fn half_cauchy(scale: f64) -> PyResult<Self> {
+        Self::from_result(Prior::half_cauchy(scale))
+    }
+
+    /// Beta prior on `[0, 1]`.
+    #[staticmethod]
+    fn beta(alpha: f64, beta: f64) -> PyResult<Self> {
+        Self::from_result(Prior::beta(alpha, beta))
+    }
+
+    /// Gamma prior parameterised by shape and rate.
+    #[staticmethod]
+    fn gamma(shape: f64, rate: f64) -> PyResult<Self> {
+        Self::from_result(Prior::gamma(shape, rate))
+    }
+
+    /// Interval containing all of the prior's probability mass.
+    #[getter]
+    fn support(&self) -> (f64, f64) {
+        self.inner.support()
+    }
+
+    /// Natural logarithm of the density at `x`.
+    fn log_density(&self, x: f64) -> f64 {
+        self.inner.log_density(x)
+    }
+
+    /// Map `x` onto the unit interval using the prior's CDF.
+    fn to_unit(&self, x: f64) -> f64 {
+        self.inner.to_unit(x)
+    }
+
+    /// Map a unit-interval coordinate onto the support using the inverse CDF.
+    fn from_unit(&self, u: f64) -> f64 {
+        self.inner.from_unit(u)
+    }
+
+    /// Draw `size` independent values from the prior.
+    #[pyo3(signature = (size = 1, seed = None))]
+    fn sample(&self, size: usize, seed: Option<u64>) -> Vec<f64> {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        (0..size).map(|_| self.inner.sample(&mut rng)).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        match self.inner {
+            Prior::Uniform { lower, upper } => format!("Prior.uniform({lower}, {upper})"),
+            Prior::Normal { mean, std } => format!("Prior.normal({mean}, {std})"),
+            Prior::LogNormal { mean, std } => format!("Prior.log_normal({mean}, {std})"),
+            Prior::TruncatedNormal {
+                mean,
+                std,
+                lower,
+                upper,
+            } => format!("Prior.truncated_normal({mean}, {std}, {lower}, {upper})"),
+            Prior::HalfCauchy { scale } => format!("Prior.half_cauchy({scale})"),
+            Prior::Beta { alpha, beta } => format!("Prior.beta({alpha}, {beta})"),
+            Prior::Gamma { shape, rate } => format!("Prior.gamma({shape}, {rate})"),
+        }
+    }
+}
+
+impl PyPrior {
+    fn from_result(prior: Result<Prior, String>) -> PyResult<Self> {
+        prior
+            .map(|inner| Self { inner })
+            .map_err(PyValueError::new_err)
+    }
+}
+
+/// Build a parameter spec, attaching the prior when one is supplied.
+fn parameter_spec(
+    name: String,
+    initial_value: f64,
+    bounds: Option<(f64, f64)>,
+    prior: Option<PyRef<'_, PyPrior>>,
+) -> ParameterSpec {
+    let spec = ParameterSpec::new(name, initial_value, bounds);
+    match prior {
+        Some(prior) => spec.with_prior(prior.inner),
+        None => spec,
+    }
+}
+
+/// Interpret DiffSL output selectors given as indices or names.
+fn observed_outputs(outputs: Vec<Bound<'_, PyAny>>) -> PyResult<Vec<ObservedOutput>> {
+    outputs
+        .into_iter()
+        .map(|output| {
+            if let Ok(index) = output.extract::<usize>() {
+                Ok(ObservedOutput::Index(index))
+            } else if let Ok(name) = output.extract::<String>() {
+                Ok(ObservedOutput::Name(name))
+            } else {
+                Err(PyTypeError::new_err(
+                    "Observed outputs must be non-negative indices or output names",
+                ))
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Python Objective Function Wrapper
+// ============================================================================
+
+struct PyObjectiveFn {
+    callable: Py<PyAny>,
+}
+
+impl PyObjectiveFn {
+    fn new(callable: Py<PyAny>) -> Self {
+        Self { callable }
+    }
+
+    fn call(&self, x: &[f64]) -> PyResult<f64> {
+        Python::attach(|py| {
+            let callable = self.callable.bind(py);
+            let input = PyArray1::from_slice(py, x);
+            let result = callable.call1((input,))?;
+
+            if let Ok(output) = result.extract::<PyReadonlyArray1<f64>>() {
+                let array = output.as_array();
+                return match array.len() {
+                    1 => Ok(array[0]),
+                    n => Err(PyValueError::new_err(format!(
+                        "Objective array must contain exactly one element, got {}",
+                        n
+                    ))),
+                };
+            }
+
+            if let Ok(values) = result.extract::<Vec<f64>>() {
+                return match values.len() {
+                    1 => Ok(values[0]),
+                    n => Err(PyValueError::new_err(format!(
+                        "Objective sequence must contain exactly one element, got {}",
+                        n
+                    ))),
+                };
+            }
+
+            if let Ok(value) = result.extract::<f64>() {
+                return Ok(value);
+            }
+
+            let ty_name = result
+                .get_type()
+                .name()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+
+            Err(PyTypeError::new_err(format!(
+                "Objective callable must return a float, numpy array, or single-element sequence; got {}",
+                ty_name
+            )))
+        })
+    }
+}
+
+struct PyGradientFn {
+    callable: Py<PyAny>,
+}
+
+impl PyGradientFn {
+    fn new(callable: Py<PyAny>) -> Self {
+        Self { callable }
+    }
+
+    fn call(&self, x: &[f64]) -> PyResult<Vec<f64>> {
+        Python::attach(|py| {
+            let callable = self.callable.bind(py);
+            let input = PyArray1::from_slice(py, x);
+            let result = callable.call1((input,))?;
+
+            if let Ok(output) = result.extract::<PyReadonlyArray1<f64>>() {
+                return Ok(output.as_array().to_vec());
+            }
+
+            result.extract::<Vec<f64>>()
+        })
+    }
+}
+
+struct PyObserverFn {
+    callable: Py<PyAny>,
+}
+
+impl PyObserverFn {
+    fn new(callable: Py<PyAny>) -> Self {
+        Self { callable }
+    }
+}
+
+impl OptimisationObserver for PyObserverFn {
+    fn observe(&self, progress: &IterationProgress) -> ObserverAction {
+        Python::attach(|py| {
+            let progress = PyIterationProgress {
+                inner: progress.clone(),
+            };
+            let outcome = self
+                .callable
+                .bind(py)
+                .call1((progress,))
+                .and_then(|result| result.is_truthy());
+
+            match outcome {
+                Ok(false) => ObserverAction::Continue,
+                Ok(true) => ObserverAction::Stop,
+                Err(err) => {
+                    // Exceptions cannot propagate through the optimiser; report and stop.
+                    err.write_unraisable(py, Some(self.callable.bind(py)));
+                    ObserverAction::Stop
+                }
+            }
+        })
+    }
+}
+
+fn observer_from_callable(obj: Py<PyAny>) -> PyResult<PyObserverFn> {
+    Python::attach(|py| {
+        if !obj.bind(py).is_callable() {
+            return Err(PyTypeError::new_err("Observer must be callable"));
+        }
+        Ok(())
+    })?;
+    Ok(PyObserverFn::new(obj))
+}
+
+// ============================================================================
+// Builder
+// ============================================================================
+
+/// High-level builder for optimisation `Problem` instances exposed to Python.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "ScalarBuilder")]
+pub struct PyScalarBuilder {
+    inner: ScalarProblemBuilder,
+    py_callable: Option<Arc<PyObjectiveFn>>,
+    py_gradient: Option<Arc<PyGradientFn>>,
+    default_optimiser: Option<Optimiser>,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyScalarBuilder {
+    /// Create an empty builder with no objective, parameters, or default optimiser.
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: ScalarProblemBuilder::new(),
+            py_callable: None,
+            py_gradient: None,
+            default_optimiser: None,
+        }
+    }
+
+    /// Configure the default optimiser used when `Problem.optimize` omits one.
+    fn with_optimiser(mut slf: PyRefMut<'_, Self>, optimiser: Optimiser) -> PyRefMut<'_, Self> {
+        match &optimiser {
+            Optimiser::NelderMead(nm) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(nm.clone());
+            }
+            Optimiser::Cmaes(cma) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(cma.clone());
+            }
+            Optimiser::Adam(adam) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(adam.clone());
+            }
+            Optimiser::Lbfgsb(lbfgsb) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(lbfgsb.clone());
+            }
+            Optimiser::LevenbergMarquardt(lm) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(lm.clone());
+            }
+            Optimiser::DifferentialEvolution(de) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(de.clone());
+            }
+            Optimiser::Pso(pso) => {
+                slf.inner = std::mem::take(&mut slf.inner).with_optimiser(pso.clone());
+            }
+        }
+
+        slf.default_optimiser = Some(optimiser);
+        slf
+    }
+
+    /// Attach the objective function callable executed during optimisation.
+    fn with_callable(mut slf: PyRefMut<'_, Self>, obj: Py<PyAny>) -> PyResult<PyRefMut<'_, Self>> {
+        Python::attach(|py| {
+            if !obj.bind(py).is_callable() {
+                return Err(PyTypeError::new_err("Object must be callable"));
+            }
+            Ok(())
+        })?;
+
+        let py_fn = Arc::new(PyObjectiveFn::new(obj));
+        let objective = Arc::clone(&py_fn);
+
+        slf.inner = std::mem::take(&mut slf.inner)
+            .with_objective(move |x: &[f64]| objective.call(x).unwrap_or(f64::INFINITY));
+        slf.py_callable = Some(py_fn);
+        Ok(slf)
+    }
+
+    /// Attach the gradient callable returning derivatives of the objective.
+    fn with_gradient(mut slf: PyRefMut<'_, Self>, obj: Py<PyAny>) -> PyResult<PyRefMut<'_, Self>> {
+        Python::attach(|py| {
+            if !obj.bind(py).is_callable() {
+                return Err(PyTypeError::new_err("Object must be callable"));
+            }
+            Ok(())
+        })?;
+
+        let py_grad = Arc::new(PyGradientFn::new(obj));
+        let grad = Arc::clone(&py_grad);
+
+        slf.inner = std::mem::take(&mut slf.inner).with_gradient(move |x: &[f64]| {
+            grad.call(x).unwrap_or_else(|_| vec![f64::NAN; x.len()])
+        });
+        slf.py_gradient = Some(py_grad);
+        Ok(slf)
+    }
+
+    /// Register a named optimisation variable in the order it appears in vectors.
+    ///
+    /// An optional `prior` is used by the samplers to target the posterior.
+    #[pyo3(signature = (name, initial_value, bounds=None, prior=None))]
+    fn with_parameter<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: String,
+        initial_value: f64,
+        bounds: Option<(f64, f64)>,
+        prior: Option<PyRef<'py, PyPrior>>,
+    ) -> PyRefMut<'py, Self> {
+        let spec = parameter_spec(name, initial_value, bounds, prior);
+        slf.inner = std::mem::take(&mut slf.inner).with_parameter(spec);
+        slf
+    }
+
+    /// Finalize the builder into an executable `Problem`.
+    fn build(&mut self) -> PyResult<PyProblem> {
+        let problem = self.inner.build().map_err(PyValueError::new_err)?;
+        Ok(PyProblem {
+            inner: problem,
+            default_optimiser: self.default_optimiser.clone(),
+        })
+    }
+}
+
+// ============================================================================
+// Diffsol Builder
+// ============================================================================
+
+/// Differential equation solver builder.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "DiffsolBuilder")]
+pub struct PyDiffsolBuilder {
+    inner: DiffsolProblemBuilder,
+    default_optimiser: Option<Optimiser>,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyDiffsolBuilder {
+    /// Create an empty differential solver builder.
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: DiffsolProblemBuilder::new(),
+            default_optimiser: None,
+        }
+    }
+
+    fn __copy__(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            default_optimiser: self.default_optimiser.clone(),
+        }
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyDict>) -> Self {
+        self.__copy__()
+    }
+
+    /// Register the DiffSL program describing the system dynamics.
+    fn with_diffsl(mut slf: PyRefMut<'_, Self>, dsl: String) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_diffsl(dsl);
+        slf
+    }
+
+    /// Remove any registered DiffSL program.
+    fn remove_diffsl(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_diffsl();
+        slf
+    }
+
+    /// Attach observed data used to fit the differential equation.
+    ///
+    /// The first column must contain the time samples (t_span) and the remaining
+    /// columns the observed trajectories.
+    fn with_data<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        data: PyReadonlyArrayDyn<'py, f64>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let data_matrix = convert_array_to_dmatrix(&data)?;
+        if data_matrix.ncols() < 2 {
+            return Err(PyValueError::new_err(
+                "Data must include at least two columns with t_span in the first column",
+            ));
+        }
+        slf.inner = std::mem::take(&mut slf.inner).with_data(data_matrix);
+        Ok(slf)
+    }
+
+    /// Remove any previously attached data along with its time span.
+    fn remove_data(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_data();
+        slf
+    }
+
+    /// Mark which observations are present (`True`) or missing (`False`).
+    ///
+    /// The mask has the shape of the data without its time column. Missing
+    /// observations, like NaN entries in the data, are left out of the residuals and
+    /// every cost metric.
+    fn with_mask<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        mask: PyReadonlyArrayDyn<'py, bool>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let mask_matrix = convert_array_to_dmatrix(&mask)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_mask(mask_matrix);
+        Ok(slf)
+    }
+
+    /// Treat every non-NaN observation as present again.
+    fn remove_mask(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_mask();
+        slf
+    }
+
+    /// Add an experiment fitted jointly with the data and other experiments.
+    ///
+    /// `data` follows the layout of `with_data`. `inputs` fixes DiffSL inputs and
+    /// `initial_conditions` overrides named states of the `u_i` block for this
+    /// experiment only; inputs fixed in every experiment leave the parameter vector.
+    /// `observations` and `cost` replace the builder's observation mapping and cost
+    /// metric for this experiment, and `mask` marks its missing observations as in
+    /// `with_mask`.
+    #[pyo3(signature = (
+        data,
+        inputs=None,
+        initial_conditions=None,
+        observations=None,
+        mask=None,
+        cost=None,
+    ))]
+    fn with_experiment<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        data: PyReadonlyArrayDyn<'py, f64>,
+        inputs: Option<HashMap<String, f64>>,
+        initial_conditions: Option<HashMap<String, f64>>,
+        observations: Option<Vec<Bound<'py, PyAny>>>,
+        mask: Option<PyReadonlyArrayDyn<'py, bool>>,
+        cost: Option<PyRef<'py, PyCostMetric>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let data_matrix = convert_array_to_dmatrix(&data)?;
+        if data_matrix.ncols() < 2 {
+            return Err(PyValueError::new_err(
+                "Data must include at least two columns with t_span in the first column",
+            ));
+        }
+        let mut experiment = DiffsolExperiment::new(data_matrix);
+        for (name, value) in inputs.unwrap_or_default() {
+            experiment = experiment.with_input(name, value);
+        }
+        for (state, value) in initial_conditions.unwrap_or_default() {
+            experiment = experiment.with_initial_condition(state, value);
+        }
+        if let Some(observations) = observations {
+            experiment = experiment.with_observations(observed_outputs(observations)?);
+        }
+        if let Some(mask) = mask {
+            experiment = experiment.with_mask(convert_array_to_dmatrix(&mask)?);
+        }
+        if let Some(cost) = cost {
+            experiment = experiment.with_cost_metric_arc(cost.metric_arc());
+        }
+        slf.inner = std::mem::take(&mut slf.inner).with_experiment(experiment);
+        Ok(slf)
+    }
+
+    /// Remove all experiments added with `with_experiment`.
+    fn remove_experiments(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_experiments();
+        slf
+    }
+
+    /// Compare each data column against a selected DiffSL output.
+    ///
+    /// Column `j` after the time column is fitted to `outputs[j]`, given either as an
+    /// index into `out_i` or as the name of an output (or state when the program has
+    /// no `out_i` block), so unobserved states need no data columns.
+    fn with_observations<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        outputs: Vec<Bound<'py, PyAny>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let outputs = observed_outputs(outputs)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observations(outputs);
+        Ok(slf)
+    }
+
+    /// Compare the full model output against the data again.
+    fn remove_observations(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_observations();
+        slf
+    }
+
+    /// Choose whether to use dense or sparse diffusion solvers.
+    fn with_backend(mut slf: PyRefMut<'_, Self>, backend: String) -> PyResult<PyRefMut<'_, Self>> {
+        let backend_enum = match backend.as_str() {
+            "dense" => DiffsolBackend::Dense,
+            "sparse" => DiffsolBackend::Sparse,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown backend '{}'. Expected 'dense' or 'sparse'",
+                    other
+                )))
+            }
+        };
+        slf.inner = std::mem::take(&mut slf.inner).with_backend(backend_enum);
+        Ok(slf)
+    }
+
+    /// Opt into parallel proposal generation when supported by the backend.
+    #[pyo3(signature = (parallel=None))]
+    fn with_parallel(mut slf: PyRefMut<'_, Self>, parallel: Option<bool>) -> PyRefMut<'_, Self> {
+        let parallel = parallel.unwrap_or(true);
+        slf.inner = std::mem::take(&mut slf.inner).with_parallel(parallel);
+        slf
+    }
+
+    fn with_config(
+        mut slf: PyRefMut<'_, Self>,
+        config: HashMap<String, f64>,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_config(config);
+        slf
+    }
+
+    /// Adjust the relative integration tolerance.
+    fn with_rtol(mut slf: PyRefMut<'_, Self>, rtol: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_rtol(rtol);
+        slf
+    }
+
+    /// Adjust the absolute integration tolerance.
+    fn with_atol(mut slf: PyRefMut<'_, Self>, atol: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_atol(atol);
+        slf
+    }
+
+    /// Register a named optimisation variable in the order it appears in vectors.
+    ///
+    /// An optional `prior` is used by the samplers to target the posterior.
+    #[pyo3(signature = (name, initial_value, bounds=None, prior=None))]
+    fn with_parameter<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: String,
+        initial_value: f64,
+        bounds: Option<(f64, f64)>,
+        prior: Option<PyRef<'py, PyPrior>>,
+    ) -> PyRefMut<'py, Self> {
+        let spec = parameter_spec(name, initial_value, bounds, prior);
+        slf.inner = std::mem::take(&mut slf.inner).with_parameter(spec);
+        slf
+    }
+
+    /// Remove previously provided parameter defaults.
+    fn clear_parameters(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner.clear_parameters();
+        slf
+    }
+
+    /// Estimate the initial value of a DiffSL state alongside the model parameters.
+    ///
+    /// Estimated initial states follow the registered parameters in vectors.
+    #[pyo3(signature = (state, name, initial_value, bounds=None, prior=None))]
+    fn with_estimated_initial_state<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        state: String,
+        name: String,
+        initial_value: f64,
+        bounds: Option<(f64, f64)>,
+        prior: Option<PyRef<'py, PyPrior>>,
+    ) -> PyRefMut<'py, Self> {
+        let spec = parameter_spec(name, initial_value, bounds, prior);
+        slf.inner = std::mem::take(&mut slf.inner).with_estimated_initial_state(state, spec);
+        slf
+    }
+
+    /// Keep every initial state at the value written in the DiffSL program.
+    fn remove_estimated_initial_states(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_estimated_initial_states();
+        slf
+    }
+
+    /// Select the error metric used to compare simulated and observed data.
+    fn with_cost<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        cost: PyRef<'py, PyCostMetric>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let metric = cost.metric_arc();
+        slf.inner = std::mem::take(&mut slf.inner).with_cost_metric_arc(metric);
+        Ok(slf)
+    }
+
+    /// Reset the cost metric to the default sum of squared errors.
+    fn remove_cost(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_cost();
+        slf
+    }
+
+    /// Configure the default optimiser used when `Problem.optimize` omits one.
+    fn with_optimiser(mut slf: PyRefMut<'_, Self>, optimiser: Optimiser) -> PyRefMut<'_, Self> {
+        let mut inner = std::mem::take(&mut slf.inner);
+        match &optimiser {
+            Optimiser::NelderMead(nm) => {
+                inner = inner.with_optimiser(nm.clone());
+            }
+            Optimiser::Cmaes(cma) => {
+                inner = inner.with_optimiser(cma.clone());
+            }
+            Optimiser::Adam(adam) => {
+                inner = inner.with_optimiser(adam.clone());
+            }
+            Optimiser::Lbfgsb(lbfgsb) => {
+                inner = inner.with_optimiser(lbfgsb.clone());
+            }
+            Optimiser::LevenbergMarquardt(lm) => {
+                inner = inner.with_optimiser(lm.clone());
+            }
+            Optimiser::DifferentialEvolution(de) => {
+                inner = inner.with_optimiser(de.clone());
+            }
+            Optimiser::Pso(pso) => {
+                inner = inner.with_optimiser(pso.clone());
+            }
+        }
+        slf.inner = inner;
+
+        slf.default_optimiser = Some(optimiser);
+        slf
+    }
+
+    /// Create a `Problem` representing the differential solver model.
+    fn build(&mut self) -> PyResult<PyProblem> {
+        let problem = self.inner.build().map_err(PyValueError::new_err)?;
+        Ok(PyProblem {
+            inner: problem,
+            default_optimiser: self.default_optimiser.clone(),
+        })
+    }
+}
+
+// ============================================================================
+// Vector Builder
+// ============================================================================
+
+/// Time-series problem builder for vector-valued objectives.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "VectorBuilder")]
+pub struct PyVectorBuilder {
+    inner: VectorProblemBuilder,
+    default_optimiser: Option<Optimiser>,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyVectorBuilder {
+    /// Create an empty vector problem builder.
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: VectorProblemBuilder::new(),
+            default_optimiser: None,
+        }
+    }
+
+    /// Register a callable that produces predictions matching the data shape.
+    ///
+    /// The callable should accept a parameter vector and return a numpy array
+    /// of the same shape as the observed data.
+    fn with_objective(
+        mut slf: PyRefMut<'_, Self>,
+        objective: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let obj_fn = move |params: &[f64]| -> Result<Vec<f64>, String> {
+            Python::attach(|py| {
+                let params_array = PyArray1::from_slice(py, params);
+                let result = objective
+                    .call1(py, (params_array,))
+                    .map_err(|e| format!("Objective call failed: {}", e))?;
+
+                let array: PyReadonlyArray1<f64> = result
+                    .extract(py)
+                    .map_err(|e| format!("Failed to extract array from objective: {}", e))?;
+
+                Ok(array
+                    .as_slice()
+                    .map_err(|_| "Array must be contiguous")?
+                    .to_vec())
+            })
+        };
+
+        slf.inner = std::mem::take(&mut slf.inner).with_objective(obj_fn);
+        Ok(slf)
+    }
+
+    /// Attach observed data used to fit the model.
+    ///
+    /// The data should be a 1D numpy array. The shape will be inferred
+    /// from the data length.
+    fn with_data<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        data: PyReadonlyArray1<'py, f64>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let data_vec = data
+            .as_slice()
+            .map_err(|_| PyValueError::new_err("Data array must be contiguous"))?
+            .to_vec();
+
+        slf.inner = std::mem::take(&mut slf.inner).with_data(data_vec);
+        Ok(slf)
+    }
+
+    /// Mark which observations are present (`True`) or missing (`False`).
+    ///
+    /// Missing observations, like NaN entries in the data, are left out of the
+    /// residuals and every cost metric.
+    fn with_mask<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        mask: PyReadonlyArray1<'py, bool>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let mask_vec = mask.as_array().to_vec();
+        slf.inner = std::mem::take(&mut slf.inner).with_mask(mask_vec);
+        Ok(slf)
+    }
+
+    /// Treat every non-NaN observation as present again.
+    fn remove_mask(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_mask();
+        slf
+    }
+
+    /// Stores an optimisation configuration value keyed by name.
+    fn with_config(mut slf: PyRefMut<'_, Self>, key: String, value: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_config(key, value);
+        slf
+    }
+
+    /// Register a named optimisation variable in the order it appears in vectors.
+    ///
+    /// An optional `prior` is used by the samplers to target the posterior.
+    #[pyo3(signature = (name, initial_value, bounds=None, prior=None))]
+    fn with_parameter<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: String,
+        initial_value: f64,
+        bounds: Option<(f64, f64)>,
+        prior: Option<PyRef<'py, PyPrior>>,
+    ) -> PyRefMut<'py, Self> {
+        let spec = parameter_spec(name, initial_value, bounds, prior);
+        slf.inner = std::mem::take(&mut slf.inner).with_parameter(spec);
+        slf
+    }
+
+    /// Remove previously provided parameter defaults.
+    fn clear_parameters(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner.clear_parameters();
+        slf
+    }
+
+    /// Select the error metric used to compare predictions and observed data.
+    fn with_cost<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        cost: PyRef<'py, PyCostMetric>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let metric = cost.metric_arc();
+        slf.inner = std::mem::take(&mut slf.inner).with_cost_metric_arc(metric);
+        Ok(slf)
+    }
+
+    /// Reset the cost metric to the default sum of squared errors.
+    fn remove_cost(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).remove_cost();
+        slf
+    }
+
+    /// Configure the default optimiser used when `Problem.optimize` omits one.
+    fn with_optimiser(mut slf: PyRefMut<'_, Self>, optimiser: Optimiser) -> PyRefMut<'_, Self> {
+        let mut inner = std::mem::take(&mut slf.inner);
+        match &optimiser {
+            Optimiser::NelderMead(nm) => {
+                inner = inner.with_optimiser(nm.clone());
+            }
             Optimiser::Cmaes(cma) => {
                 inner = inner.with_optimiser(cma.clone());
             }
             Optimiser::Adam(adam) => {
                 inner = inner.with_optimiser(adam.clone());
             }
+            Optimiser::Lbfgsb(lbfgsb) => {
+                inner = inner.with_optimiser(lbfgsb.clone());
+            }
+            Optimiser::LevenbergMarquardt(lm) => {
+                inner = inner.with_optimiser(lm.clone());
+            }
+            Optimiser::DifferentialEvolution(de) => {
+                inner = inner.with_optimiser(de.clone());
+            }
+            Optimiser::Pso(pso) => {
+                inner = inner.with_optimiser(pso.clone());
+            }
+        }
+        slf.inner = inner;
+        slf.default_optimiser = Some(optimiser);
+        slf
+    }
+
+    /// Create a `Problem` representing the vector optimisation model.
+    fn build(slf: PyRefMut<'_, Self>) -> PyResult<PyProblem> {
+        let problem = slf.inner.build().map_err(PyValueError::new_err)?;
+        Ok(PyProblem {
+            inner: problem,
+            default_optimiser: slf.default_optimiser.clone(),
+        })
+    }
+}
+
+// ============================================================================
+// Problem
+// ============================================================================
+
+/// Executable optimisation problem wrapping the Chronopt core implementation.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "Problem")]
+pub struct PyProblem {
+    inner: Problem,
+    default_optimiser: Option<Optimiser>,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyProblem {
+    /// Evaluate the configured objective function at `x`.
+    fn evaluate(&self, x: Vec<f64>) -> PyResult<f64> {
+        self.inner
+            .evaluate(&x)
+            .map_err(|e| PyValueError::new_err(format!("Evaluation failed: {}", e)))
+    }
+
+    /// Evaluate the gradient of the objective function at `x` if available.
+    fn evaluate_gradient(&self, x: Vec<f64>) -> PyResult<Option<Vec<f64>>> {
+        Ok(self.inner.gradient().map(|grad| grad(x.as_slice())))
+    }
+
+    /// Return the unreduced residuals (prediction minus observation) at `x`.
+    fn residuals(&self, x: Vec<f64>) -> PyResult<Vec<f64>> {
+        self.inner
+            .residuals(&x)
+            .map_err(|e| PyValueError::new_err(format!("Residual evaluation failed: {}", e)))
+    }
+
+    /// Return the residuals at `x` together with their Jacobian (one row per residual).
+    fn residuals_with_jacobian(&self, x: Vec<f64>) -> PyResult<(Vec<f64>, Vec<Vec<f64>>)> {
+        let (residuals, jacobian) = self
+            .inner
+            .residuals_with_jacobian(&x)
+            .map_err(|e| PyValueError::new_err(format!("Residual evaluation failed: {}", e)))?;
+        let rows = jacobian
+            .row_iter()
+            .map(|row| row.iter().copied().collect())
+            .collect();
+        Ok((residuals, rows))
+    }
+
+    #[pyo3(signature = (initial=None, optimiser=None))]
+    /// Solve the problem starting from `initial` using the supplied optimiser.
+    fn optimize(
+        &self,
+        initial: Option<Vec<f64>>,
+        optimiser: Option<Optimiser>,
+    ) -> PyResult<PyOptimisationResults> {
+        let initial = initial.or_else(|| {
+            let defaults = self.inner.default_parameters();
+            if defaults.is_empty() {
+                None
+            } else {
+                Some(defaults)
+            }
+        });
+        let result = match optimiser.as_ref().or(self.default_optimiser.as_ref()) {
+            Some(Optimiser::NelderMead(nm)) => self.inner.optimize(initial, Some(nm)),
+            Some(Optimiser::Cmaes(cma)) => self.inner.optimize(initial, Some(cma)),
+            Some(Optimiser::Adam(adam)) => self.inner.optimize(initial, Some(adam)),
+            Some(Optimiser::Lbfgsb(lbfgsb)) => self.inner.optimize(initial, Some(lbfgsb)),
+            Some(Optimiser::LevenbergMarquardt(lm)) => self.inner.optimize(initial, Some(lm)),
+            Some(Optimiser::DifferentialEvolution(de)) => self.inner.optimize(initial, Some(de)),
+            Some(Optimiser::Pso(pso)) => self.inner.optimize(initial, Some(pso)),
+            None => self.inner.optimize(initial, None),
+        };
+
+        Ok(PyOptimisationResults { inner: result })
+    }
+
+    /// Return the numeric configuration value stored under `key` if present.
+    fn get_config(&self, key: String) -> Option<f64> {
+        self.inner.get_config(&key).copied()
+    }
+
+    /// Return the number of parameters the problem expects.
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    fn parameters(&self) -> Vec<ParameterSpecEntry> {
+        self.inner
+            .parameter_specs()
+            .iter()
+            .map(|spec| (spec.name.clone(), spec.initial_value, spec.bounds))
+            .collect()
+    }
+
+    /// Return the default parameter vector implied by the builder.
+    #[pyo3(name = "default_parameters")]
+    fn default_parameters_py(&self) -> Vec<f64> {
+        self.inner.default_parameters()
+    }
+
+    /// Return a copy of the problem configuration dictionary.
+    fn config(&self) -> HashMap<String, f64> {
+        self.inner.config().clone()
+    }
+}
+
+// ============================================================================
+// NelderMead Optimiser
+// ============================================================================
+
+/// Classic simplex-based direct search optimiser.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "NelderMead")]
+#[derive(Clone)]
+pub struct PyNelderMead {
+    inner: NelderMead,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyNelderMead {
+    /// Create a Nelder-Mead optimiser with default coefficients.
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: NelderMead::new(),
+        }
+    }
+
+    /// Limit the number of simplex iterations.
+    fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
+        slf
+    }
+
+    /// Set the stopping threshold on simplex size or objective reduction.
+    fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
+        slf
+    }
+
+    /// Stop once simplex vertices fall within the supplied positional tolerance.
+    fn with_position_tolerance(mut slf: PyRefMut<'_, Self>, tolerance: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_position_tolerance(tolerance);
+        slf
+    }
+
+    /// Abort after evaluating the objective `max_evaluations` times.
+    fn with_max_evaluations(
+        mut slf: PyRefMut<'_, Self>,
+        max_evaluations: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_evaluations(max_evaluations);
+        slf
+    }
+
+    /// Override the reflection, expansion, contraction, and shrink coefficients.
+    fn with_coefficients(
+        mut slf: PyRefMut<'_, Self>,
+        alpha: f64,
+        gamma: f64,
+        rho: f64,
+        sigma: f64,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_coefficients(alpha, gamma, rho, sigma);
+        slf
+    }
+
+    /// Abort if the objective fails to improve within the allotted time.
+    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
+        slf
+    }
+
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
+        slf
+    }
+
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
+    }
+
+    /// Start an ask/tell session from `initial`, optionally clamped to per-parameter bounds.
+    #[pyo3(signature = (initial, bounds=None))]
+    fn initialise(
+        &self,
+        initial: Vec<f64>,
+        bounds: Option<Vec<(f64, f64)>>,
+    ) -> PyResult<PyNelderMeadState> {
+        self.inner
+            .initialise(initial, bounds)
+            .map(|inner| PyNelderMeadState { inner })
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Write a checkpoint to `path` every `every` iterations; continue with `resume_from`.
+    fn with_checkpoint(
+        mut slf: PyRefMut<'_, Self>,
+        path: PathBuf,
+        every: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_checkpoint(path, every);
+        slf
+    }
+
+    /// Continue an interrupted run from the checkpoint at `path`.
+    fn resume_from(&self, problem: &PyProblem, path: PathBuf) -> PyResult<PyOptimisationResults> {
+        self.inner
+            .resume_from(&problem.inner, path)
+            .map(|inner| PyOptimisationResults { inner })
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Optimise the given problem starting from the provided initial simplex centre.
+    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
+        let result = self.inner.run(&problem.inner, initial);
+        PyOptimisationResults { inner: result }
+    }
+}
+
+// ============================================================================
+// CMAES Optimiser
+// ============================================================================
+
+/// Covariance Matrix Adaptation Evolution Strategy optimiser.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "CMAES")]
+#[derive(Clone)]
+pub struct PyCMAES {
+    inner: CMAES,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyCMAES {
+    /// Create a CMA-ES optimiser with library defaults.
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: CMAES::new(),
+        }
+    }
+
+    /// Limit the number of iterations/generations before termination.
+    fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
+        slf
+    }
+
+    /// Set the stopping threshold on the best objective value.
+    fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
+        slf
+    }
+
+    /// Set the initial global step-size (standard deviation).
+    fn with_sigma0(mut slf: PyRefMut<'_, Self>, sigma0: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_sigma0(sigma0);
+        slf
+    }
+
+    /// Abort the run if no improvement occurs for the given wall-clock duration.
+    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
+        slf
+    }
+
+    /// Specify the number of offspring evaluated per generation.
+    fn with_population_size(
+        mut slf: PyRefMut<'_, Self>,
+        population_size: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_population_size(population_size);
+        slf
+    }
+
+    /// Initialise the internal RNG for reproducible runs.
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
+        slf
+    }
+
+    /// Restart stalled runs with growing ('ipop') or alternating ('bipop') population sizes.
+    ///
+    /// Each restart begins from a fresh starting point, and the sequence runs until
+    /// `max_restarts` is reached or patience elapses.
+    fn with_restarts(
+        mut slf: PyRefMut<'_, Self>,
+        strategy: String,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let strategy_enum = match strategy.to_ascii_lowercase().as_str() {
+            "ipop" => RestartStrategy::Ipop,
+            "bipop" => RestartStrategy::Bipop,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown restart strategy '{}'. Expected 'ipop' or 'bipop'",
+                    other
+                )))
+            }
+        };
+        slf.inner = std::mem::take(&mut slf.inner).with_restarts(strategy_enum);
+        Ok(slf)
+    }
+
+    /// Limit the number of restarts performed after the initial run.
+    fn with_max_restarts(mut slf: PyRefMut<'_, Self>, max_restarts: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_restarts(max_restarts);
+        slf
+    }
+
+    /// Set the factor by which large-population restarts grow the population size.
+    fn with_population_increase(mut slf: PyRefMut<'_, Self>, factor: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_population_increase(factor);
+        slf
+    }
+
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
+        slf
+    }
+
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
+    }
+
+    /// Start an ask/tell session from `initial`, optionally clamped to per-parameter bounds.
+    #[pyo3(signature = (initial, bounds=None))]
+    fn initialise(
+        &self,
+        initial: Vec<f64>,
+        bounds: Option<Vec<(f64, f64)>>,
+    ) -> PyResult<PyCMAESState> {
+        self.inner
+            .initialise(initial, bounds)
+            .map(|inner| PyCMAESState { inner })
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Write a checkpoint to `path` every `every` iterations; continue with `resume_from`.
+    fn with_checkpoint(
+        mut slf: PyRefMut<'_, Self>,
+        path: PathBuf,
+        every: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_checkpoint(path, every);
+        slf
+    }
+
+    /// Continue an interrupted run from the checkpoint at `path`.
+    fn resume_from(&self, problem: &PyProblem, path: PathBuf) -> PyResult<PyOptimisationResults> {
+        self.inner
+            .resume_from(&problem.inner, path)
+            .map(|inner| PyOptimisationResults { inner })
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Optimise the given problem starting from the provided mean vector.
+    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
+        let result = self.inner.run(&problem.inner, initial);
+        PyOptimisationResults { inner: result }
+    }
+}
+
+// ============================================================================
+// Differential Evolution Optimiser
+// ============================================================================
+
+/// Differential evolution optimiser evaluating each generation as a batch.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "DifferentialEvolution")]
+#[derive(Clone)]
+pub struct PyDifferentialEvolution {
+    inner: DifferentialEvolution,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyDifferentialEvolution {
+    /// Create a differential evolution optimiser with library defaults.
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: DifferentialEvolution::new(),
         }
-        slf.inner = inner;
+    }
+
+    /// Limit the number of generations before termination.
+    fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
+        slf
+    }
+
+    /// Set the stopping threshold on the population's objective and parameter spread.
+    fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
+        slf
+    }
+
+    /// Set the initial spread used for parameters without finite bounds.
+    fn with_sigma0(mut slf: PyRefMut<'_, Self>, sigma0: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_sigma0(sigma0);
+        slf
+    }
+
+    /// Abort the run once the patience window has elapsed.
+    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
+        slf
+    }
+
+    /// Specify the number of population members (at least four).
+    fn with_population_size(
+        mut slf: PyRefMut<'_, Self>,
+        population_size: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_population_size(population_size);
+        slf
+    }
+
+    /// Set the differential weight `F` applied to difference vectors.
+    fn with_mutation(mut slf: PyRefMut<'_, Self>, mutation: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_mutation(mutation);
+        slf
+    }
+
+    /// Set the binomial crossover probability `CR`.
+    fn with_crossover(mut slf: PyRefMut<'_, Self>, crossover: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_crossover(crossover);
+        slf
+    }
+
+    /// Choose the mutation scheme: 'rand1bin' or 'best1bin'.
+    fn with_strategy(
+        mut slf: PyRefMut<'_, Self>,
+        strategy: String,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let strategy_enum = match strategy.to_ascii_lowercase().as_str() {
+            "rand1bin" => DEStrategy::Rand1Bin,
+            "best1bin" => DEStrategy::Best1Bin,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown strategy '{}'. Expected 'rand1bin' or 'best1bin'",
+                    other
+                )))
+            }
+        };
+        slf.inner = std::mem::take(&mut slf.inner).with_strategy(strategy_enum);
+        Ok(slf)
+    }
+
+    /// Initialise the internal RNG for reproducible runs.
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
+        slf
+    }
 
-        slf.default_optimiser = Some(optimiser);
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
         slf
     }
 
-    /// Create a `Problem` representing the differential solver model.
-    fn build(&mut self) -> PyResult<PyProblem> {
-        let problem = self.inner.build().map_err(PyValueError::new_err)?;
-        Ok(PyProblem {
-            inner: problem,
-            default_optimiser: self.default_optimiser.clone(),
-        })
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
+    }
+
+    /// Optimise the given problem, seeding the population with the provided point.
+    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
+        let result = self.inner.run(&problem.inner, initial);
+        PyOptimisationResults { inner: result }
     }
 }
 
 // ============================================================================
-// Vector Builder
+// Particle Swarm Optimiser
 // ============================================================================
 
-/// Time-series problem builder for vector-valued objectives.
+/// Particle swarm optimiser evaluating the whole swarm as a batch each iteration.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "VectorBuilder")]
-pub struct PyVectorBuilder {
-    inner: VectorProblemBuilder,
-    default_optimiser: Option<Optimiser>,
+#[pyclass(name = "PSO")]
+#[derive(Clone)]
+pub struct PyPSO {
+    inner: PSO,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyVectorBuilder {
-    /// Create an empty vector problem builder.
+impl PyPSO {
+    /// Create a particle swarm optimiser with library defaults.
     #[new]
     fn new() -> Self {
-        Self {
-            inner: VectorProblemBuilder::new(),
-            default_optimiser: None,
-        }
+        Self { inner: PSO::new() }
     }
 
-    /// Register a callable that produces predictions matching the data shape.
-    ///
-    /// The callable should accept a parameter vector and return a numpy array
-    /// of the same shape as the observed data.
-    fn with_objective(
-        mut slf: PyRefMut<'_, Self>,
-        objective: Py<PyAny>,
-    ) -> PyResult<PyRefMut<'_, Self>> {
-        let obj_fn = move |params: &[f64]| -> Result<Vec<f64>, String> {
-            Python::attach(|py| {
-                let params_array = PyArray1::from_slice(py, params);
-                let result = objective
-                    .call1(py, (params_array,))
-                    .map_err(|e| format!("Objective call failed: {}", e))?;
-
-                let array: PyReadonlyArray1<f64> = result
-                    .extract(py)
-                    .map_err(|e| format!("Failed to extract array from objective: {}", e))?;
-
-                Ok(array
-                    .as_slice()
-                    .map_err(|_| "Array must be contiguous")?
-                    .to_vec())
-            })
-        };
-
-        slf.inner = std::mem::take(&mut slf.inner).with_objective(obj_fn);
-        Ok(slf)
+    /// Limit the number of swarm iterations before termination.
+    fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
+        slf
     }
 
-    /// Attach observed data used to fit the model.
-    ///
-    /// The data should be a 1D numpy array. The shape will be inferred
-    /// from the data length.
-    fn with_data<'py>(
-        mut slf: PyRefMut<'py, Self>,
-        data: PyReadonlyArray1<'py, f64>,
-    ) -> PyResult<PyRefMut<'py, Self>> {
-        let data_vec = data
-            .as_slice()
-            .map_err(|_| PyValueError::new_err("Data array must be contiguous"))?
-            .to_vec();
+    /// Set the stopping threshold on the spread of the particles' best positions and values.
+    fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
+        slf
+    }
 
-        slf.inner = std::mem::take(&mut slf.inner).with_data(data_vec);
-        Ok(slf)
+    /// Set the initial spread of positions and velocities for parameters without finite bounds.
+    fn with_sigma0(mut slf: PyRefMut<'_, Self>, sigma0: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_sigma0(sigma0);
+        slf
     }
 
-    /// Stores an optimisation configuration value keyed by name.
-    fn with_config(mut slf: PyRefMut<'_, Self>, key: String, value: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_config(key, value);
+    /// Abort the run once the patience window has elapsed.
+    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
         slf
     }
 
-    /// Register a named optimisation variable in the order it appears in vectors.
-    #[pyo3(signature = (name, initial_value, bounds=None))]
-    fn with_parameter(
+    /// Specify the number of particles in the swarm.
+    fn with_population_size(
         mut slf: PyRefMut<'_, Self>,
-        name: String,
-        initial_value: f64,
-        bounds: Option<(f64, f64)>,
+        population_size: usize,
     ) -> PyRefMut<'_, Self> {
-        let spec = ParameterSpec::new(name.clone(), initial_value, bounds);
-        slf.inner = std::mem::take(&mut slf.inner).with_parameter(spec);
+        slf.inner = std::mem::take(&mut slf.inner).with_population_size(population_size);
         slf
     }
 
-    /// Remove previously provided parameter defaults.
-    fn clear_parameters(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
-        slf.inner.clear_parameters();
+    /// Set the weight applied to each particle's previous velocity.
+    fn with_inertia(mut slf: PyRefMut<'_, Self>, inertia: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_inertia(inertia);
         slf
     }
 
-    /// Select the error metric used to compare predictions and observed data.
-    fn with_cost<'py>(
-        mut slf: PyRefMut<'py, Self>,
-        cost: PyRef<'py, PyCostMetric>,
-    ) -> PyResult<PyRefMut<'py, Self>> {
-        let metric = cost.metric_arc();
-        slf.inner = std::mem::take(&mut slf.inner).with_cost_metric_arc(metric);
-        Ok(slf)
+    /// Set the attraction towards each particle's own best position.
+    fn with_cognitive(mut slf: PyRefMut<'_, Self>, cognitive: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_cognitive(cognitive);
+        slf
     }
 
-    /// Reset the cost metric to the default sum of squared errors.
-    fn remove_cost(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).remove_cost();
+    /// Set the attraction towards the best position found by the swarm.
+    fn with_social(mut slf: PyRefMut<'_, Self>, social: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_social(social);
         slf
     }
 
-    /// Configure the default optimiser used when `Problem.optimize` omits one.
-    fn with_optimiser(mut slf: PyRefMut<'_, Self>, optimiser: Optimiser) -> PyRefMut<'_, Self> {
-        let mut inner = std::mem::take(&mut slf.inner);
-        match &optimiser {
-            Optimiser::NelderMead(nm) => {
-                inner = inner.with_optimiser(nm.clone());
-            }
-            Optimiser::Cmaes(cma) => {
-                inner = inner.with_optimiser(cma.clone());
-            }
-            Optimiser::Adam(adam) => {
-                inner = inner.with_optimiser(adam.clone());
-            }
-        }
-        slf.inner = inner;
-        slf.default_optimiser = Some(optimiser);
+    /// Initialise the internal RNG for reproducible runs.
+    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
         slf
     }
 
-    /// Create a `Problem` representing the vector optimisation model.
-    fn build(slf: PyRefMut<'_, Self>) -> PyResult<PyProblem> {
-        let problem = slf.inner.build().map_err(PyValueError::new_err)?;
-        Ok(PyProblem {
-            inner: problem,
-            default_optimiser: slf.default_optimiser.clone(),
-        })
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
+        slf
+    }
+
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
+    }
+
+    /// Optimise the given problem, seeding the swarm with the provided point.
+    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
+        let result = self.inner.run(&problem.inner, initial);
+        PyOptimisationResults { inner: result }
     }
 }
 
 // ============================================================================
-// Problem
+// Adam Optimiser
 // ============================================================================
 
-/// Executable optimisation problem wrapping the Chronopt core implementation.
+/// Adaptive Moment Estimation (Adam) gradient-based optimiser.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "Problem")]
-pub struct PyProblem {
-    inner: Problem,
-    default_optimiser: Option<Optimiser>,
+#[pyclass(name = "Adam")]
+#[derive(Clone)]
+pub struct PyAdam {
+    inner: Adam,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyProblem {
-    /// Evaluate the configured objective function at `x`.
-    fn evaluate(&self, x: Vec<f64>) -> PyResult<f64> {
-        self.inner
-            .evaluate(&x)
-            .map_err(|e| PyValueError::new_err(format!("Evaluation failed: {}", e)))
+impl PyAdam {
+    /// Create an Adam optimiser with library defaults.
+    #[new]
+    fn new() -> Self {
+        Self { inner: Adam::new() }
     }
 
-    /// Evaluate the gradient of the objective function at `x` if available.
-    fn evaluate_gradient(&self, x: Vec<f64>) -> PyResult<Option<Vec<f64>>> {
-        Ok(self.inner.gradient().map(|grad| grad(x.as_slice())))
+    /// Limit the maximum number of optimisation iterations.
+    fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
+        slf
     }
 
-    #[pyo3(signature = (initial=None, optimiser=None))]
-    /// Solve the problem starting from `initial` using the supplied optimiser.
-    fn optimize(
+    /// Set the stopping threshold on the gradient norm.
+    fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
+        slf
+    }
+
+    /// Configure the base learning rate / step size.
+    fn with_step_size(mut slf: PyRefMut<'_, Self>, step_size: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_step_size(step_size);
+        slf
+    }
+
+    /// Override the exponential decay rates for the first and second moments.
+    fn with_betas(mut slf: PyRefMut<'_, Self>, beta1: f64, beta2: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_betas(beta1, beta2);
+        slf
+    }
+
+    /// Override the numerical stability constant added to the denominator.
+    fn with_eps(mut slf: PyRefMut<'_, Self>, eps: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_eps(eps);
+        slf
+    }
+
+    /// Abort the run once the patience window has elapsed.
+    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
+        slf
+    }
+
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
+        slf
+    }
+
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
+    }
+
+    /// Start an ask/tell session from `initial`, optionally clamped to per-parameter bounds.
+    #[pyo3(signature = (initial, bounds=None))]
+    fn initialise(
         &self,
-        initial: Option<Vec<f64>>,
-        optimiser: Option<Optimiser>,
-    ) -> PyResult<PyOptimisationResults> {
-        let initial = initial.or_else(|| {
-            let defaults = self.inner.default_parameters();
-            if defaults.is_empty() {
-                None
-            } else {
-                Some(defaults)
-            }
-        });
-        let result = match optimiser.as_ref().or(self.default_optimiser.as_ref()) {
-            Some(Optimiser::NelderMead(nm)) => self.inner.optimize(initial, Some(nm)),
-            Some(Optimiser::Cmaes(cma)) => self.inner.optimize(initial, Some(cma)),
-            Some(Optimiser::Adam(adam)) => self.inner.optimize(initial, Some(adam)),
-            None => self.inner.optimize(initial, None),
-        };
+        initial: Vec<f64>,
+        bounds: Option<Vec<(f64, f64)>>,
+    ) -> PyResult<PyAdamState> {
+        self.inner
+            .initialise(initial, bounds)
+            .map(|inner| PyAdamState { inner })
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Optimise the given problem using Adam starting from the provided point.
+    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
+        let result = self.inner.run(&problem.inner, initial);
+        PyOptimisationResults { inner: result }
+    }
+}
+
+// ============================================================================
+// Ask/Tell Sessions
+// ============================================================================
+
+/// In-progress Nelder-Mead search driven through `ask` and `tell`.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "NelderMeadState")]
+pub struct PyNelderMeadState {
+    inner: NelderMeadState,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyNelderMeadState {
+    /// Candidate points to evaluate next; empty once the search has finished.
+    fn ask(&mut self) -> Vec<Vec<f64>> {
+        self.inner.ask()
+    }
 
-        Ok(PyOptimisationResults { inner: result })
+    /// Report objective values for the points returned by the previous `ask`.
+    fn tell(&mut self, points: Vec<Vec<f64>>, values: Vec<f64>) -> PyResult<()> {
+        let values = values.into_iter().map(Ok).collect();
+        self.inner
+            .tell(&points, values)
+            .map_err(PyValueError::new_err)
     }
 
-    /// Return the numeric configuration value stored under `key` if present.
-    fn get_config(&self, key: String) -> Option<f64> {
-        self.inner.get_config(&key).copied()
+    /// Whether the search has met a termination criterion.
+    fn is_finished(&self) -> bool {
+        self.inner.is_finished()
     }
 
-    /// Return the number of parameters the problem expects.
-    fn dimension(&self) -> usize {
-        self.inner.dimension()
+    /// Snapshot of the search so far.
+    fn result(&self) -> PyOptimisationResults {
+        PyOptimisationResults {
+            inner: self.inner.results(),
+        }
     }
+}
 
-    fn parameters(&self) -> Vec<ParameterSpecEntry> {
+/// In-progress CMA-ES search driven through `ask` and `tell`.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "CMAESState")]
+pub struct PyCMAESState {
+    inner: CMAESState,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyCMAESState {
+    /// Candidate points to evaluate next; empty once the search has finished.
+    fn ask(&mut self) -> Vec<Vec<f64>> {
+        self.inner.ask()
+    }
+
+    /// Report objective values for the points returned by the previous `ask`.
+    fn tell(&mut self, points: Vec<Vec<f64>>, values: Vec<f64>) -> PyResult<()> {
+        let values = values.into_iter().map(Ok).collect();
         self.inner
-            .parameter_specs()
-            .iter()
-            .map(|spec| (spec.name.clone(), spec.initial_value, spec.bounds))
-            .collect()
+            .tell(&points, values)
+            .map_err(PyValueError::new_err)
     }
 
-    /// Return the default parameter vector implied by the builder.
-    #[pyo3(name = "default_parameters")]
-    fn default_parameters_py(&self) -> Vec<f64> {
-        self.inner.default_parameters()
+    /// Whether the search has met a termination criterion.
+    fn is_finished(&self) -> bool {
+        self.inner.is_finished()
     }
 
-    /// Return a copy of the problem configuration dictionary.
-    fn config(&self) -> HashMap<String, f64> {
-        self.inner.config().clone()
+    /// Snapshot of the search so far.
+    fn result(&self) -> PyOptimisationResults {
+        PyOptimisationResults {
+            inner: self.inner.results(),
+        }
+    }
+}
+
+/// In-progress Adam descent driven through `ask` and `tell`.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "AdamState")]
+pub struct PyAdamState {
+    inner: AdamState,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyAdamState {
+    /// The current iterate to evaluate; empty once the descent has finished.
+    fn ask(&mut self) -> Vec<Vec<f64>> {
+        self.inner.ask()
+    }
+
+    /// Report objective values and gradients for the points returned by `ask`.
+    fn tell(
+        &mut self,
+        points: Vec<Vec<f64>>,
+        values: Vec<f64>,
+        gradients: Vec<Vec<f64>>,
+    ) -> PyResult<()> {
+        if values.len() != gradients.len() {
+            return Err(PyValueError::new_err(format!(
+                "Expected one gradient per value, got {} values and {} gradients",
+                values.len(),
+                gradients.len()
+            )));
+        }
+        let values = values.into_iter().zip(gradients).map(Ok).collect();
+        self.inner
+            .tell(&points, values)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Whether the descent has met a termination criterion.
+    fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    /// Snapshot of the descent so far.
+    fn result(&self) -> PyOptimisationResults {
+        PyOptimisationResults {
+            inner: self.inner.results(),
+        }
     }
 }
 
 // ============================================================================
-// NelderMead Optimiser
+// L-BFGS-B Optimiser
 // ============================================================================
 
-/// Classic simplex-based direct search optimiser.
+/// Limited-memory BFGS quasi-Newton optimiser with projected bound handling.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "NelderMead")]
+#[pyclass(name = "LBFGSB")]
 #[derive(Clone)]
-pub struct PyNelderMead {
-    inner: NelderMead,
+pub struct PyLBFGSB {
+    inner: LBFGSB,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyNelderMead {
-    /// Create a Nelder-Mead optimiser with default coefficients.
+impl PyLBFGSB {
+    /// Create an L-BFGS-B optimiser with library defaults.
     #[new]
     fn new() -> Self {
         Self {
-            inner: NelderMead::new(),
+            inner: LBFGSB::new(),
         }
     }
 
-    /// Limit the number of simplex iterations.
+    /// Limit the maximum number of optimisation iterations.
     fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
         slf
     }
 
-    /// Set the stopping threshold on simplex size or objective reduction.
+    /// Set the stopping threshold on the projected gradient norm and cost change.
     fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
         slf
     }
 
-    /// Stop once simplex vertices fall within the supplied positional tolerance.
-    fn with_position_tolerance(mut slf: PyRefMut<'_, Self>, tolerance: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_position_tolerance(tolerance);
+    /// Set the number of curvature pairs retained for the Hessian approximation.
+    fn with_memory(mut slf: PyRefMut<'_, Self>, memory: usize) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_memory(memory);
         slf
     }
 
-    /// Abort after evaluating the objective `max_evaluations` times.
-    fn with_max_evaluations(
+    /// Limit the number of backtracking steps attempted per line search.
+    fn with_max_line_search(
         mut slf: PyRefMut<'_, Self>,
-        max_evaluations: usize,
+        max_line_search: usize,
     ) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_max_evaluations(max_evaluations);
+        slf.inner = std::mem::take(&mut slf.inner).with_max_line_search(max_line_search);
         slf
     }
 
-    /// Override the reflection, expansion, contraction, and shrink coefficients.
-    fn with_coefficients(
-        mut slf: PyRefMut<'_, Self>,
-        alpha: f64,
-        gamma: f64,
-        rho: f64,
-        sigma: f64,
-    ) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_coefficients(alpha, gamma, rho, sigma);
+    /// Abort the run once the patience window has elapsed.
+    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
         slf
     }
 
-    /// Abort if the objective fails to improve within the allotted time.
-    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
         slf
     }
 
-    /// Optimise the given problem starting from the provided initial simplex centre.
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
+    }
+
+    /// Optimise the given problem using L-BFGS-B starting from the provided point.
     fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
         let result = self.inner.run(&problem.inner, initial);
         PyOptimisationResults { inner: result }
@@ -1047,68 +2796,72 @@ impl PyNelderMead {
 }
 
 // ============================================================================
-// CMAES Optimiser
+// Levenberg-Marquardt Optimiser
 // ============================================================================
 
-/// Covariance Matrix Adaptation Evolution Strategy optimiser.
+/// Levenberg-Marquardt least-squares optimiser for residual-based problems.
+///
+/// Residuals are weighted by the problem's cost metric, which must be SSE or
+/// Gaussian NLL. Sparse Diffsol backends use a finite-difference Jacobian.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "CMAES")]
+#[pyclass(name = "LevenbergMarquardt")]
 #[derive(Clone)]
-pub struct PyCMAES {
-    inner: CMAES,
+pub struct PyLevenbergMarquardt {
+    inner: LevenbergMarquardt,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyCMAES {
-    /// Create a CMA-ES optimiser with library defaults.
+impl PyLevenbergMarquardt {
+    /// Create a Levenberg-Marquardt optimiser with library defaults.
     #[new]
     fn new() -> Self {
         Self {
-            inner: CMAES::new(),
+            inner: LevenbergMarquardt::new(),
         }
     }
 
-    /// Limit the number of iterations/generations before termination.
+    /// Limit the maximum number of accepted steps.
     fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
         slf
     }
 
-    /// Set the stopping threshold on the best objective value.
+    /// Set the stopping threshold on the gradient norm, relative cost change and step size.
     fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
         slf
     }
 
-    /// Set the initial global step-size (standard deviation).
-    fn with_sigma0(mut slf: PyRefMut<'_, Self>, sigma0: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_sigma0(sigma0);
+    /// Set the initial damping factor blending Gauss-Newton and gradient descent steps.
+    fn with_initial_damping(mut slf: PyRefMut<'_, Self>, damping: f64) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_initial_damping(damping);
         slf
     }
 
-    /// Abort the run if no improvement occurs for the given wall-clock duration.
+    /// Abort the run once the patience window has elapsed.
     fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
         slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
         slf
     }
 
-    /// Specify the number of offspring evaluated per generation.
-    fn with_population_size(
-        mut slf: PyRefMut<'_, Self>,
-        population_size: usize,
-    ) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_population_size(population_size);
+    /// Record a per-iteration trace in `OptimisationResults.history`.
+    fn with_history(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.inner = std::mem::take(&mut slf.inner).with_history(enabled);
         slf
     }
 
-    /// Initialise the internal RNG for reproducible runs.
-    fn with_seed(mut slf: PyRefMut<'_, Self>, seed: u64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_seed(seed);
-        slf
+    /// Call `observer(progress)` after every iteration; a truthy return value stops the run.
+    fn with_observer(
+        mut slf: PyRefMut<'_, Self>,
+        observer: Py<PyAny>,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let observer = observer_from_callable(observer)?;
+        slf.inner = std::mem::take(&mut slf.inner).with_observer(observer);
+        Ok(slf)
     }
 
-    /// Optimise the given problem starting from the provided mean vector.
+    /// Fit the residuals of the given problem starting from the provided point.
     fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
         let result = self.inner.run(&problem.inner, initial);
         PyOptimisationResults { inner: result }
@@ -1116,72 +2869,185 @@ impl PyCMAES {
 }
 
 // ============================================================================
-// Adam Optimiser
-// ============================================================================
+// Optimisation Results
+// ====================================================================================
 
-/// Adaptive Moment Estimation (Adam) gradient-based optimiser.
+/// Optimiser progress passed to observer callables after each iteration.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
-#[pyclass(name = "Adam")]
-#[derive(Clone)]
-pub struct PyAdam {
-    inner: Adam,
+#[pyclass(name = "IterationProgress")]
+pub struct PyIterationProgress {
+    inner: IterationProgress,
 }
 
 #[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
 #[pymethods]
-impl PyAdam {
-    /// Create an Adam optimiser with library defaults.
-    #[new]
-    fn new() -> Self {
-        Self { inner: Adam::new() }
+impl PyIterationProgress {
+    /// Iteration number, starting at 1.
+    #[getter]
+    fn iteration(&self) -> usize {
+        self.inner.iteration
     }
 
-    /// Limit the maximum number of optimisation iterations.
-    fn with_max_iter(mut slf: PyRefMut<'_, Self>, max_iter: usize) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_max_iter(max_iter);
-        slf
+    /// Objective evaluations performed so far.
+    #[getter]
+    fn nfev(&self) -> usize {
+        self.inner.nfev
     }
 
-    /// Set the stopping threshold on the gradient norm.
-    fn with_threshold(mut slf: PyRefMut<'_, Self>, threshold: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_threshold(threshold);
-        slf
+    /// Best decision vector found so far.
+    #[getter]
+    fn x(&self) -> Vec<f64> {
+        self.inner.x.clone()
     }
 
-    /// Configure the base learning rate / step size.
-    fn with_step_size(mut slf: PyRefMut<'_, Self>, step_size: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_step_size(step_size);
-        slf
+    /// Objective value at `x`.
+    #[getter]
+    fn fun(&self) -> f64 {
+        self.inner.fun
     }
 
-    /// Override the exponential decay rates for the first and second moments.
-    fn with_betas(mut slf: PyRefMut<'_, Self>, beta1: f64, beta2: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_betas(beta1, beta2);
-        slf
+    /// CMA-ES step-size, Nelder-Mead simplex radius or length of the latest Adam update.
+    #[getter]
+    fn step_size(&self) -> f64 {
+        self.inner.step_size
     }
 
-    /// Override the numerical stability constant added to the denominator.
-    fn with_eps(mut slf: PyRefMut<'_, Self>, eps: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_eps(eps);
-        slf
+    /// Wall-clock time since the run started.
+    #[getter]
+    fn elapsed(&self) -> Duration {
+        self.inner.elapsed
     }
 
-    /// Abort the run once the patience window has elapsed.
-    fn with_patience(mut slf: PyRefMut<'_, Self>, patience_seconds: f64) -> PyRefMut<'_, Self> {
-        slf.inner = std::mem::take(&mut slf.inner).with_patience(patience_seconds);
-        slf
+    fn __repr__(&self) -> String {
+        format!(
+            "IterationProgress(iteration={}, nfev={}, fun={:.6}, step_size={:.3e})",
+            self.inner.iteration, self.inner.nfev, self.inner.fun, self.inner.step_size
+        )
     }
+}
 
-    /// Optimise the given problem using Adam starting from the provided point.
-    fn run(&self, problem: &PyProblem, initial: Vec<f64>) -> PyOptimisationResults {
-        let result = self.inner.run(&problem.inner, initial);
-        PyOptimisationResults { inner: result }
+/// Per-iteration optimisation trace returned as NumPy arrays.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "OptimisationHistory")]
+pub struct PyOptimisationHistory {
+    inner: OptimisationHistory,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyOptimisationHistory {
+    /// Best objective value after each iteration.
+    #[getter]
+    fn fun<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.fun)
+    }
+
+    /// Best decision vector after each iteration, one row per iteration.
+    #[getter]
+    fn x<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        PyArray2::from_vec2(py, &self.inner.x).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Cumulative objective evaluations after each iteration.
+    #[getter]
+    fn nfev<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<usize>> {
+        PyArray1::from_slice(py, &self.inner.nfev)
+    }
+
+    /// CMA-ES step-size, Nelder-Mead simplex radius or Adam update length per iteration.
+    #[getter]
+    fn step_size<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.step_size)
+    }
+
+    /// Gradient norm per iteration, recorded by gradient-based optimisers only.
+    #[getter]
+    fn gradient_norm<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray1<f64>>> {
+        self.inner
+            .gradient_norm
+            .as_ref()
+            .map(|norms| PyArray1::from_slice(py, norms))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OptimisationHistory(iterations={})", self.inner.len())
     }
 }
 
-// ============================================================================
-// Optimisation Results
-// ====================================================================================
+/// Summary of a single CMA-ES run within a restart sequence.
+#[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
+#[pyclass(name = "RestartRecord")]
+pub struct PyRestartRecord {
+    inner: RestartRecord,
+}
+
+#[cfg_attr(feature = "stubgen", gen_stub_pymethods)]
+#[pymethods]
+impl PyRestartRecord {
+    /// Population size used by this run.
+    #[getter]
+    fn population_size(&self) -> usize {
+        self.inner.population_size
+    }
+
+    /// Initial step-size used by this run.
+    #[getter]
+    fn sigma0(&self) -> f64 {
+        self.inner.sigma0
+    }
+
+    /// Starting point of this run.
+    #[getter]
+    fn x0(&self) -> Vec<f64> {
+        self.inner.x0.clone()
+    }
+
+    /// Best decision vector found during this run.
+    #[getter]
+    fn x(&self) -> Vec<f64> {
+        self.inner.x.clone()
+    }
+
+    /// Objective value at `x`.
+    #[getter]
+    fn fun(&self) -> f64 {
+        self.inner.fun
+    }
+
+    /// Number of generations performed by this run.
+    #[getter]
+    fn nit(&self) -> usize {
+        self.inner.nit
+    }
+
+    /// Number of objective evaluations performed by this run.
+    #[getter]
+    fn nfev(&self) -> usize {
+        self.inner.nfev
+    }
+
+    /// Reason this run stopped.
+    #[getter]
+    fn termination_reason(&self) -> String {
+        self.inner.termination_reason.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RestartRecord(population_size={}, sigma0={}, fun={:.6}, nit={}, nfev={}, reason={})",
+            self.inner.population_size,
+            self.inner.sigma0,
+            self.inner.fun,
+            self.inner.nit,
+            self.inner.nfev,
+            self.inner.termination_reason
+        )
+    }
+}
 
 /// Container for optimiser outputs and diagnostic metadata.
 #[cfg_attr(feature = "stubgen", gen_stub_pyclass)]
@@ -1253,12 +3119,32 @@ impl PyOptimisationResults {
         self.inner.final_simplex_values.clone()
     }
 
-    /// Estimated covariance of the search distribution, if available.
+    /// Estimated covariance of the search distribution or fitted parameters, if available.
     #[getter]
     fn covariance(&self) -> Option<Vec<Vec<f64>>> {
         self.inner.covariance.clone()
     }
 
+    /// Per-run summaries recorded by CMA-ES restart strategies.
+    #[getter]
+    fn restarts(&self) -> Vec<PyRestartRecord> {
+        self.inner
+            .restarts
+            .iter()
+            .cloned()
+            .map(|inner| PyRestartRecord { inner })
+            .collect()
+    }
+
+    /// Per-iteration trace, present when the optimiser was built `with_history(True)`.
+    #[getter]
+    fn history(&self) -> Option<PyOptimisationHistory> {
+        self.inner
+            .history
+            .clone()
+            .map(|inner| PyOptimisationHistory { inner })
+    }
+
     /// Render a concise summary of the optimisation outcome.
     fn __repr__(&self) -> String {
         format!(
@@ -1331,14 +3217,31 @@ fn chronopt(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyNelderMead>()?;
     m.add_class::<PyCMAES>()?;
     m.add_class::<PyAdam>()?;
+    m.add_class::<PyLBFGSB>()?;
+    m.add_class::<PyLevenbergMarquardt>()?;
+    m.add_class::<PyDifferentialEvolution>()?;
+    m.add_class::<PyPSO>()?;
+    m.add_class::<PyNelderMeadState>()?;
+    m.add_class::<PyCMAESState>()?;
+    m.add_class::<PyAdamState>()?;
+    m.add_class::<PyIterationProgress>()?;
+    m.add_class::<PyRestartRecord>()?;
     m.add_class::<PyOptimisationResults>()?;
+    m.add_class::<PyOptimisationHistory>()?;
     m.add_class::<PyDiffsolBuilder>()?;
     m.add_class::<PyVectorBuilder>()?;
     m.add_class::<PyCostMetric>()?;
+    m.add_class::<PyPrior>()?;
     m.add_class::<PySamples>()?;
     m.add_class::<PyNestedSamples>()?;
     m.add_class::<PyMetropolisHastings>()?;
     m.add_class::<PyDynamicNestedSampler>()?;
+    m.add_class::<PyHamiltonianMonteCarlo>()?;
+    m.add_class::<PyEnsembleSampler>()?;
+    m.add_class::<PyParallelTempering>()?;
+    m.add_class::<PyTemperedSamples>()?;
+    m.add_class::<PySequentialMonteCarlo>()?;
+    m.add_class::<PyParticleSamples>()?;
 
     // Builder submodule
     let builder_module = PyModule::new(py, "builder")?;
@@ -1366,7 +3269,13 @@ fn chronopt(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     let sampler_module = PyModule::new(py, "sampler")?;
     sampler_module.add_class::<PyMetropolisHastings>()?;
     sampler_module.add_class::<PyDynamicNestedSampler>()?;
+    sampler_module.add_class::<PyHamiltonianMonteCarlo>()?;
+    sampler_module.add_class::<PyEnsembleSampler>()?;
+    sampler_module.add_class::<PyParallelTempering>()?;
+    sampler_module.add_class::<PySequentialMonteCarlo>()?;
     sampler_module.add_class::<PyNestedSamples>()?;
+    sampler_module.add_class::<PyTemperedSamples>()?;
+    sampler_module.add_class::<PyParticleSamples>()?;
     sampler_module.add_class::<PySamples>()?;
     m.add_submodule(&sampler_module)?;
     m.setattr("sampler", &sampler_module)?;
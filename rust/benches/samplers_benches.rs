@@ -27,7 +27,7 @@ fn bench_metropolis_hastings_gaussian(c: &mut Criterion) {
         let sampler = sampler.clone();
         let initial = initial.clone();
         b.iter(|| {
-            let samples = sampler.run(problem, black_box(initial.clone()));
+            let samples = sampler.run(problem, black_box(initial.clone())).unwrap();
             black_box(samples.draws());
         });
     });
@@ -47,15 +47,68 @@ fn bench_dynamic_nested_gaussian(c: &mut Criterion) {
         let sampler = sampler.clone();
         let initial = initial.clone();
         b.iter(|| {
-            let nested = sampler.run_nested(problem, black_box(initial.clone()));
+            let nested = sampler
+                .run_nested(problem, black_box(initial.clone()))
+                .unwrap();
             black_box((nested.draws(), nested.log_evidence()));
         });
     });
 }
 
+fn bench_hamiltonian_gaussian(c: &mut Criterion) {
+    let problem = ScalarProblemBuilder::new()
+        .with_objective_and_gradient(
+            |x: &[f64]| {
+                let diff = x[0] - 0.5;
+                0.5 * diff * diff
+            },
+            |x: &[f64]| vec![x[0] - 0.5],
+        )
+        .with_parameter(ParameterSpec::new("x", 0.6, Some((-5.0, 5.0))))
+        .build()
+        .expect("failed to build gaussian problem");
+    let sampler = HamiltonianMonteCarlo::new()
+        .with_num_chains(4)
+        .with_warmup(200)
+        .with_iterations(500)
+        .with_seed(42);
+    let initial = vec![0.5_f64];
+
+    c.bench_function("hamiltonian_gaussian", move |b| {
+        let problem = &problem;
+        let sampler = sampler.clone();
+        let initial = initial.clone();
+        b.iter(|| {
+            let samples = sampler.run(problem, black_box(initial.clone())).unwrap();
+            black_box(samples.draws());
+        });
+    });
+}
+
+fn bench_ensemble_gaussian(c: &mut Criterion) {
+    let problem = gaussian_problem();
+    let sampler = EnsembleSampler::new()
+        .with_walkers(8)
+        .with_iterations(500)
+        .with_seed(42);
+    let initial = vec![0.5_f64];
+
+    c.bench_function("ensemble_gaussian", move |b| {
+        let problem = &problem;
+        let sampler = sampler.clone();
+        let initial = initial.clone();
+        b.iter(|| {
+            let samples = sampler.run(problem, black_box(initial.clone())).unwrap();
+            black_box(samples.draws());
+        });
+    });
+}
+
 fn sampler_benches(c: &mut Criterion) {
     bench_metropolis_hastings_gaussian(c);
     bench_dynamic_nested_gaussian(c);
+    bench_hamiltonian_gaussian(c);
+    bench_ensemble_gaussian(c);
 }
 
 criterion_group!(name = samplers; config = Criterion::default().measurement_time(Duration::from_secs(10)); targets = sampler_benches);
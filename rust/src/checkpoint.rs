@@ -0,0 +1,362 @@
+//! Plain-text snapshots used to checkpoint and resume long optimiser and sampler runs.
+//!
+//! A checkpoint is a header line followed by one `key value...` record per line.
+//! Floating-point values are written in their shortest round-trip form and RNG
+//! streams by seed and word position, so a resumed run continues bit-for-bit.
+
+use nalgebra::{DMatrix, DVector};
+use rand_chacha::ChaCha12Rng;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+const HEADER: &str = "chronopt-checkpoint";
+const VERSION: u32 = 1;
+
+/// Random number generator used by checkpointable algorithms.
+///
+/// This is the generator behind `rand::rngs::StdRng`, so seeded streams are unchanged,
+/// but unlike `StdRng` its position can be captured and restored.
+pub type CheckpointRng = ChaCha12Rng;
+
+/// Where and how often a run writes its checkpoint.
+#[derive(Clone, Debug)]
+pub struct CheckpointConfig {
+    path: PathBuf,
+    every: usize,
+}
+
+impl CheckpointConfig {
+    pub fn new(path: impl Into<PathBuf>, every: usize) -> Self {
+        Self {
+            path: path.into(),
+            every: every.max(1),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether a checkpoint is due after `iteration` completed iterations.
+    pub fn is_due(&self, iteration: usize) -> bool {
+        iteration > 0 && iteration.is_multiple_of(self.every)
+    }
+}
+
+/// Accumulates records for a checkpoint of the given kind.
+pub(crate) struct CheckpointWriter {
+    lines: Vec<String>,
+}
+
+impl CheckpointWriter {
+    pub fn new(kind: &str) -> Self {
+        Self {
+            lines: vec![format!("{} {} {}", HEADER, VERSION, kind)],
+        }
+    }
+
+    fn record<T: Display>(&mut self, key: &str, values: impl IntoIterator<Item = T>) {
+        let mut line = key.to_string();
+        for value in values {
+            line.push(' ');
+            line.push_str(&value.to_string());
+        }
+        self.lines.push(line);
+    }
+
+    pub fn flag(&mut self, key: &str, value: bool) {
+        self.record(key, [u8::from(value)]);
+    }
+
+    pub fn count(&mut self, key: &str, value: usize) {
+        self.record(key, [value]);
+    }
+
+    pub fn scalar(&mut self, key: &str, value: f64) {
+        self.record(key, [value]);
+    }
+
+    pub fn duration(&mut self, key: &str, value: Duration) {
+        self.record(key, [value.as_secs(), u64::from(value.subsec_nanos())]);
+    }
+
+    pub fn values(&mut self, key: &str, values: &[f64]) {
+        self.record(key, values);
+    }
+
+    pub fn vector(&mut self, key: &str, vector: &DVector<f64>) {
+        self.record(key, vector.iter());
+    }
+
+    pub fn rows(&mut self, key: &str, rows: &[Vec<f64>]) {
+        self.count(&format!("{}.rows", key), rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            self.values(&format!("{}.{}", key, i), row);
+        }
+    }
+
+    pub fn matrix(&mut self, key: &str, matrix: &DMatrix<f64>) {
+        self.record(&format!("{}.shape", key), [matrix.nrows(), matrix.ncols()]);
+        self.record(key, matrix.iter());
+    }
+
+    pub fn rng(&mut self, key: &str, rng: &CheckpointRng) {
+        let seed: String = rng
+            .get_seed()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        self.lines.push(format!(
+            "{} {} {} {}",
+            key,
+            seed,
+            rng.get_stream(),
+            rng.get_word_pos()
+        ));
+    }
+
+    /// Write the checkpoint, replacing any previous file only once it is complete.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut contents = self.lines.join("\n");
+        contents.push('\n');
+
+        let mut staging = path.as_os_str().to_owned();
+        staging.push(".tmp");
+        let staging = PathBuf::from(staging);
+
+        fs::write(&staging, contents)
+            .and_then(|_| fs::rename(&staging, path))
+            .map_err(|err| format!("Failed to write checkpoint {}: {}", path.display(), err))
+    }
+}
+
+/// Records read back from a checkpoint file.
+pub(crate) struct Checkpoint {
+    records: HashMap<String, Vec<String>>,
+}
+
+impl Checkpoint {
+    /// Load a checkpoint, checking that it was written by an algorithm of `kind`.
+    pub fn load(path: &Path, kind: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read checkpoint {}: {}", path.display(), err))?;
+        let mut lines = contents.lines();
+
+        let header: Vec<&str> = lines
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+        match header.as_slice() {
+            [HEADER, version, found] if *version == VERSION.to_string() => {
+                if *found != kind {
+                    return Err(format!(
+                        "Checkpoint {} was written by {}, not {}",
+                        path.display(),
+                        found,
+                        kind
+                    ));
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "{} is not a chronopt checkpoint (version {})",
+                    path.display(),
+                    VERSION
+                ))
+            }
+        }
+
+        let records = lines
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut tokens = line.split_whitespace().map(str::to_string);
+                let key = tokens.next().unwrap_or_default();
+                (key, tokens.collect())
+            })
+            .collect();
+
+        Ok(Self { records })
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.records.contains_key(key)
+    }
+
+    fn tokens(&self, key: &str) -> Result<&[String], String> {
+        self.records
+            .get(key)
+            .map(Vec::as_slice)
+            .ok_or_else(|| format!("Checkpoint is missing '{}'", key))
+    }
+
+    fn parse_all<T: FromStr>(&self, key: &str) -> Result<Vec<T>, String> {
+        self.tokens(key)?
+            .iter()
+            .map(|token| {
+                token
+                    .parse()
+                    .map_err(|_| format!("Invalid value '{}' for '{}' in checkpoint", token, key))
+            })
+            .collect()
+    }
+
+    fn parse_one<T: FromStr>(&self, key: &str) -> Result<T, String> {
+        let mut values = self.parse_all(key)?;
+        if values.len() != 1 {
+            return Err(format!(
+                "Expected a single value for '{}' in checkpoint",
+                key
+            ));
+        }
+        Ok(values.remove(0))
+    }
+
+    pub fn flag(&self, key: &str) -> Result<bool, String> {
+        Ok(self.parse_one::<u8>(key)? != 0)
+    }
+
+    pub fn count(&self, key: &str) -> Result<usize, String> {
+        self.parse_one(key)
+    }
+
+    pub fn scalar(&self, key: &str) -> Result<f64, String> {
+        self.parse_one(key)
+    }
+
+    pub fn duration(&self, key: &str) -> Result<Duration, String> {
+        match self.parse_all::<u64>(key)?.as_slice() {
+            [secs, nanos] => Ok(Duration::new(*secs, *nanos as u32)),
+            _ => Err(format!("Expected seconds and nanoseconds for '{}'", key)),
+        }
+    }
+
+    pub fn values(&self, key: &str) -> Result<Vec<f64>, String> {
+        self.parse_all(key)
+    }
+
+    /// Read a vector of exactly `len` entries.
+    pub fn vector(&self, key: &str, len: usize) -> Result<DVector<f64>, String> {
+        let values = self.values(key)?;
+        if values.len() != len {
+            return Err(format!(
+                "Expected {} values for '{}' in checkpoint, found {}",
+                len,
+                key,
+                values.len()
+            ));
+        }
+        Ok(DVector::from_vec(values))
+    }
+
+    pub fn rows(&self, key: &str) -> Result<Vec<Vec<f64>>, String> {
+        let count = self.count(&format!("{}.rows", key))?;
+        (0..count)
+            .map(|i| self.values(&format!("{}.{}", key, i)))
+            .collect()
+    }
+
+    pub fn matrix(&self, key: &str) -> Result<DMatrix<f64>, String> {
+        let shape: Vec<usize> = self.parse_all(&format!("{}.shape", key))?;
+        let values = self.values(key)?;
+        match shape.as_slice() {
+            [nrows, ncols] if nrows * ncols == values.len() => {
+                Ok(DMatrix::from_vec(*nrows, *ncols, values))
+            }
+            _ => Err(format!("Checkpoint matrix '{}' has an invalid shape", key)),
+        }
+    }
+
+    pub fn rng(&self, key: &str) -> Result<CheckpointRng, String> {
+        let invalid = || format!("Invalid RNG state for '{}' in checkpoint", key);
+        let tokens = self.tokens(key)?;
+        let [seed_hex, stream, word_pos] = tokens else {
+            return Err(invalid());
+        };
+        if seed_hex.len() != 64 {
+            return Err(invalid());
+        }
+
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&seed_hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+
+        let mut rng = <CheckpointRng as rand::SeedableRng>::from_seed(seed);
+        rng.set_stream(stream.parse().map_err(|_| invalid())?);
+        rng.set_word_pos(word_pos.parse().map_err(|_| invalid())?);
+        Ok(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn checkpoint_rng_matches_std_rng_stream() {
+        let mut std_rng = StdRng::seed_from_u64(17);
+        let mut rng = CheckpointRng::seed_from_u64(17);
+        for _ in 0..8 {
+            assert_eq!(std_rng.random::<u64>(), rng.random::<u64>());
+        }
+    }
+
+    #[test]
+    fn records_round_trip_exactly() {
+        let path = std::env::temp_dir().join(format!(
+            "chronopt-checkpoint-roundtrip-{}.txt",
+            std::process::id()
+        ));
+
+        let mut rng = CheckpointRng::seed_from_u64(5);
+        let _: f64 = rng.random();
+        let matrix =
+            DMatrix::from_row_slice(2, 3, &[0.1, -2.5e-300, 3.0, f64::INFINITY, 5.0, -0.0]);
+        let values = [std::f64::consts::PI, f64::NAN, 1.0 / 3.0];
+
+        let mut writer = CheckpointWriter::new("test");
+        writer.flag("flag", true);
+        writer.count("count", 42);
+        writer.scalar("scalar", -1e-17);
+        writer.duration("elapsed", Duration::new(3, 250));
+        writer.values("values", &values);
+        writer.rows("rows", &[vec![1.0, 2.0], vec![], vec![3.5]]);
+        writer.matrix("matrix", &matrix);
+        writer.rng("rng", &rng);
+        writer.save(&path).unwrap();
+
+        let checkpoint = Checkpoint::load(&path, "test").unwrap();
+        assert!(checkpoint.flag("flag").unwrap());
+        assert_eq!(checkpoint.count("count").unwrap(), 42);
+        assert_eq!(checkpoint.scalar("scalar").unwrap(), -1e-17);
+        assert_eq!(
+            checkpoint.duration("elapsed").unwrap(),
+            Duration::new(3, 250)
+        );
+
+        let restored = checkpoint.values("values").unwrap();
+        assert_eq!(restored[0], values[0]);
+        assert!(restored[1].is_nan());
+        assert_eq!(restored[2], values[2]);
+
+        assert_eq!(
+            checkpoint.rows("rows").unwrap(),
+            vec![vec![1.0, 2.0], vec![], vec![3.5]]
+        );
+        assert_eq!(checkpoint.matrix("matrix").unwrap(), matrix);
+
+        let mut restored_rng = checkpoint.rng("rng").unwrap();
+        assert_eq!(restored_rng.random::<u64>(), rng.random::<u64>());
+
+        assert!(Checkpoint::load(&path, "other").is_err());
+        assert!(checkpoint.count("missing").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
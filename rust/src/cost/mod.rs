@@ -6,6 +6,18 @@ pub trait CostMetric: Send + Sync {
     fn evaluate(&self, residuals: &[f64]) -> f64;
     fn name(&self) -> &'static str;
 
+    /// Whether `evaluate` is a normalised negative log-likelihood of the residuals, so
+    /// that `exp(-cost)` is a proper likelihood the samplers can target.
+    fn is_negative_log_likelihood(&self) -> bool {
+        false
+    }
+
+    /// Factor `s` such that `evaluate` is `s` times the sum of squared residuals plus a
+    /// constant, for metrics that least-squares optimisers can minimise directly.
+    fn sum_of_squares_scale(&self) -> Option<f64> {
+        None
+    }
+
     fn evaluate_with_sensitivities(
         &self,
         _residuals: &[f64],
@@ -43,6 +55,10 @@ impl CostMetric for SumSquaredError {
         "sse"
     }
 
+    fn sum_of_squares_scale(&self) -> Option<f64> {
+        Some(self.weight)
+    }
+
     fn evaluate_with_sensitivities(
         &self,
         residuals: &[f64],
@@ -234,6 +250,15 @@ impl CostMetric for GaussianNll {
         "gaussian_nll"
     }
 
+    /// A weight other than one tempers the likelihood, which the samplers then target.
+    fn is_negative_log_likelihood(&self) -> bool {
+        true
+    }
+
+    fn sum_of_squares_scale(&self) -> Option<f64> {
+        Some(0.5 * self.weight / self.variance)
+    }
+
     fn evaluate_with_sensitivities(
         &self,
         residuals: &[f64],
@@ -415,4 +440,26 @@ mod tests {
         let expected_grad = (1.0 * 0.5 + 2.0 * 0.5) / variance;
         assert!((grad[0] - expected_grad).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_sum_of_squares_scale() {
+        let residuals = [1.0, -2.0, 0.5];
+        let sse: f64 = residuals.iter().map(|r| r * r).sum();
+
+        let metric = SumSquaredError::new(Some(3.0));
+        let scale = metric.sum_of_squares_scale().unwrap();
+        assert!((metric.evaluate(&residuals) - scale * sse).abs() < 1e-12);
+
+        // The Gaussian NLL differs from its scaled sum of squares by a constant.
+        let metric = GaussianNll::new(Some(2.0), 0.25);
+        let scale = metric.sum_of_squares_scale().unwrap();
+        let shifted: Vec<f64> = residuals.iter().map(|r| 2.0 * r).collect();
+        let shifted_sse: f64 = shifted.iter().map(|r| r * r).sum();
+        let difference = metric.evaluate(&shifted) - metric.evaluate(&residuals);
+        assert!((difference - scale * (shifted_sse - sse)).abs() < 1e-10);
+
+        assert!(RootMeanSquaredError::default()
+            .sum_of_squares_scale()
+            .is_none());
+    }
 }
@@ -1,3 +1,4 @@
+mod checkpoint;
 pub mod cost;
 pub mod optimisers;
 pub mod problem;
@@ -6,16 +7,20 @@ pub mod sampler;
 // Convenience re-exports so users can `use chronopt::prelude::*;`
 pub mod prelude {
     pub use crate::optimisers::{
-        Adam, NelderMead, OptimisationResults, Optimiser, WithMaxIter, WithPatience, WithSigma0,
-        WithThreshold, CMAES,
+        Adam, AdamState, CMAESState, DEStrategy, DifferentialEvolution, IterationProgress,
+        LevenbergMarquardt, NelderMead, NelderMeadState, ObserverAction, OptimisationHistory,
+        OptimisationObserver, OptimisationResults, Optimiser, RestartRecord, RestartStrategy,
+        WithMaxIter, WithPatience, WithSigma0, WithThreshold, CMAES, LBFGSB, PSO,
     };
     pub use crate::problem::builders::{BuilderOptimiserExt, BuilderParameterExt};
     pub use crate::problem::{
-        DiffsolProblemBuilder, ParameterSet, ParameterSpec, Problem, ScalarProblemBuilder,
-        VectorProblemBuilder,
+        DiffsolExperiment, DiffsolProblemBuilder, LogPosterior, ObservedOutput, ParameterSet,
+        ParameterSpec, Prior, Problem, ScalarProblemBuilder, VectorProblemBuilder,
     };
     pub use crate::sampler::{
-        DynamicNestedSampler, MetropolisHastings, NestedSample, NestedSamples, Sampler, Samples,
+        Bounding, DynamicNestedSampler, EnsembleSampler, HamiltonianMonteCarlo, MassMatrix,
+        MetropolisHastings, NestedProposal, NestedSample, NestedSamples, ParallelTempering,
+        ParticleSamples, Sampler, Samples, SequentialMonteCarlo, TemperedSamples, Trajectory,
     };
 }
 
@@ -5,9 +5,11 @@ use crate::cost::{CostMetric, SumSquaredError};
 use crate::optimisers::Optimiser;
 use nalgebra::DMatrix;
 
+use super::dsl;
+use super::prior::Prior;
 use super::{
-    CallableObjective, GradientFn, ObjectiveFn, Problem, ProblemKind, SharedOptimiser,
-    VectorObjectiveFn,
+    CallableObjective, Experiment, GradientFn, ModelInput, ObjectiveFn, Problem, ProblemKind,
+    SharedOptimiser, VectorObjectiveFn,
 };
 
 type SharedObjectiveFn = Arc<dyn Fn(&[f64]) -> f64 + Send + Sync>;
@@ -89,6 +91,7 @@ pub struct ParameterSpec {
     pub name: String,
     pub initial_value: f64,
     pub bounds: Option<(f64, f64)>,
+    pub prior: Option<Prior>,
 }
 
 impl ParameterSpec {
@@ -100,8 +103,15 @@ impl ParameterSpec {
             name: name.into(),
             initial_value,
             bounds,
+            prior: None,
         }
     }
+
+    /// Attach a prior distribution, used by the samplers to target the posterior.
+    pub fn with_prior(mut self, prior: Prior) -> Self {
+        self.prior = Some(prior);
+        self
+    }
 }
 
 #[derive(Clone, Default)]
@@ -139,6 +149,35 @@ impl ParameterSet {
     pub fn iter(&self) -> std::slice::Iter<'_, ParameterSpec> {
         self.0.iter()
     }
+
+    /// Returns true when at least one parameter carries a prior.
+    pub fn has_priors(&self) -> bool {
+        self.0.iter().any(|spec| spec.prior.is_some())
+    }
+
+    /// Sum of the parameter priors' log-densities at `x`.
+    ///
+    /// Parameters without a prior contribute nothing (a flat prior).
+    pub fn log_prior(&self, x: &[f64]) -> f64 {
+        self.0
+            .iter()
+            .zip(x)
+            .filter_map(|(spec, &value)| spec.prior.map(|prior| prior.log_density(value)))
+            .sum()
+    }
+
+    /// Gradient of [`ParameterSet::log_prior`] with respect to `x`.
+    pub fn log_prior_gradient(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                self.0
+                    .get(i)
+                    .and_then(|spec| spec.prior)
+                    .map_or(0.0, |prior| prior.log_density_gradient(value))
+            })
+            .collect()
+    }
 }
 
 pub trait BuilderWithParameters {
@@ -351,6 +390,7 @@ impl BuilderWithOptimiser for ScalarProblemBuilder {
 pub struct VectorProblemBuilder {
     objective: Option<SharedVectorObjectiveFn>,
     data: Option<Vec<f64>>,
+    mask: Option<Vec<bool>>,
     shape: Option<Vec<usize>>,
     config: HashMap<String, f64>,
     parameters: ParameterSet,
@@ -363,6 +403,7 @@ impl VectorProblemBuilder {
         Self {
             objective: None,
             data: None,
+            mask: None,
             shape: None,
             config: HashMap::new(),
             parameters: ParameterSet::default(),
@@ -380,7 +421,7 @@ impl VectorProblemBuilder {
         self
     }
 
-    /// Stores the time-series observations
+    /// Stores the time-series observations; NaN entries are treated as missing.
     pub fn with_data(mut self, data: Vec<f64>) -> Self {
         let shape = vec![data.len()];
         self.data = Some(data);
@@ -388,6 +429,20 @@ impl VectorProblemBuilder {
         self
     }
 
+    /// Marks which observations are present (`true`) or missing (`false`).
+    ///
+    /// Missing observations are left out of the residuals and every cost metric.
+    pub fn with_mask(mut self, mask: Vec<bool>) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Treats every non-NaN observation as present again.
+    pub fn remove_mask(mut self) -> Self {
+        self.mask = None;
+        self
+    }
+
     /// Stores an optimisation configuration value keyed by name.
     pub fn with_config(mut self, key: String, value: f64) -> Self {
         self.config.insert(key, value);
@@ -420,11 +475,25 @@ impl VectorProblemBuilder {
             .as_ref()
             .cloned()
             .ok_or_else(|| "Vector objective must be provided".to_string())?;
-        let data = self
+        let mut data = self
             .data
             .as_ref()
             .cloned()
             .ok_or_else(|| "Observed data must be provided".to_string())?;
+        if let Some(mask) = &self.mask {
+            if mask.len() != data.len() {
+                return Err(format!(
+                    "Mask length {} does not match data length {}",
+                    mask.len(),
+                    data.len()
+                ));
+            }
+            for (value, &observed) in data.iter_mut().zip(mask) {
+                if !observed {
+                    *value = f64::NAN;
+                }
+            }
+        }
         let shape = self.shape.clone().unwrap_or_default();
 
         let objective_box: VectorObjectiveFn = Box::new(move |params: &[f64]| objective(params));
@@ -472,10 +541,254 @@ impl BuilderWithOptimiser for VectorProblemBuilder {
     }
 }
 
+/// A DiffSL model output compared against one data column, chosen by its position
+/// in the `out_i` block or by name.
+///
+/// Named outputs are the labels of `out_i` entries (`total = x + y`) or entries that
+/// are a bare identifier; without an `out_i` block the outputs are the states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObservedOutput {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for ObservedOutput {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<&str> for ObservedOutput {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+impl From<String> for ObservedOutput {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+/// Resolves observed outputs to their positions in the model output vector.
+fn observed_indices(program: &str, observations: &[ObservedOutput]) -> Result<Vec<usize>, String> {
+    let names = if observations
+        .iter()
+        .any(|output| matches!(output, ObservedOutput::Name(_)))
+    {
+        dsl::output_names(program)?
+    } else {
+        Vec::new()
+    };
+
+    observations
+        .iter()
+        .map(|output| match output {
+            ObservedOutput::Index(index) => Ok(*index),
+            ObservedOutput::Name(name) => names
+                .iter()
+                .position(|candidate| candidate.as_deref() == Some(name.as_str()))
+                .ok_or_else(|| format!("Output '{}' is not defined by the DiffSL program", name)),
+        })
+        .collect()
+}
+
+/// Observations from one experimental condition, fitted jointly with the others
+/// registered on a [`DiffsolProblemBuilder`].
+///
+/// The data layout matches [`DiffsolProblemBuilder::with_data`]: the first column holds
+/// the time samples and the remaining columns the observed trajectories. Inputs and
+/// initial states may be fixed to condition-specific values; any other inputs are
+/// taken from the shared parameter vector.
+#[derive(Clone)]
+pub struct DiffsolExperiment {
+    data: DMatrix<f64>,
+    inputs: Vec<(String, f64)>,
+    initial_conditions: Vec<(String, f64)>,
+    mask: Option<DMatrix<bool>>,
+    observations: Vec<ObservedOutput>,
+    cost_metrics: Vec<Arc<dyn CostMetric>>,
+}
+
+impl DiffsolExperiment {
+    /// Creates an experiment from data whose first column is the time span.
+    ///
+    /// NaN observations are treated as missing.
+    pub fn new(data: DMatrix<f64>) -> Self {
+        Self {
+            data,
+            inputs: Vec::new(),
+            initial_conditions: Vec::new(),
+            mask: None,
+            observations: Vec::new(),
+            cost_metrics: Vec::new(),
+        }
+    }
+
+    /// Fixes the DiffSL input `name` to `value` for this experiment.
+    ///
+    /// Inputs fixed in every experiment are dropped from the shared parameter vector.
+    pub fn with_input<N>(mut self, name: N, value: f64) -> Self
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        self.inputs.retain(|(existing, _)| *existing != name);
+        self.inputs.push((name, value));
+        self
+    }
+
+    /// Overrides the initial value of the named state in the `u_i` block.
+    pub fn with_initial_condition<N>(mut self, state: N, value: f64) -> Self
+    where
+        N: Into<String>,
+    {
+        let state = state.into();
+        self.initial_conditions
+            .retain(|(existing, _)| *existing != state);
+        self.initial_conditions.push((state, value));
+        self
+    }
+
+    /// Marks which observations are present (`true`) or missing (`false`).
+    ///
+    /// The mask has one row per time point and one column per observed column, i.e.
+    /// the shape of the data without its time column.
+    pub fn with_mask(mut self, mask: DMatrix<bool>) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Maps the data columns of this experiment onto model outputs, replacing the
+    /// builder's observation mapping.
+    pub fn with_observations<I, O>(mut self, outputs: I) -> Self
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<ObservedOutput>,
+    {
+        self.observations = outputs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a cost metric for this experiment, replacing the builder's metrics.
+    pub fn with_cost_metric<M>(mut self, cost_metric: M) -> Self
+    where
+        M: CostMetric + 'static,
+    {
+        self.cost_metrics.push(Arc::new(cost_metric));
+        self
+    }
+
+    /// Directly add a cost metric from a trait object.
+    pub fn with_cost_metric_arc(mut self, cost_metric: Arc<dyn CostMetric>) -> Self {
+        self.cost_metrics.push(cost_metric);
+        self
+    }
+
+    fn fixed_input(&self, name: &str) -> Option<f64> {
+        self.inputs
+            .iter()
+            .find(|(input, _)| input == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// Writes the experiment's initial states into `program` and splits off the time
+    /// column. Fixed inputs are left to [`DiffsolProblemBuilder::build`].
+    fn resolve(
+        &self,
+        program: &str,
+        default_observations: &[ObservedOutput],
+        default_metrics: &[Arc<dyn CostMetric>],
+    ) -> Result<Experiment, String> {
+        if self.data.ncols() < 2 {
+            return Err(
+                "Data must include at least two columns: t_span followed by observed values"
+                    .to_string(),
+            );
+        }
+        let t_span: Vec<f64> = self.data.column(0).iter().cloned().collect();
+        if t_span.iter().any(|t| t.is_nan()) {
+            return Err("Time column must not contain missing values".to_string());
+        }
+        let mut data = self.data.columns(1, self.data.ncols() - 1).into_owned();
+        if let Some(mask) = &self.mask {
+            if mask.shape() != data.shape() {
+                return Err(format!(
+                    "Mask shape {}x{} does not match observed data shape {}x{}",
+                    mask.nrows(),
+                    mask.ncols(),
+                    data.nrows(),
+                    data.ncols()
+                ));
+            }
+            data.zip_apply(mask, |value, observed| {
+                if !observed {
+                    *value = f64::NAN;
+                }
+            });
+        }
+        if data.iter().all(|value| value.is_nan()) {
+            return Err("Data must contain at least one observed value".to_string());
+        }
+
+        if let Some((name, value)) = self.inputs.iter().find(|(_, value)| !value.is_finite()) {
+            return Err(format!(
+                "Input '{}' must be fixed to a finite value, got {}",
+                name, value
+            ));
+        }
+        let mut source = program.to_string();
+        for (state, value) in &self.initial_conditions {
+            if !value.is_finite() {
+                return Err(format!(
+                    "Initial condition of '{}' must be finite, got {}",
+                    state, value
+                ));
+            }
+            source = dsl::replace_initial_state(&source, state, &dsl::literal(*value))?;
+        }
+
+        let observations = if self.observations.is_empty() {
+            default_observations
+        } else {
+            self.observations.as_slice()
+        };
+        if !observations.is_empty() && observations.len() != data.ncols() {
+            return Err(format!(
+                "Data has {} observed columns but {} outputs are mapped onto them",
+                data.ncols(),
+                observations.len()
+            ));
+        }
+
+        let cost_metrics = if self.cost_metrics.is_empty() {
+            default_metrics.to_vec()
+        } else {
+            self.cost_metrics.clone()
+        };
+
+        let observed = if observations.is_empty() {
+            None
+        } else {
+            Some(observed_indices(&source, observations)?)
+        };
+
+        let experiment = Experiment::new(source, t_span, data, cost_metrics);
+        Ok(match observed {
+            Some(outputs) => experiment.with_observed_outputs(outputs),
+            None => experiment,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct DiffsolProblemBuilder {
     dsl: Option<String>,
     data: Option<DMatrix<f64>>,
+    mask: Option<DMatrix<bool>>,
+    experiments: Vec<DiffsolExperiment>,
+    observations: Vec<ObservedOutput>,
+    initial_states: Vec<(String, ParameterSpec)>,
     config: DiffsolConfig,
     parameters: ParameterSet,
     optimiser_slot: OptimiserSlot,
@@ -488,6 +801,10 @@ impl DiffsolProblemBuilder {
         Self {
             dsl: None,
             data: None,
+            mask: None,
+            experiments: Vec::new(),
+            observations: Vec::new(),
+            initial_states: Vec::new(),
             config: DiffsolConfig::default(),
             parameters: ParameterSet::default(),
             optimiser_slot: OptimiserSlot::default(),
@@ -508,17 +825,88 @@ impl DiffsolProblemBuilder {
     }
 
     /// Supplies observed data used to fit the differential model.
+    ///
+    /// NaN observations are treated as missing and left out of every cost metric.
     pub fn with_data(mut self, data: DMatrix<f64>) -> Self {
         self.data = Some(data);
         self
     }
 
+    /// Marks which entries of the data are present (`true`) or missing (`false`).
+    ///
+    /// The mask has the shape of the data without its time column.
+    pub fn with_mask(mut self, mask: DMatrix<bool>) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Treats every non-NaN observation as present again.
+    pub fn remove_mask(mut self) -> Self {
+        self.mask = None;
+        self
+    }
+
     /// Removes any previously supplied observed data and associated time span.
     pub fn remove_data(mut self) -> Self {
         self.data = None;
         self
     }
 
+    /// Adds an experiment fitted jointly with the data and any other experiments.
+    ///
+    /// Experiments follow the data supplied through `with_data` in registration order,
+    /// share one parameter vector and sum their costs into a single problem.
+    pub fn with_experiment(mut self, experiment: DiffsolExperiment) -> Self {
+        self.experiments.push(experiment);
+        self
+    }
+
+    /// Removes all experiments added with `with_experiment`.
+    pub fn remove_experiments(mut self) -> Self {
+        self.experiments.clear();
+        self
+    }
+
+    /// Maps data columns onto DiffSL outputs: column `j` (after the time column) is
+    /// compared against `outputs[j]`, so unobserved states need no data columns.
+    pub fn with_observations<I, O>(mut self, outputs: I) -> Self
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<ObservedOutput>,
+    {
+        self.observations = outputs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Compares the full model output against the data again.
+    pub fn remove_observations(mut self) -> Self {
+        self.observations.clear();
+        self
+    }
+
+    /// Estimates the initial value of `state` alongside the model parameters.
+    ///
+    /// The state's `u_i` entry is rewritten to read a new DiffSL input named after
+    /// `spec`, so forward sensitivities cover it like any other parameter. Estimated
+    /// initial states follow the registered parameters in the parameter vector, in the
+    /// order they are declared here.
+    pub fn with_estimated_initial_state<N>(mut self, state: N, spec: ParameterSpec) -> Self
+    where
+        N: Into<String>,
+    {
+        let state = state.into();
+        self.initial_states
+            .retain(|(existing, _)| *existing != state);
+        self.initial_states.push((state, spec));
+        self
+    }
+
+    /// Keeps every initial state at the value written in the DiffSL program.
+    pub fn remove_estimated_initial_states(mut self) -> Self {
+        self.initial_states.clear();
+        self
+    }
+
     /// Sets the relative tolerance applied during integration.
     pub fn with_rtol(mut self, rtol: f64) -> Self {
         self.config.rtol = rtol;
@@ -578,31 +966,107 @@ impl DiffsolProblemBuilder {
     }
 
     /// Finalises the builder into an optimisation problem.
+    ///
+    /// The parameter vector holds the DiffSL inputs in declaration order, omitting any
+    /// input that every experiment fixes, followed by the estimated initial states. Fails
+    /// unless one parameter spec is supplied per entry of that vector.
     pub fn build(&self) -> Result<Problem, String> {
-        let dsl = self.dsl.clone().ok_or("DSL must be provided")?;
-        let data_with_t = self.data.as_ref().cloned().ok_or("Data must be provided")?;
-        if data_with_t.ncols() < 2 {
-            return Err(
-                "Data must include at least two columns: t_span followed by observed values"
-                    .to_string(),
-            );
+        let mut dsl = self.dsl.clone().ok_or("DSL must be provided")?;
+        let mut parameters = self.parameters.clone();
+        for (state, spec) in &self.initial_states {
+            if !spec.initial_value.is_finite() {
+                return Err(format!(
+                    "Initial value of '{}' must be finite, got {}",
+                    spec.name, spec.initial_value
+                ));
+            }
+            dsl = dsl::add_input(&dsl, &spec.name, &dsl::literal(spec.initial_value))?;
+            dsl = dsl::replace_initial_state(&dsl, state, &spec.name)?;
+            parameters.push(spec.clone());
         }
 
-        let t_span: Vec<f64> = data_with_t.column(0).iter().cloned().collect();
-        let data = data_with_t.columns(1, data_with_t.ncols() - 1).into_owned();
+        let mut experiments: Vec<DiffsolExperiment> = self
+            .data
+            .iter()
+            .map(|data| {
+                let experiment = DiffsolExperiment::new(data.clone());
+                match &self.mask {
+                    Some(mask) => experiment.with_mask(mask.clone()),
+                    None => experiment,
+                }
+            })
+            .collect();
+        experiments.extend(self.experiments.iter().cloned());
+        if experiments.is_empty() {
+            return Err("Data must be provided".to_string());
+        }
 
         let mut cost_metrics = self.cost_metrics.clone();
         if cost_metrics.is_empty() {
             cost_metrics.push(Arc::new(SumSquaredError::default()));
         }
 
-        Problem::new_diffsol(
-            &dsl,
-            data,
-            t_span,
+        let fixes_inputs = experiments.iter().any(|e| !e.inputs.is_empty());
+        let inputs = dsl::input_names(&dsl)?;
+        let shared: Vec<&String> = inputs
+            .iter()
+            .filter(|name| {
+                !experiments
+                    .iter()
+                    .all(|e| e.fixed_input(name.as_str()).is_some())
+            })
+            .collect();
+
+        let resolved = experiments
+            .iter()
+            .map(|experiment| {
+                let resolved = experiment.resolve(&dsl, &self.observations, &cost_metrics)?;
+                if !fixes_inputs {
+                    return Ok(resolved);
+                }
+                if let Some((name, _)) = experiment
+                    .inputs
+                    .iter()
+                    .find(|(name, _)| !inputs.contains(name))
+                {
+                    return Err(format!(
+                        "Input '{}' is not declared in the DiffSL `in` list",
+                        name
+                    ));
+                }
+                let sources = inputs
+                    .iter()
+                    .map(|name| match experiment.fixed_input(name) {
+                        Some(value) => ModelInput::Fixed(value),
+                        None => ModelInput::Parameter(
+                            shared
+                                .iter()
+                                .position(|shared_name| *shared_name == name)
+                                .expect("inputs left free by an experiment are shared"),
+                        ),
+                    })
+                    .collect();
+                Ok(resolved.with_inputs(sources))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if parameters.len() != shared.len() {
+            return Err(format!(
+                "Expected {} parameters for the shared DiffSL inputs [{}], got {}",
+                shared.len(),
+                shared
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                parameters.len()
+            ));
+        }
+
+        Problem::new_diffsol_experiments(
+            resolved,
             self.config.clone(),
-            self.parameters.clone(),
-            cost_metrics,
+            parameters,
             self.optimiser_slot.get().cloned(),
         )
     }
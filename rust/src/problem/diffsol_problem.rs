@@ -1,4 +1,4 @@
-use super::{DiffsolBackend, DiffsolConfig};
+use super::{residual_weight, DiffsolBackend, DiffsolConfig};
 use crate::cost::CostMetric;
 use diffsol::error::DiffsolError;
 use diffsol::ode_solver::sensitivities::SensitivitiesOdeSolverMethod;
@@ -38,6 +38,26 @@ pub enum BackendProblem {
     Sparse(Box<SparseProblem>),
 }
 
+impl BackendProblem {
+    /// Compiles a DiffSL program for the backend selected in `config`.
+    pub fn compile(dsl: &str, config: &DiffsolConfig) -> Result<Self, String> {
+        match config.backend {
+            DiffsolBackend::Dense => OdeBuilder::<NalgebraMat<f64>>::new()
+                .atol([config.atol])
+                .rtol(config.rtol)
+                .build_from_diffsl(dsl)
+                .map_err(|e| format!("Failed to build ODE model: {}", e))
+                .map(|problem| BackendProblem::Dense(Box::new(problem))),
+            DiffsolBackend::Sparse => OdeBuilder::<FaerSparseMat<f64>>::new()
+                .atol([config.atol])
+                .rtol(config.rtol)
+                .build_from_diffsl(dsl)
+                .map_err(|e| format!("Failed to build ODE model: {}", e))
+                .map(|problem| BackendProblem::Sparse(Box::new(problem))),
+        }
+    }
+}
+
 thread_local! {
     static PROBLEM_CACHE: RefCell<HashMap<usize, BackendProblem>> = RefCell::new(HashMap::new());
 }
@@ -46,70 +66,130 @@ static NEXT_DIFFSOL_PROBLEM_ID: AtomicUsize = AtomicUsize::new(1);
 
 const FAILED_SOLVE_PENALTY: f64 = 1e5;
 
-/// Solver for Diffsol problems maintaining per-thread cached ODE instances.
-///
-/// # Thread Safety
+/// Index of a solution entry paired with the index of the data entry it is fitted to.
+type IndexPair = ((usize, usize), (usize, usize));
+
+/// Helper to convert DiffsolError to String with context
+#[inline]
+fn error_context<T>(result: Result<T, DiffsolError>, msg: &str) -> Result<T, String> {
+    result.map_err(|e| format!("{}: {}", msg, e))
+}
+
+/// Helper to solve with panic recovery
+#[inline]
+fn solve_safely<F, T>(solve_fn: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, DiffsolError>,
+{
+    catch_unwind(AssertUnwindSafe(solve_fn))
+        .map_err(|_| "Solver panicked".to_string())?
+        .map_err(|e| format!("Solve failed: {}", e))
+}
+
+/// Where an [`Experiment`] takes the value of one model input from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModelInput {
+    /// Entry of the shared parameter vector.
+    Parameter(usize),
+    /// Value fixed for this experiment.
+    Fixed(f64),
+}
+
+/// One dataset fitted by a [`DiffsolProblem`].
 ///
-/// This type uses thread-local storage to maintain per-thread ODE solver
-/// problem instances, enabling safe parallel evaluation without locks.
-/// Each thread lazily initializes its own problem instance on first use.
-pub struct DiffsolProblem {
-    id: usize,
+/// Each experiment owns its time grid, observations and cost metrics. Its model inputs
+/// are set through `inputs` (the shared parameter vector itself when unset), so
+/// experiments that only fix inputs differently solve the same DiffSL program and
+/// share one compiled model.
+pub struct Experiment {
+    /// Key of the compiled model in the thread-local cache.
+    model: usize,
     dsl: String,
-    config: DiffsolConfig,
     t_span: Vec<f64>,
     data: DMatrix<f64>,
     cost_metric: Vec<Arc<dyn CostMetric>>,
+    inputs: Option<Vec<ModelInput>>,
+    observed: Option<Vec<usize>>,
 }
 
-impl DiffsolProblem {
+impl Experiment {
     pub fn new(
-        diffsol_problem: BackendProblem,
         dsl: String,
-        config: DiffsolConfig,
         t_span: Vec<f64>,
         data: DMatrix<f64>,
         cost_metric: Vec<Arc<dyn CostMetric>>,
     ) -> Self {
-        let id = NEXT_DIFFSOL_PROBLEM_ID.fetch_add(1, Ordering::Relaxed);
-        let chron_problem = Self {
-            id,
+        Self {
+            model: NEXT_DIFFSOL_PROBLEM_ID.fetch_add(1, Ordering::Relaxed),
             dsl,
-            config,
             t_span,
             data,
             cost_metric,
-        };
-        chron_problem.seed_initial_problem(diffsol_problem);
-        chron_problem
+            inputs: None,
+            observed: None,
+        }
     }
 
-    fn build_problem(&self) -> Result<BackendProblem, String> {
-        match self.config.backend {
-            DiffsolBackend::Dense => OdeBuilder::<NalgebraMat<f64>>::new()
-                .atol([self.config.atol])
-                .rtol(self.config.rtol)
-                .build_from_diffsl(&self.dsl)
-                .map_err(|e| format!("Failed to build ODE model: {}", e))
-                .map(|problem| BackendProblem::Dense(Box::new(problem))),
-            DiffsolBackend::Sparse => OdeBuilder::<FaerSparseMat<f64>>::new()
-                .atol([self.config.atol])
-                .rtol(self.config.rtol)
-                .build_from_diffsl(&self.dsl)
-                .map_err(|e| format!("Failed to build ODE model: {}", e))
-                .map(|problem| BackendProblem::Sparse(Box::new(problem))),
+    /// Sets model input `i` from `inputs[i]` instead of entry `i` of the shared
+    /// parameter vector.
+    pub fn with_inputs(mut self, inputs: Vec<ModelInput>) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    /// Compares data column `j` against model output `outputs[j]` instead of
+    /// requiring the full solution to match the data.
+    pub fn with_observed_outputs(mut self, outputs: Vec<usize>) -> Self {
+        self.observed = Some(outputs);
+        self
+    }
+
+    fn local_params(&self, params: &[f64]) -> Result<Vec<f64>, String> {
+        match &self.inputs {
+            None => Ok(params.to_vec()),
+            Some(inputs) => inputs
+                .iter()
+                .map(|input| match *input {
+                    ModelInput::Parameter(idx) => params.get(idx).copied().ok_or_else(|| {
+                        format!(
+                            "Expected at least {} parameters but received {}",
+                            idx + 1,
+                            params.len()
+                        )
+                    }),
+                    ModelInput::Fixed(value) => Ok(value),
+                })
+                .collect(),
+        }
+    }
+
+    /// Scatters a Jacobian over the model inputs onto the shared parameter vector,
+    /// dropping the columns of fixed inputs.
+    fn scatter_jacobian(&self, local: DMatrix<f64>, n_params: usize) -> DMatrix<f64> {
+        match &self.inputs {
+            None => local,
+            Some(inputs) => {
+                let mut jacobian = DMatrix::zeros(local.nrows(), n_params);
+                for (local_idx, input) in inputs.iter().enumerate() {
+                    if let ModelInput::Parameter(idx) = *input {
+                        let mut column = jacobian.column_mut(idx);
+                        column += local.column(local_idx);
+                    }
+                }
+                jacobian
+            }
         }
     }
 
-    fn seed_initial_problem(&self, problem: BackendProblem) {
-        let id = self.id;
+    fn seed(&self, problem: BackendProblem) {
+        let model = self.model;
         PROBLEM_CACHE.with(|cache| {
             let mut cache = cache.borrow_mut();
-            cache.insert(id, problem);
+            cache.insert(model, problem);
         });
     }
 
-    fn with_thread_local_problem<F, R>(&self, mut f: F) -> Result<R, String>
+    fn with_thread_local_problem<F, R>(&self, config: &DiffsolConfig, mut f: F) -> Result<R, String>
     where
         F: FnMut(&mut BackendProblem) -> Result<R, String>,
     {
@@ -117,25 +197,25 @@ impl DiffsolProblem {
         // The ProbeGuard will only be active if a probe is registered
         let _probe_guard = test_support::ProbeGuard::new();
 
-        let id = self.id;
+        let model = self.model;
         PROBLEM_CACHE.with(|cache| {
             let mut cache = cache.borrow_mut();
-            if let Entry::Vacant(e) = cache.entry(id) {
-                e.insert(self.build_problem()?);
+            if let Entry::Vacant(e) = cache.entry(model) {
+                e.insert(BackendProblem::compile(&self.dsl, config)?);
             }
             let problem = cache
-                .get_mut(&id)
+                .get_mut(&model)
                 .expect("problem cache must contain entry after insertion");
             match catch_unwind(AssertUnwindSafe(|| f(problem))) {
                 Ok(result) => result,
                 Err(_) => {
-                    let rebuilt = self.build_problem()?;
+                    let rebuilt = BackendProblem::compile(&self.dsl, config)?;
                     let entry = cache
-                        .get_mut(&id)
+                        .get_mut(&model)
                         .expect("problem cache must contain entry after rebuild");
                     *entry = rebuilt;
                     let problem = cache
-                        .get_mut(&id)
+                        .get_mut(&model)
                         .expect("problem cache must contain entry after rebuild");
                     f(problem)
                 }
@@ -143,68 +223,89 @@ impl DiffsolProblem {
         })
     }
 
-    pub fn failed_solve_penalty() -> f64 {
-        FAILED_SOLVE_PENALTY
-    }
-
-    pub fn is_parallel(&self) -> bool {
-        self.config.parallel
-    }
-
-    fn build_residuals<M>(&self, solution: &M) -> Result<Vec<f64>, String>
-    where
-        M: Matrix + MatrixCommon + Index<(usize, usize), Output = f64>,
-    {
-        let sol_rows = solution.nrows();
-        let sol_cols = solution.ncols();
+    /// Pairs of (solution, data) indices in residual order, i.e. time-major.
+    ///
+    /// Without an observation mapping the solution must match the data shape or its
+    /// transpose. With one, data column `j` is compared against output `observed[j]`
+    /// of the outputs-by-times solution. Missing (NaN) observations are skipped.
+    fn residual_pairs(&self, sol_rows: usize, sol_cols: usize) -> Result<Vec<IndexPair>, String> {
         let (data_rows, data_cols) = self.data.shape();
+        let mut pairs = Vec::with_capacity(data_rows * data_cols);
 
-        let (transpose_data, expected_rows, expected_cols) =
-            match (sol_rows, sol_cols, data_rows, data_cols) {
-                (sr, sc, dr, dc) if sr == dr && sc == dc => (false, sr, sc),
-                (sr, sc, dr, dc) if sr == dc && sc == dr => (true, sr, sc),
-                (sr, sc, dr, dc) => {
+        match &self.observed {
+            Some(observed) => {
+                if observed.len() != data_cols || sol_cols != data_rows {
                     return Err(format!(
-                        "Solution shape {}x{} does not match data shape {}x{}",
-                        sr, sc, dr, dc
-                    ))
+                        "Solution with {} time points cannot be observed through {} outputs \
+                         against data shape {}x{}",
+                        sol_cols,
+                        observed.len(),
+                        data_rows,
+                        data_cols
+                    ));
                 }
-            };
-
-        let mut residuals = Vec::with_capacity(expected_rows * expected_cols);
-
-        if transpose_data {
-            for col in 0..expected_cols {
-                for row in 0..expected_rows {
-                    residuals.push(solution[(row, col)] - self.data[(col, row)]);
+                if let Some(output) = observed.iter().find(|&&output| output >= sol_rows) {
+                    return Err(format!(
+                        "Observed output {} is out of range for a model with {} outputs",
+                        output, sol_rows
+                    ));
+                }
+                for time in 0..data_rows {
+                    for (col, &output) in observed.iter().enumerate() {
+                        pairs.push(((output, time), (time, col)));
+                    }
                 }
             }
-        } else {
-            for row in 0..expected_rows {
-                for col in 0..expected_cols {
-                    residuals.push(solution[(row, col)] - self.data[(row, col)]);
+            None if sol_rows == data_rows && sol_cols == data_cols => {
+                for row in 0..sol_rows {
+                    for col in 0..sol_cols {
+                        pairs.push(((row, col), (row, col)));
+                    }
                 }
             }
+            None if sol_rows == data_cols && sol_cols == data_rows => {
+                for col in 0..sol_cols {
+                    for row in 0..sol_rows {
+                        pairs.push(((row, col), (col, row)));
+                    }
+                }
+            }
+            None => {
+                return Err(format!(
+                    "Solution shape {}x{} does not match data shape {}x{}",
+                    sol_rows, sol_cols, data_rows, data_cols
+                ))
+            }
         }
 
-        Ok(residuals)
+        pairs.retain(|&(_, data_idx)| !self.data[data_idx].is_nan());
+        Ok(pairs)
     }
 
-    /// Helper to convert DiffsolError to String with context
-    #[inline]
-    fn error_context<T>(result: Result<T, DiffsolError>, msg: &str) -> Result<T, String> {
-        result.map_err(|e| format!("{}: {}", msg, e))
-    }
-
-    /// Helper to solve with panic recovery
-    #[inline]
-    fn solve_safely<F, T>(solve_fn: F) -> Result<T, String>
+    fn build_residuals<M>(&self, solution: &M) -> Result<Vec<f64>, String>
     where
-        F: FnOnce() -> Result<T, DiffsolError>,
+        M: Matrix + MatrixCommon + Index<(usize, usize), Output = f64>,
     {
-        catch_unwind(AssertUnwindSafe(solve_fn))
-            .map_err(|_| "Solver panicked".to_string())?
-            .map_err(|e| format!("Solve failed: {}", e))
+        let pairs = self.residual_pairs(solution.nrows(), solution.ncols())?;
+        Ok(pairs
+            .into_iter()
+            .map(|(sol_idx, data_idx)| solution[sol_idx] - self.data[data_idx])
+            .collect())
+    }
+
+    /// Assemble the residual Jacobian with rows ordered exactly as `build_residuals`.
+    fn build_jacobian(&self, sensitivities: &[NalgebraMat<f64>]) -> Result<DMatrix<f64>, String> {
+        let n_residuals = self.data.iter().filter(|value| !value.is_nan()).count();
+        let mut jacobian = DMatrix::zeros(n_residuals, sensitivities.len());
+
+        for (param_idx, sens) in sensitivities.iter().enumerate() {
+            let pairs = self.residual_pairs(sens.nrows(), sens.ncols())?;
+            for (residual_idx, (sol_idx, _)) in pairs.into_iter().enumerate() {
+                jacobian[(residual_idx, param_idx)] = sens[sol_idx];
+            }
+        }
+
+        Ok(jacobian)
     }
 
     #[inline]
@@ -213,26 +314,51 @@ impl DiffsolProblem {
         M: Matrix + MatrixCommon + Index<(usize, usize), Output = f64>,
     {
         let residuals = self.build_residuals(solution)?;
-        let total_cost = self
-            .cost_metric
+        Ok(self.reduce_residuals(&residuals))
+    }
+
+    #[inline]
+    fn reduce_residuals(&self, residuals: &[f64]) -> f64 {
+        self.cost_metric
             .iter()
-            .map(|metric| metric.evaluate(&residuals))
-            .sum();
-        Ok(total_cost)
+            .map(|metric| metric.evaluate(residuals))
+            .sum()
     }
 
+    /// Reduces residuals and their Jacobian through every cost metric.
+    ///
+    /// Each Jacobian column is handed to the metrics as a single-column sensitivity so
+    /// that its entries line up with the residual ordering.
     fn calculate_cost_with_grad(
         &self,
-        solution: &NalgebraMat<f64>,
-        sensitivities: &[NalgebraMat<f64>],
+        residuals: &[f64],
+        jacobian: &DMatrix<f64>,
     ) -> Result<(f64, Vec<f64>), String> {
-        let residuals = self.build_residuals(solution)?;
+        let sensitivities = (0..jacobian.ncols())
+            .map(|col| {
+                let triplets = jacobian
+                    .column(col)
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &value)| (row, 0, value))
+                    .collect();
+                error_context(
+                    NalgebraMat::try_from_triplets(
+                        jacobian.nrows(),
+                        1,
+                        triplets,
+                        Default::default(),
+                    ),
+                    "Failed to assemble sensitivities",
+                )
+            })
+            .collect::<Result<Vec<NalgebraMat<f64>>, String>>()?;
 
         self.cost_metric
             .iter()
             .try_fold((0.0, Vec::new()), |(acc_cost, acc_grad), metric| {
                 let (cost, grad) = metric
-                    .evaluate_with_sensitivities(&residuals, sensitivities)
+                    .evaluate_with_sensitivities(residuals, &sensitivities)
                     .ok_or_else(|| {
                         format!(
                             "Cost metric '{}' does not support gradient evaluation",
@@ -254,112 +380,391 @@ impl DiffsolProblem {
             })
     }
 
-    pub fn evaluate(&self, params: &[f64]) -> Result<f64, String> {
-        self.with_thread_local_problem(|problem| match problem {
+    fn evaluate(&self, config: &DiffsolConfig, params: &[f64]) -> Result<f64, String> {
+        let residuals = self.residuals(config, params)?;
+        Ok(self.reduce_residuals(&residuals))
+    }
+
+    fn residuals(&self, config: &DiffsolConfig, params: &[f64]) -> Result<Vec<f64>, String> {
+        let local = self.local_params(params)?;
+        self.local_residuals(config, &local)
+    }
+
+    /// Residuals for a full vector of model inputs.
+    fn local_residuals(&self, config: &DiffsolConfig, local: &[f64]) -> Result<Vec<f64>, String> {
+        self.with_thread_local_problem(config, |problem| match problem {
             BackendProblem::Dense(p) => {
                 let ctx = *p.eqn().context();
                 p.eqn_mut()
-                    .set_params(&DenseVector::from_vec(params.to_vec(), ctx));
+                    .set_params(&DenseVector::from_vec(local.to_vec(), ctx));
 
                 let mut solver =
-                    Self::error_context(p.bdf::<DenseSolver>(), "Failed to create BDF solver")?;
-                let solution = Self::solve_safely(|| solver.solve_dense(&self.t_span))?;
-                self.calculate_cost(&solution)
+                    error_context(p.bdf::<DenseSolver>(), "Failed to create BDF solver")?;
+                let solution = solve_safely(|| solver.solve_dense(&self.t_span))?;
+                self.build_residuals(&solution)
             }
             BackendProblem::Sparse(p) => {
                 let ctx = *p.eqn().context();
                 p.eqn_mut()
-                    .set_params(&SparseVector::from_vec(params.to_vec(), ctx));
+                    .set_params(&SparseVector::from_vec(local.to_vec(), ctx));
 
                 let mut solver =
-                    Self::error_context(p.bdf::<SparseSolver>(), "Failed to create BDF solver")?;
-                let solution = Self::solve_safely(|| solver.solve_dense(&self.t_span))?;
-                self.calculate_cost(&solution)
+                    error_context(p.bdf::<SparseSolver>(), "Failed to create BDF solver")?;
+                let solution = solve_safely(|| solver.solve_dense(&self.t_span))?;
+                self.build_residuals(&solution)
             }
         })
     }
 
-    pub fn evaluate_with_gradient(&self, params: &[f64]) -> Result<(f64, Vec<f64>), String> {
-        self.with_thread_local_problem(|problem| match problem {
+    /// Residuals and their Jacobian with respect to the shared parameter vector.
+    ///
+    /// The sparse backend has no forward sensitivities, so its Jacobian is
+    /// approximated by central differences instead.
+    fn residuals_with_jacobian(
+        &self,
+        config: &DiffsolConfig,
+        params: &[f64],
+    ) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        let local = self.local_params(params)?;
+        let sensitivities = self.with_thread_local_problem(config, |problem| match problem {
             BackendProblem::Dense(p) => {
                 let ctx = *p.eqn().context();
                 p.eqn_mut()
-                    .set_params(&DenseVector::from_vec(params.to_vec(), ctx));
+                    .set_params(&DenseVector::from_vec(local.clone(), ctx));
 
-                let mut solver = Self::error_context(
+                let mut solver = error_context(
                     p.bdf_sens::<DenseSolver>(),
                     "Failed to create BDF sensitivities solver",
                 )?;
 
                 let (solution, sensitivities) =
-                    Self::solve_safely(|| solver.solve_dense_sensitivities(&self.t_span))?;
+                    solve_safely(|| solver.solve_dense_sensitivities(&self.t_span))?;
 
-                self.calculate_cost_with_grad(&solution, &sensitivities)
+                let residuals = self.build_residuals(&solution)?;
+                let jacobian = self.build_jacobian(&sensitivities)?;
+                Ok(Some((residuals, jacobian)))
             }
-            BackendProblem::Sparse(_p) => Err(
-                "Sparse diffsol backend does not currently support gradient evaluation".to_string(),
-            ),
-        })
+            BackendProblem::Sparse(_) => Ok(None),
+        })?;
+        let (residuals, jacobian) = match sensitivities {
+            Some(result) => result,
+            None => self.finite_difference_jacobian(config, &local)?,
+        };
+        Ok((residuals, self.scatter_jacobian(jacobian, params.len())))
     }
 
-    pub fn evaluate_population(&self, params: &[&[f64]]) -> Vec<Result<f64, String>> {
-        let eval_fn = |param: &&[f64]| {
-            self.with_thread_local_problem(|problem| {
-                self.evaluate_single_with_penalty(problem, param)
-            })
-        };
+    /// Central-difference residual Jacobian over the model inputs, skipping inputs
+    /// fixed for this experiment. The step scales with the cube root of the solver's
+    /// relative tolerance, balancing truncation error against solver noise.
+    fn finite_difference_jacobian(
+        &self,
+        config: &DiffsolConfig,
+        local: &[f64],
+    ) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        let residuals = self.local_residuals(config, local)?;
+        let mut jacobian = DMatrix::zeros(residuals.len(), local.len());
+        let mut perturbed = local.to_vec();
+        let relative_step = config.rtol.max(f64::EPSILON).cbrt();
+
+        for j in 0..local.len() {
+            let fixed = self
+                .inputs
+                .as_ref()
+                .is_some_and(|inputs| matches!(inputs[j], ModelInput::Fixed(_)));
+            if fixed {
+                continue;
+            }
 
-        if self.config.parallel {
-            params.par_iter().map(eval_fn).collect()
-        } else {
-            params.iter().map(eval_fn).collect()
+            let step = relative_step * local[j].abs().max(1.0);
+            perturbed[j] = local[j] + step;
+            let plus = self.local_residuals(config, &perturbed)?;
+            perturbed[j] = local[j] - step;
+            let minus = self.local_residuals(config, &perturbed)?;
+            perturbed[j] = local[j];
+
+            for (i, (r_plus, r_minus)) in plus.iter().zip(&minus).enumerate() {
+                jacobian[(i, j)] = (r_plus - r_minus) / (2.0 * step);
+            }
         }
+
+        Ok((residuals, jacobian))
     }
 
-    // Helper to avoid code duplication in evaluate_population
-    fn evaluate_single_with_penalty(
+    fn evaluate_with_gradient(
         &self,
-        problem: &mut BackendProblem,
+        config: &DiffsolConfig,
         params: &[f64],
-    ) -> Result<f64, String> {
-        let result = match problem {
-            BackendProblem::Dense(p) => {
-                let ctx = *p.eqn().context();
-                p.eqn_mut()
-                    .set_params(&DenseVector::from_vec(params.to_vec(), ctx));
-
-                Self::error_context(p.bdf::<DenseSolver>(), "Failed to create BDF solver")
-                    .and_then(|mut solver| Self::solve_safely(|| solver.solve_dense(&self.t_span)))
-                    .and_then(|solution| self.calculate_cost(&solution))
-                    .ok()
-            }
-            BackendProblem::Sparse(p) => {
-                let ctx = *p.eqn().context();
-                p.eqn_mut()
-                    .set_params(&SparseVector::from_vec(params.to_vec(), ctx));
+    ) -> Result<(f64, Vec<f64>), String> {
+        let (residuals, jacobian) = self.residuals_with_jacobian(config, params)?;
+        self.calculate_cost_with_grad(&residuals, &jacobian)
+    }
 
-                Self::error_context(p.bdf::<SparseSolver>(), "Failed to create BDF solver")
-                    .and_then(|mut solver| Self::solve_safely(|| solver.solve_dense(&self.t_span)))
-                    .and_then(|solution| self.calculate_cost(&solution))
-                    .ok()
-            }
-        };
+    /// Cost of a single solve, replacing solver failures by the fixed penalty.
+    fn evaluate_with_penalty(&self, config: &DiffsolConfig, params: &[f64]) -> Result<f64, String> {
+        let params = self.local_params(params)?;
+        self.with_thread_local_problem(config, |problem| {
+            let result = match problem {
+                BackendProblem::Dense(p) => {
+                    let ctx = *p.eqn().context();
+                    p.eqn_mut()
+                        .set_params(&DenseVector::from_vec(params.clone(), ctx));
+
+                    error_context(p.bdf::<DenseSolver>(), "Failed to create BDF solver")
+                        .and_then(|mut solver| solve_safely(|| solver.solve_dense(&self.t_span)))
+                        .and_then(|solution| self.calculate_cost(&solution))
+                        .ok()
+                }
+                BackendProblem::Sparse(p) => {
+                    let ctx = *p.eqn().context();
+                    p.eqn_mut()
+                        .set_params(&SparseVector::from_vec(params.clone(), ctx));
+
+                    error_context(p.bdf::<SparseSolver>(), "Failed to create BDF solver")
+                        .and_then(|mut solver| solve_safely(|| solver.solve_dense(&self.t_span)))
+                        .and_then(|solution| self.calculate_cost(&solution))
+                        .ok()
+                }
+            };
 
-        Ok(result.unwrap_or_else(|| Self::failed_solve_penalty()))
+            Ok(result.unwrap_or(FAILED_SOLVE_PENALTY))
+        })
     }
 }
 
 /// Clean-up for globally stored
 /// PROBLEM_CACHE HashMap
-impl Drop for DiffsolProblem {
+impl Drop for Experiment {
     fn drop(&mut self) {
-        let id = self.id;
+        let model = self.model;
         PROBLEM_CACHE.with(|cache| {
-            cache.borrow_mut().remove(&id);
+            cache.borrow_mut().remove(&model);
         });
     }
 }
 
+/// Solver for Diffsol problems maintaining per-thread cached ODE instances.
+///
+/// A problem fits one or more [`Experiment`]s with a shared parameter vector: their
+/// residuals are concatenated in experiment order and their costs summed.
+///
+/// # Thread Safety
+///
+/// This type uses thread-local storage to maintain per-thread ODE solver
+/// problem instances, enabling safe parallel evaluation without locks.
+/// Each thread lazily initializes its own problem instance on first use.
+pub struct DiffsolProblem {
+    config: DiffsolConfig,
+    experiments: Vec<Experiment>,
+    cost_metric: Vec<Arc<dyn CostMetric>>,
+}
+
+impl DiffsolProblem {
+    pub fn new(
+        diffsol_problem: BackendProblem,
+        dsl: String,
+        config: DiffsolConfig,
+        t_span: Vec<f64>,
+        data: DMatrix<f64>,
+        cost_metric: Vec<Arc<dyn CostMetric>>,
+    ) -> Self {
+        let experiment = Experiment::new(dsl, t_span, data, cost_metric.clone());
+        experiment.seed(diffsol_problem);
+        Self {
+            config,
+            experiments: vec![experiment],
+            cost_metric,
+        }
+    }
+
+    /// Jointly fits several experiments, compiling each distinct DiffSL program once
+    /// up front; experiments with the same program share its compiled model.
+    pub fn from_experiments(
+        config: DiffsolConfig,
+        mut experiments: Vec<Experiment>,
+    ) -> Result<Self, String> {
+        if experiments.is_empty() {
+            return Err("At least one experiment must be provided".to_string());
+        }
+        for i in 0..experiments.len() {
+            let (compiled, rest) = experiments.split_at_mut(i);
+            let experiment = &mut rest[0];
+            match compiled.iter().find(|other| other.dsl == experiment.dsl) {
+                Some(other) => experiment.model = other.model,
+                None => experiment.seed(BackendProblem::compile(&experiment.dsl, &config)?),
+            }
+        }
+        let cost_metric = experiments
+            .iter()
+            .flat_map(|experiment| experiment.cost_metric.iter().cloned())
+            .collect();
+        Ok(Self {
+            config,
+            experiments,
+            cost_metric,
+        })
+    }
+
+    /// Cost metrics of every experiment.
+    pub(crate) fn cost_metrics(&self) -> &[Arc<dyn CostMetric>] {
+        &self.cost_metric
+    }
+
+    pub fn n_experiments(&self) -> usize {
+        self.experiments.len()
+    }
+
+    pub fn failed_solve_penalty() -> f64 {
+        FAILED_SOLVE_PENALTY
+    }
+
+    pub fn is_parallel(&self) -> bool {
+        self.config.parallel
+    }
+
+    /// Runs `f` on every experiment, spreading the solves over the rayon pool when
+    /// parallel evaluation is enabled.
+    fn map_experiments<F, R>(&self, f: F) -> Vec<R>
+    where
+        F: Fn(&Experiment) -> R + Send + Sync,
+        R: Send,
+    {
+        if self.config.parallel && self.experiments.len() > 1 {
+            self.experiments.par_iter().map(f).collect()
+        } else {
+            self.experiments.iter().map(f).collect()
+        }
+    }
+
+    pub fn evaluate(&self, params: &[f64]) -> Result<f64, String> {
+        self.map_experiments(|experiment| experiment.evaluate(&self.config, params))
+            .into_iter()
+            .sum()
+    }
+
+    /// Residuals between the simulated outputs and the observed data, omitting
+    /// missing (NaN) observations.
+    pub fn residuals(&self, params: &[f64]) -> Result<Vec<f64>, String> {
+        self.stacked_residuals(params, false)
+    }
+
+    /// Residuals scaled by each experiment's cost metrics so that their sum of squares
+    /// equals the cost up to a constant.
+    pub fn weighted_residuals(&self, params: &[f64]) -> Result<Vec<f64>, String> {
+        self.stacked_residuals(params, true)
+    }
+
+    /// Residuals together with their Jacobian (one row per residual, one column
+    /// per parameter) obtained from forward sensitivities.
+    pub fn residuals_with_jacobian(
+        &self,
+        params: &[f64],
+    ) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        self.stacked_residuals_with_jacobian(params, false)
+    }
+
+    /// [`DiffsolProblem::weighted_residuals`] together with their Jacobian.
+    pub fn weighted_residuals_with_jacobian(
+        &self,
+        params: &[f64],
+    ) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        self.stacked_residuals_with_jacobian(params, true)
+    }
+
+    fn stacked_residuals(&self, params: &[f64], weighted: bool) -> Result<Vec<f64>, String> {
+        let mut residuals = Vec::new();
+        for result in self.map_experiments(|experiment| -> Result<Vec<f64>, String> {
+            let residuals = experiment.residuals(&self.config, params)?;
+            if !weighted {
+                return Ok(residuals);
+            }
+            let weight = residual_weight(&experiment.cost_metric)?;
+            Ok(residuals.into_iter().map(|r| weight * r).collect())
+        }) {
+            residuals.extend(result?);
+        }
+        Ok(residuals)
+    }
+
+    fn stacked_residuals_with_jacobian(
+        &self,
+        params: &[f64],
+        weighted: bool,
+    ) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        let mut parts = self
+            .map_experiments(|experiment| -> Result<(Vec<f64>, DMatrix<f64>), String> {
+                let (residuals, jacobian) =
+                    experiment.residuals_with_jacobian(&self.config, params)?;
+                if !weighted {
+                    return Ok((residuals, jacobian));
+                }
+                let weight = residual_weight(&experiment.cost_metric)?;
+                Ok((
+                    residuals.into_iter().map(|r| weight * r).collect(),
+                    jacobian * weight,
+                ))
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if parts.len() == 1 {
+            return Ok(parts.remove(0));
+        }
+
+        let n_residuals = parts.iter().map(|(residuals, _)| residuals.len()).sum();
+        let mut residuals = Vec::with_capacity(n_residuals);
+        let mut jacobian = DMatrix::zeros(n_residuals, params.len());
+        for (part_residuals, part_jacobian) in parts {
+            jacobian
+                .rows_mut(residuals.len(), part_residuals.len())
+                .copy_from(&part_jacobian);
+            residuals.extend(part_residuals);
+        }
+        Ok((residuals, jacobian))
+    }
+
+    pub fn evaluate_with_gradient(&self, params: &[f64]) -> Result<(f64, Vec<f64>), String> {
+        let mut cost = 0.0;
+        let mut gradient = vec![0.0; params.len()];
+        for result in self
+            .map_experiments(|experiment| experiment.evaluate_with_gradient(&self.config, params))
+        {
+            let (part_cost, part_gradient) = result?;
+            cost += part_cost;
+            for (total, part) in gradient.iter_mut().zip(part_gradient) {
+                *total += part;
+            }
+        }
+        Ok((cost, gradient))
+    }
+
+    /// Costs of several parameter vectors, replacing failed solves by the fixed penalty
+    /// so that optimisers steer away from them.
+    pub fn evaluate_population(&self, params: &[&[f64]]) -> Vec<Result<f64, String>> {
+        self.map_population(params, |param| {
+            self.map_experiments(|experiment| experiment.evaluate_with_penalty(&self.config, param))
+                .into_iter()
+                .sum()
+        })
+    }
+
+    /// Costs of several parameter vectors, reporting failed solves as errors rather
+    /// than the penalty applied by [`DiffsolProblem::evaluate_population`].
+    pub fn evaluate_population_strict(&self, params: &[&[f64]]) -> Vec<Result<f64, String>> {
+        self.map_population(params, |param| self.evaluate(param))
+    }
+
+    fn map_population<F>(&self, params: &[&[f64]], eval_fn: F) -> Vec<Result<f64, String>>
+    where
+        F: Fn(&[f64]) -> Result<f64, String> + Sync,
+    {
+        if self.config.parallel {
+            params.par_iter().map(|param| eval_fn(param)).collect()
+        } else {
+            params.iter().map(|param| eval_fn(param)).collect()
+        }
+    }
+}
+
 pub mod test_support {
     use super::*;
     use std::sync::{atomic::Ordering, Mutex};
@@ -612,4 +1017,137 @@ F_i { (r * y) * (1 - (y / k)) }
             );
         }
     }
+
+    #[cfg(not(feature = "cranelift-backend"))]
+    #[test]
+    fn diffsol_residual_jacobian_matches_finite_difference() {
+        let problem = build_logistic_problem(DiffsolBackend::Dense);
+        let params = [1.1_f64, 0.9_f64];
+
+        let (residuals, jacobian) = problem
+            .residuals_with_jacobian(&params)
+            .expect("residual Jacobian calculation failed");
+
+        assert_eq!(residuals, problem.residuals(&params).unwrap());
+        assert_eq!(jacobian.shape(), (residuals.len(), params.len()));
+
+        let eps = 1e-6_f64;
+        for j in 0..params.len() {
+            let mut plus = params;
+            let mut minus = params;
+            plus[j] += eps;
+            minus[j] -= eps;
+            let r_plus = problem.residuals(&plus).unwrap();
+            let r_minus = problem.residuals(&minus).unwrap();
+
+            for i in 0..residuals.len() {
+                let fd = (r_plus[i] - r_minus[i]) / (2.0 * eps);
+                let diff = (fd - jacobian[(i, j)]).abs();
+                assert!(
+                    diff < 1e-5,
+                    "Jacobian mismatch at ({}, {}): fd={} jac={}",
+                    i,
+                    j,
+                    fd,
+                    jacobian[(i, j)]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sparse_residual_jacobian_matches_dense_sensitivities() {
+        let dense = build_logistic_problem(DiffsolBackend::Dense);
+        let sparse = build_logistic_problem(DiffsolBackend::Sparse);
+        let params = [1.1_f64, 0.9_f64];
+
+        let (expected_residuals, expected) = dense.residuals_with_jacobian(&params).unwrap();
+        let (residuals, jacobian) = sparse
+            .residuals_with_jacobian(&params)
+            .expect("sparse Jacobian should fall back to finite differences");
+
+        assert_eq!(jacobian.shape(), expected.shape());
+        for (r, e) in residuals.iter().zip(&expected_residuals) {
+            assert!((r - e).abs() < 1e-5);
+        }
+        for (j, e) in jacobian.iter().zip(expected.iter()) {
+            assert!((j - e).abs() < 1e-3 * e.abs().max(1.0), "{} vs {}", j, e);
+        }
+        assert!(sparse.evaluate_with_gradient(&params).is_ok());
+    }
+
+    #[test]
+    fn strict_population_reports_failed_solves() {
+        let problem = build_logistic_problem(DiffsolBackend::Dense);
+        // A negative capacity turns logistic growth into a finite-time blow-up.
+        let failing = [50.0_f64, -0.01_f64];
+        let regular = [1.0_f64, 1.0_f64];
+        assert!(problem.evaluate(&failing).is_err());
+
+        let penalised = problem.evaluate_population(&[&failing, &regular]);
+        assert_eq!(penalised[0], Ok(DiffsolProblem::failed_solve_penalty()));
+
+        let strict = problem.evaluate_population_strict(&[&failing, &regular]);
+        assert!(strict[0].is_err());
+        assert_eq!(strict[1], penalised[1]);
+        assert_eq!(strict[1], problem.evaluate(&regular));
+    }
+
+    #[cfg(not(feature = "cranelift-backend"))]
+    #[test]
+    fn multi_output_experiments_gradient_matches_finite_difference() {
+        // Two outputs make the time-major residual ordering differ from the
+        // output-major layout of the sensitivities.
+        let dsl = r#"
+in = [a, b, c]
+a { 1 }
+b { 1 }
+c { 1 }
+u_i { x = 1, y = 0 }
+F_i { -a * x, a * x - b * c * y }
+"#;
+        let t_span: Vec<f64> = (0..5).map(|i| i as f64 * 0.25).collect();
+        let data = DMatrix::from_fn(t_span.len(), 2, |i, j| 0.5 + 0.1 * (i + j) as f64);
+        let metrics: Vec<Arc<dyn CostMetric>> = vec![Arc::new(SumSquaredError::default())];
+
+        // The second experiment fixes `b` and reuses the first one's compiled model.
+        let problem = DiffsolProblem::from_experiments(
+            DiffsolConfig::default(),
+            vec![
+                Experiment::new(
+                    dsl.to_string(),
+                    t_span.clone(),
+                    data.clone(),
+                    metrics.clone(),
+                ),
+                Experiment::new(dsl.to_string(), t_span.clone(), data, metrics).with_inputs(vec![
+                    ModelInput::Parameter(0),
+                    ModelInput::Fixed(2.0),
+                    ModelInput::Parameter(2),
+                ]),
+            ],
+        )
+        .expect("experiments should compile");
+        assert_eq!(problem.experiments[0].model, problem.experiments[1].model);
+
+        let params = [0.8_f64, 1.3, 0.7];
+        let (cost, grad) = problem.evaluate_with_gradient(&params).unwrap();
+        assert!((cost - problem.evaluate(&params).unwrap()).abs() < 1e-10);
+        assert_eq!(
+            problem.residuals(&params).unwrap().len(),
+            2 * 2 * t_span.len()
+        );
+
+        for i in 0..params.len() {
+            let mut params_fd = params;
+            let fd = finite_difference(&mut params_fd, i, 1e-6, |p| problem.evaluate(p).unwrap());
+            assert!(
+                (fd - grad[i]).abs() < 1e-5,
+                "gradient mismatch for param {}: fd={} grad={}",
+                i,
+                fd,
+                grad[i]
+            );
+        }
+    }
 }
@@ -0,0 +1,357 @@
+//! Source-level edits to DiffSL programs.
+//!
+//! Experiment-specific and estimated initial states are applied by rewriting a copy of
+//! the model text before it is compiled, so every experiment solves an ordinary DiffSL
+//! program.
+
+/// A top-level `name { ... }` tensor or `in = [...]` list of a DiffSL program.
+struct Item {
+    header: String,
+    delimiter: u8,
+    open: usize,
+    close: usize,
+}
+
+impl Item {
+    fn is_input_list(&self) -> bool {
+        self.delimiter == b'[' && self.header.trim_end_matches('=').trim() == "in"
+    }
+
+    fn is_block(&self, name: &str) -> bool {
+        self.delimiter == b'{' && self.header == name
+    }
+
+    fn is_state_block(&self) -> bool {
+        self.delimiter == b'{' && (self.header == "u" || self.header.starts_with("u_"))
+    }
+
+    fn is_output_block(&self) -> bool {
+        self.delimiter == b'{' && (self.header == "out" || self.header.starts_with("out_"))
+    }
+}
+
+/// Splits a DiffSL program into its top-level items, skipping comments.
+fn top_level_items(dsl: &str) -> Result<Vec<Item>, String> {
+    let bytes = dsl.as_bytes();
+    let mut items = Vec::new();
+    let mut stack: Vec<(u8, usize, String)> = Vec::new();
+    let mut boundary = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"//") {
+            i = dsl[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            if stack.is_empty() {
+                boundary = i;
+            }
+            continue;
+        }
+        if bytes[i..].starts_with(b"/*") {
+            i = dsl[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| i + 2 + end + 2);
+            if stack.is_empty() {
+                boundary = i;
+            }
+            continue;
+        }
+
+        match bytes[i] {
+            open @ (b'{' | b'[' | b'(') => {
+                let header = if stack.is_empty() {
+                    dsl[boundary..i].trim().to_string()
+                } else {
+                    String::new()
+                };
+                stack.push((open, i, header));
+            }
+            close @ (b'}' | b']' | b')') => {
+                let (open, start, header) = stack
+                    .pop()
+                    .ok_or_else(|| "Unbalanced brackets in DiffSL program".to_string())?;
+                let expected = match open {
+                    b'{' => b'}',
+                    b'[' => b']',
+                    _ => b')',
+                };
+                if close != expected {
+                    return Err("Unbalanced brackets in DiffSL program".to_string());
+                }
+                if stack.is_empty() {
+                    if open != b'(' {
+                        items.push(Item {
+                            header,
+                            delimiter: open,
+                            open: start,
+                            close: i,
+                        });
+                    }
+                    boundary = i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if !stack.is_empty() {
+        return Err("Unbalanced brackets in DiffSL program".to_string());
+    }
+    Ok(items)
+}
+
+/// Byte ranges of the comma-separated entries in `source[start..end]`.
+fn entries(source: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut depth = 0usize;
+    let mut entry_start = start;
+    for (offset, byte) in source.as_bytes()[start..end].iter().enumerate() {
+        match byte {
+            b'{' | b'[' | b'(' => depth += 1,
+            b'}' | b']' | b')' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                spans.push((entry_start, start + offset));
+                entry_start = start + offset + 1;
+            }
+            _ => {}
+        }
+    }
+    spans.push((entry_start, end));
+    spans.retain(|&(s, e)| !source[s..e].trim().is_empty());
+    spans
+}
+
+/// Byte range of `source[start..end]` with surrounding whitespace removed.
+fn trimmed(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let slice = &source[start..end];
+    let leading = slice.len() - slice.trim_start().len();
+    let trailing = slice.len() - slice.trim_end().len();
+    (start + leading, end - trailing)
+}
+
+/// Applies non-overlapping `(start, end, replacement)` edits to `source`.
+fn apply_edits(source: &str, mut edits: Vec<(usize, usize, String)>) -> String {
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.0));
+    let mut result = source.to_string();
+    for (start, end, replacement) in edits {
+        result.replace_range(start..end, &replacement);
+    }
+    result
+}
+
+/// Offset of the `=` labelling an entry such as `y = 0.1`, ignoring comparisons.
+fn assignment(entry: &str) -> Option<usize> {
+    let bytes = entry.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i] == b'='
+            && bytes.get(i + 1) != Some(&b'=')
+            && (i == 0 || !matches!(bytes[i - 1], b'<' | b'>' | b'!' | b'='))
+    })
+}
+
+/// Name labelling an entry, i.e. the last token before its `=`.
+fn entry_label(entry: &str) -> Option<&str> {
+    let eq = assignment(entry)?;
+    entry[..eq]
+        .rsplit(|c: char| c.is_whitespace() || c == ':')
+        .find(|token| !token.is_empty())
+}
+
+/// Formats a finite value as a DiffSL literal.
+pub(crate) fn literal(value: f64) -> String {
+    if value.is_sign_negative() && value != 0.0 {
+        format!("({})", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Names listed in the `in = [...]` declaration, in order; empty when there is none.
+pub(crate) fn input_names(dsl: &str) -> Result<Vec<String>, String> {
+    let items = top_level_items(dsl)?;
+    Ok(items
+        .iter()
+        .find(|item| item.is_input_list())
+        .map(|item| {
+            entries(dsl, item.open + 1, item.close)
+                .into_iter()
+                .map(|(start, end)| dsl[start..end].trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Declares a new input `name` after the existing ones, defaulting to `expr`.
+///
+/// The name must not clash with an input, tensor or state of the program.
+pub(crate) fn add_input(dsl: &str, name: &str, expr: &str) -> Result<String, String> {
+    let items = top_level_items(dsl)?;
+    let states = output_names_of(dsl, items.iter().find(|item| item.is_state_block()));
+    let clashes = input_names(dsl)?.iter().any(|input| input == name)
+        || items.iter().any(|item| item.is_block(name))
+        || states.iter().any(|state| state.as_deref() == Some(name));
+    if clashes {
+        return Err(format!(
+            "Parameter '{}' clashes with a name already defined by the DiffSL program",
+            name
+        ));
+    }
+
+    let definition = format!("{} {{ {} }}", name, expr);
+    match items.iter().find(|item| item.is_input_list()) {
+        Some(list) => {
+            let mut names: Vec<&str> = entries(dsl, list.open + 1, list.close)
+                .into_iter()
+                .map(|(start, end)| dsl[start..end].trim())
+                .collect();
+            names.push(name);
+            Ok(apply_edits(
+                dsl,
+                vec![
+                    (list.open + 1, list.close, names.join(", ")),
+                    (list.close + 1, list.close + 1, format!("\n{}", definition)),
+                ],
+            ))
+        }
+        None => Ok(format!("in = [{}]\n{}\n{}", name, definition, dsl)),
+    }
+}
+
+/// Replaces the initial value of `state` in the `u_i` block with `expr`.
+pub(crate) fn replace_initial_state(dsl: &str, state: &str, expr: &str) -> Result<String, String> {
+    let items = top_level_items(dsl)?;
+    let block = items
+        .iter()
+        .find(|item| item.is_state_block())
+        .ok_or_else(|| "DiffSL program has no u_i block".to_string())?;
+
+    for (start, end) in entries(dsl, block.open + 1, block.close) {
+        let entry = &dsl[start..end];
+        let Some(eq) = assignment(entry) else {
+            continue;
+        };
+        if entry_label(entry) == Some(state) {
+            let (value_start, value_end) = trimmed(dsl, start + eq + 1, end);
+            return Ok(apply_edits(
+                dsl,
+                vec![(value_start, value_end, expr.to_string())],
+            ));
+        }
+    }
+
+    Err(format!(
+        "Initial state '{}' is not defined in the u_i block",
+        state
+    ))
+}
+
+/// Names of the model outputs in order, taken from the `out_i` block or, when there
+/// is none, from the states. Components that are neither labelled nor a bare
+/// identifier have no name.
+pub(crate) fn output_names(dsl: &str) -> Result<Vec<Option<String>>, String> {
+    let items = top_level_items(dsl)?;
+    let block = items
+        .iter()
+        .find(|item| item.is_output_block())
+        .or_else(|| items.iter().find(|item| item.is_state_block()))
+        .ok_or_else(|| "DiffSL program has no u_i block".to_string())?;
+    Ok(output_names_of(dsl, Some(block)))
+}
+
+/// Names of the entries of `block`, as described for [`output_names`].
+fn output_names_of(dsl: &str, block: Option<&Item>) -> Vec<Option<String>> {
+    let Some(block) = block else {
+        return Vec::new();
+    };
+    entries(dsl, block.open + 1, block.close)
+        .into_iter()
+        .map(|(start, end)| {
+            let entry = &dsl[start..end];
+            if let Some(label) = entry_label(entry) {
+                return Some(label.to_string());
+            }
+            let entry = entry.trim();
+            let is_identifier = entry.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && entry.starts_with(|c: char| c.is_ascii_alphabetic());
+            is_identifier.then(|| entry.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGISTIC: &str = r#"
+in = [r, k]
+r { 1 }
+k { 1 }
+u_i {
+    y = 0.1, // initial population
+    z = r * 2,
+}
+F_i { (r * y) * (1 - (y / k)), -z }
+"#;
+
+    #[test]
+    fn input_names_follow_declaration_order() {
+        assert_eq!(input_names(LOGISTIC).unwrap(), vec!["r", "k"]);
+        assert!(input_names("u_i { y = 1 }\nF_i { -y }").unwrap().is_empty());
+    }
+
+    #[test]
+    fn negative_literals_are_parenthesised() {
+        let replaced = replace_initial_state(LOGISTIC, "y", &literal(-2.5)).unwrap();
+        assert!(replaced.contains("y = (-2.5),"));
+        assert_eq!(literal(0.5), "0.5");
+    }
+
+    #[test]
+    fn initial_states_are_replaced_by_name() {
+        let replaced = replace_initial_state(LOGISTIC, "z", "0.75").unwrap();
+        assert!(replaced.contains("z = 0.75,"));
+        assert!(replaced.contains("y = 0.1,"));
+
+        let replaced = replace_initial_state("u_i { y = 0.1 }", "y", "3").unwrap();
+        assert_eq!(replaced, "u_i { y = 3 }");
+
+        let err = replace_initial_state(LOGISTIC, "w", "1").unwrap_err();
+        assert!(err.contains("'w'"), "{}", err);
+    }
+
+    #[test]
+    fn output_names_come_from_out_or_states() {
+        assert_eq!(
+            output_names(LOGISTIC).unwrap(),
+            vec![Some("y".to_string()), Some("z".to_string())]
+        );
+
+        let observed = format!("{}out_i {{ y, total = y + z, 2 * z }}\n", LOGISTIC);
+        assert_eq!(
+            output_names(&observed).unwrap(),
+            vec![Some("y".to_string()), Some("total".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn added_inputs_follow_existing_ones() {
+        let added = add_input(LOGISTIC, "y0", "0.1").unwrap();
+        assert_eq!(input_names(&added).unwrap(), vec!["r", "k", "y0"]);
+        assert!(added.contains("y0 { 0.1 }"));
+        let estimated = replace_initial_state(&added, "y", "y0").unwrap();
+        assert!(estimated.contains("y = y0,"));
+
+        let added = add_input("u_i { y = 1 }\nF_i { -y }", "y0", "1").unwrap();
+        assert_eq!(input_names(&added).unwrap(), vec!["y0"]);
+
+        for name in ["k", "z", "F_i"] {
+            let err = add_input(LOGISTIC, name, "1").unwrap_err();
+            assert!(err.contains("clashes"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn unbalanced_programs_are_rejected() {
+        assert!(input_names("in = [r\nr { 1 }").is_err());
+    }
+}
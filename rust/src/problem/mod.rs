@@ -1,18 +1,22 @@
 use crate::optimisers::{NelderMead, OptimisationResults, Optimiser};
-use diffsol::OdeBuilder;
 use nalgebra::DMatrix;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub mod builders;
 pub mod diffsol_problem;
+mod dsl;
+pub mod posterior;
+pub mod prior;
 pub use crate::cost::{CostMetric, RootMeanSquaredError, SumSquaredError};
 pub use builders::{BuilderOptimiserExt, BuilderParameterExt};
 pub use builders::{
-    DiffsolBackend, DiffsolConfig, DiffsolProblemBuilder, OptimiserSlot, ParameterSet,
-    ParameterSpec, ScalarProblemBuilder, VectorProblemBuilder,
+    DiffsolBackend, DiffsolConfig, DiffsolExperiment, DiffsolProblemBuilder, ObservedOutput,
+    OptimiserSlot, ParameterSet, ParameterSpec, ScalarProblemBuilder, VectorProblemBuilder,
 };
-pub use diffsol_problem::DiffsolProblem;
+pub use diffsol_problem::{DiffsolProblem, Experiment, ModelInput};
+pub use posterior::LogPosterior;
+pub use prior::Prior;
 
 pub type ObjectiveFn = Box<dyn Fn(&[f64]) -> f64 + Send + Sync>;
 pub type GradientFn = Box<dyn Fn(&[f64]) -> Vec<f64> + Send + Sync>;
@@ -40,6 +44,25 @@ impl CallableObjective {
     }
 }
 
+/// Factor turning residuals into ones whose sum of squares equals the cost of
+/// `metrics` up to a constant, i.e. the square root of their combined scale.
+pub(crate) fn residual_weight(metrics: &[Arc<dyn CostMetric>]) -> Result<f64, String> {
+    let mut scale = 0.0;
+    for metric in metrics {
+        scale += metric.sum_of_squares_scale().ok_or_else(|| {
+            format!(
+                "Cost metric '{}' is not a weighted sum of squared residuals",
+                metric.name()
+            )
+        })?;
+    }
+    if scale > 0.0 && scale.is_finite() {
+        Ok(scale.sqrt())
+    } else {
+        Err("Cost metrics must weight the squared residuals positively".to_string())
+    }
+}
+
 pub struct VectorProblem {
     objective: VectorObjectiveFn,
     data: Vec<f64>,
@@ -81,15 +104,41 @@ impl VectorProblem {
         ))
     }
 
-    fn evaluate(&self, x: &[f64]) -> Result<f64, String> {
+    /// Prediction minus observation, skipping missing (NaN) observations.
+    fn residuals(&self, x: &[f64]) -> Result<Vec<f64>, String> {
         let prediction = (self.objective)(x)?;
         self.validate_prediction(prediction.len())?;
 
-        let residuals: Vec<f64> = prediction
+        Ok(prediction
             .iter()
             .zip(self.data.iter())
+            .filter(|(_, obs)| !obs.is_nan())
             .map(|(pred, obs)| pred - obs)
-            .collect();
+            .collect())
+    }
+
+    /// Forward finite-difference Jacobian, as vector objectives carry no derivatives.
+    fn residuals_with_jacobian(&self, x: &[f64]) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        let residuals = self.residuals(x)?;
+        let mut jacobian = DMatrix::zeros(residuals.len(), x.len());
+        let mut perturbed = x.to_vec();
+
+        for j in 0..x.len() {
+            let step = f64::EPSILON.sqrt() * x[j].abs().max(1.0);
+            perturbed[j] = x[j] + step;
+            let shifted = self.residuals(&perturbed)?;
+            perturbed[j] = x[j];
+
+            for (i, (r_step, r)) in shifted.iter().zip(residuals.iter()).enumerate() {
+                jacobian[(i, j)] = (r_step - r) / step;
+            }
+        }
+
+        Ok((residuals, jacobian))
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Result<f64, String> {
+        let residuals = self.residuals(x)?;
         let total_cost = self
             .cost_metric
             .iter()
@@ -101,6 +150,10 @@ impl VectorProblem {
     fn evaluate_population(&self, xs: &[Vec<f64>]) -> Vec<Result<f64, String>> {
         xs.iter().map(|params| self.evaluate(params)).collect()
     }
+
+    fn cost_metrics(&self) -> &[Arc<dyn CostMetric>] {
+        &self.cost_metric
+    }
 }
 
 pub type SharedOptimiser = Arc<dyn Optimiser + Send + Sync>;
@@ -129,20 +182,7 @@ impl Problem {
         cost_metric: Vec<Arc<dyn CostMetric>>,
         default_optimiser: Option<SharedOptimiser>,
     ) -> Result<Self, String> {
-        let backend_problem = match config.backend {
-            DiffsolBackend::Dense => OdeBuilder::<diffsol::NalgebraMat<f64>>::new()
-                .atol([config.atol])
-                .rtol(config.rtol)
-                .build_from_diffsl(dsl)
-                .map_err(|e| format!("Failed to build ODE model: {}", e))
-                .map(|problem| diffsol_problem::BackendProblem::Dense(Box::new(problem))),
-            DiffsolBackend::Sparse => OdeBuilder::<diffsol::FaerSparseMat<f64>>::new()
-                .atol([config.atol])
-                .rtol(config.rtol)
-                .build_from_diffsl(dsl)
-                .map_err(|e| format!("Failed to build ODE model: {}", e))
-                .map(|problem| diffsol_problem::BackendProblem::Sparse(Box::new(problem))),
-        }?;
+        let backend_problem = diffsol_problem::BackendProblem::compile(dsl, &config)?;
 
         let problem = diffsol_problem::DiffsolProblem::new(
             backend_problem,
@@ -161,6 +201,24 @@ impl Problem {
         })
     }
 
+    /// Jointly fits several Diffsol experiments that share one parameter vector.
+    pub fn new_diffsol_experiments(
+        experiments: Vec<Experiment>,
+        config: DiffsolConfig,
+        parameter_specs: ParameterSet,
+        default_optimiser: Option<SharedOptimiser>,
+    ) -> Result<Self, String> {
+        let problem =
+            diffsol_problem::DiffsolProblem::from_experiments(config.clone(), experiments)?;
+
+        Ok(Problem {
+            kind: ProblemKind::Diffsol(Box::new(problem)),
+            config: config.to_map(),
+            parameter_specs,
+            default_optimiser,
+        })
+    }
+
     pub fn new_vector(
         objective: VectorObjectiveFn,
         data: Vec<f64>,
@@ -173,6 +231,9 @@ impl Problem {
         if data.is_empty() {
             return Err("Data must contain at least one element".to_string());
         }
+        if data.iter().all(|value| value.is_nan()) {
+            return Err("Data must contain at least one observed value".to_string());
+        }
 
         if !shape.is_empty() {
             let expected_len: usize = shape.iter().product();
@@ -233,6 +294,89 @@ impl Problem {
         }
     }
 
+    /// [`Problem::evaluate_population`] without the penalty that replaces failed Diffsol
+    /// solves, so each failure is reported as an error.
+    pub fn evaluate_population_strict(&self, xs: &[Vec<f64>]) -> Vec<Result<f64, String>> {
+        match &self.kind {
+            ProblemKind::Diffsol(problem) => {
+                let slices: Vec<&[f64]> = xs.iter().map(|x| x.as_slice()).collect();
+                problem.evaluate_population_strict(&slices)
+            }
+            _ => self.evaluate_population(xs),
+        }
+    }
+
+    /// Unreduced residuals (prediction minus observation) for Diffsol and Vector problems.
+    ///
+    /// Diffsol residuals are ordered time-major, i.e. all outputs at the first time
+    /// point followed by all outputs at the next. Missing (NaN or masked) observations
+    /// are left out, so cost metrics only count observed points.
+    pub fn residuals(&self, x: &[f64]) -> Result<Vec<f64>, String> {
+        match &self.kind {
+            ProblemKind::Callable(_) => {
+                Err("Residuals are only available for Diffsol and Vector problems".to_string())
+            }
+            ProblemKind::Diffsol(problem) => problem.residuals(x),
+            ProblemKind::Vector(vector) => vector.residuals(x),
+        }
+    }
+
+    /// Residuals together with their Jacobian (rows follow the residual ordering,
+    /// columns the parameters). Diffsol problems use forward sensitivities while
+    /// Vector problems fall back to finite differences.
+    pub fn residuals_with_jacobian(&self, x: &[f64]) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        match &self.kind {
+            ProblemKind::Callable(_) => {
+                Err("Residuals are only available for Diffsol and Vector problems".to_string())
+            }
+            ProblemKind::Diffsol(problem) => problem.residuals_with_jacobian(x),
+            ProblemKind::Vector(vector) => vector.residuals_with_jacobian(x),
+        }
+    }
+
+    /// Residuals scaled by the cost metrics so that their sum of squares equals the
+    /// cost up to a constant. Fails for metrics that are not a weighted sum of squares.
+    pub fn weighted_residuals(&self, x: &[f64]) -> Result<Vec<f64>, String> {
+        match &self.kind {
+            ProblemKind::Diffsol(problem) => problem.weighted_residuals(x),
+            ProblemKind::Vector(vector) => {
+                let weight = residual_weight(vector.cost_metrics())?;
+                let residuals = vector.residuals(x)?;
+                Ok(residuals.into_iter().map(|r| weight * r).collect())
+            }
+            ProblemKind::Callable(_) => self.residuals(x),
+        }
+    }
+
+    /// [`Problem::weighted_residuals`] together with their Jacobian.
+    pub fn weighted_residuals_with_jacobian(
+        &self,
+        x: &[f64],
+    ) -> Result<(Vec<f64>, DMatrix<f64>), String> {
+        match &self.kind {
+            ProblemKind::Diffsol(problem) => problem.weighted_residuals_with_jacobian(x),
+            ProblemKind::Vector(vector) => {
+                let weight = residual_weight(vector.cost_metrics())?;
+                let (residuals, jacobian) = vector.residuals_with_jacobian(x)?;
+                Ok((
+                    residuals.into_iter().map(|r| weight * r).collect(),
+                    jacobian * weight,
+                ))
+            }
+            ProblemKind::Callable(_) => self.residuals_with_jacobian(x),
+        }
+    }
+
+    /// Cost metrics reducing the residuals of Diffsol and Vector problems; empty for
+    /// callable objectives.
+    pub fn cost_metrics(&self) -> &[Arc<dyn CostMetric>] {
+        match &self.kind {
+            ProblemKind::Callable(_) => &[],
+            ProblemKind::Diffsol(problem) => problem.cost_metrics(),
+            ProblemKind::Vector(vector) => vector.cost_metrics(),
+        }
+    }
+
     pub fn get_config(&self, key: &str) -> Option<&f64> {
         self.config.get(key)
     }
@@ -609,6 +753,99 @@ F_i { (r * y) * (1 - (y / k)) }
         assert!(cost.abs() < 1e-10);
     }
 
+    #[test]
+    fn vector_problem_skips_missing_observations() {
+        let objective = |params: &[f64]| -> Result<Vec<f64>, String> {
+            Ok((0..5).map(|i| params[0] * i as f64).collect())
+        };
+        let data = vec![0.0, 1.0, f64::NAN, 3.0, 4.0];
+        let build = |metric: Arc<dyn CostMetric>, mask: Option<Vec<bool>>| {
+            let builder = VectorProblemBuilder::new()
+                .with_objective(objective)
+                .with_data(data.clone())
+                .with_parameter(ParameterSpec::new("slope", 1.0, None))
+                .with_cost_metric_arc(metric);
+            match mask {
+                Some(mask) => builder.with_mask(mask),
+                None => builder,
+            }
+            .build()
+            .expect("failed to build vector problem")
+        };
+
+        // The NaN observation and the masked one are both left out.
+        let mask = vec![true, true, true, false, true];
+        let problem = build(Arc::new(SumSquaredError::default()), Some(mask));
+        assert_eq!(problem.residuals(&[2.0]).unwrap(), vec![0.0, 1.0, 4.0]);
+        let (residuals, jacobian) = problem.residuals_with_jacobian(&[2.0]).unwrap();
+        assert_eq!(residuals.len(), 3);
+        assert_eq!(jacobian.shape(), (3, 1));
+        assert!((jacobian[(2, 0)] - 4.0).abs() < 1e-6);
+
+        // Normalising metrics count only the four observed points.
+        let rmse = build(Arc::new(RootMeanSquaredError::default()), None);
+        let expected = ((1.0 + 9.0 + 16.0) / 4.0_f64).sqrt();
+        assert!((rmse.evaluate(&[2.0]).unwrap() - expected).abs() < 1e-12);
+
+        let nll = build(Arc::new(crate::cost::GaussianNll::new(None, 1.0)), None);
+        let expected = 0.5 * 4.0 * (2.0 * std::f64::consts::PI).ln() + 0.5 * 26.0;
+        assert!((nll.evaluate(&[2.0]).unwrap() - expected).abs() < 1e-12);
+
+        let err = VectorProblemBuilder::new()
+            .with_objective(objective)
+            .with_data(data.clone())
+            .with_mask(vec![true; 4])
+            .build()
+            .err()
+            .expect("mask length mismatch should be rejected");
+        assert!(err.contains("Mask length 4"), "{}", err);
+
+        let err = VectorProblemBuilder::new()
+            .with_objective(objective)
+            .with_data(vec![f64::NAN; 3])
+            .build()
+            .err()
+            .expect("fully missing data should be rejected");
+        assert!(err.contains("at least one observed value"), "{}", err);
+    }
+
+    #[test]
+    fn weighted_residuals_follow_cost_metrics() {
+        let build = |metrics: Vec<Arc<dyn CostMetric>>| {
+            let builder = VectorProblemBuilder::new()
+                .with_objective(|params: &[f64]| -> Result<Vec<f64>, String> {
+                    Ok((0..4).map(|i| params[0] * i as f64).collect())
+                })
+                .with_data(vec![0.0, 1.0, 2.5, 2.0])
+                .with_parameter(ParameterSpec::new("slope", 1.0, None));
+            metrics
+                .into_iter()
+                .fold(builder, |builder, metric| {
+                    builder.with_cost_metric_arc(metric)
+                })
+                .build()
+                .expect("failed to build vector problem")
+        };
+
+        // Both metrics contribute 3 * r^2 in total, so the residuals scale by sqrt(3).
+        let problem = build(vec![
+            Arc::new(SumSquaredError::new(Some(2.0))),
+            Arc::new(crate::cost::GaussianNll::new(None, 0.5)),
+        ]);
+        let x = [1.5];
+        let residuals = problem.residuals(&x).unwrap();
+        let (weighted, jacobian) = problem.weighted_residuals_with_jacobian(&x).unwrap();
+        assert_eq!(problem.weighted_residuals(&x).unwrap(), weighted);
+        for (w, r) in weighted.iter().zip(&residuals) {
+            assert!((w - 3.0_f64.sqrt() * r).abs() < 1e-12);
+        }
+        assert!((jacobian[(3, 0)] - 3.0 * 3.0_f64.sqrt()).abs() < 1e-6);
+
+        let problem = build(vec![Arc::new(RootMeanSquaredError::default())]);
+        let err = problem.weighted_residuals(&x).unwrap_err();
+        assert!(err.contains("'rmse'"), "{}", err);
+    }
+
     #[test]
     fn vector_problem_empty_data_error() {
         let data = vec![];
@@ -682,4 +919,126 @@ F_i { (r * y) * (1 - (y / k)) }
         assert!((g[0] - (2.0 * x[0] + 3.0 * x[1])).abs() < 1e-12);
         assert!((g[1] - (3.0 * x[0] + 4.0 * x[1])).abs() < 1e-12);
     }
+
+    #[test]
+    fn vector_problem_exposes_residuals_and_jacobian() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let objective = Box::new(|params: &[f64]| -> Result<Vec<f64>, String> {
+            let a = params[0];
+            let b = params[1];
+            Ok((0..5).map(|i| a * (i as f64) + b).collect())
+        });
+
+        let mut params = ParameterSet::new();
+        params.push(ParameterSpec::new("a", 1.0, None));
+        params.push(ParameterSpec::new("b", 1.0, None));
+
+        let problem = Problem::new_vector(
+            objective,
+            data,
+            vec![5],
+            HashMap::new(),
+            params,
+            vec![Arc::new(SumSquaredError::default())],
+            None,
+        )
+        .expect("failed to create vector problem");
+
+        let residuals = problem.residuals(&[2.0, 1.0]).expect("residuals failed");
+        assert_eq!(residuals, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        let (residuals_j, jacobian) = problem
+            .residuals_with_jacobian(&[2.0, 1.0])
+            .expect("jacobian failed");
+        assert_eq!(residuals_j, residuals);
+        assert_eq!(jacobian.shape(), (5, 2));
+        for i in 0..5 {
+            assert!((jacobian[(i, 0)] - i as f64).abs() < 1e-6);
+            assert!((jacobian[(i, 1)] - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn diffsol_problem_residuals_match_cost() {
+        let problem = build_logistic_problem(DiffsolBackend::Dense);
+        let x = [1.1, 0.9];
+
+        let residuals = problem.residuals(&x).expect("residuals failed");
+        let sse: f64 = residuals.iter().map(|r| r * r).sum();
+        let cost = problem.evaluate(&x).expect("evaluation failed");
+
+        assert_eq!(residuals.len(), 6);
+        assert!((sse - cost).abs() < 1e-10);
+    }
+
+    #[cfg(not(feature = "cranelift-backend"))]
+    #[test]
+    fn diffsol_missing_observations_are_excluded_from_costs_and_gradients() {
+        let dsl = r#"
+in = [a]
+a { 1 }
+u_i { x = 1, y = 0 }
+F_i { -a * x, a * x }
+"#;
+        let t_span: Vec<f64> = (0..6).map(|i| i as f64 * 0.2).collect();
+        let mut data = DMatrix::from_fn(t_span.len(), 3, |i, j| match j {
+            0 => t_span[i],
+            1 => (-1.3 * t_span[i]).exp(),
+            _ => 1.0 - (-1.3 * t_span[i]).exp(),
+        });
+        data[(1, 1)] = f64::NAN;
+        // y is only sampled at every other time point.
+        let mask = DMatrix::from_fn(t_span.len(), 2, |i, j| j == 0 || i % 2 == 0);
+
+        let problem = DiffsolProblemBuilder::new()
+            .with_diffsl(dsl.to_string())
+            .with_data(data.clone())
+            .with_mask(mask)
+            .with_parameter(ParameterSpec::new("a", 1.0, None))
+            .with_cost_metric(RootMeanSquaredError::default())
+            .with_rtol(1e-8)
+            .with_atol(1e-10)
+            .build()
+            .expect("masked problem should build");
+
+        let x = [0.9];
+        let residuals = problem.residuals(&x).unwrap();
+        assert_eq!(residuals.len(), (t_span.len() - 1) + t_span.len() / 2);
+        assert!(residuals.iter().all(|r| r.is_finite()));
+
+        let n = residuals.len() as f64;
+        let rmse = (residuals.iter().map(|r| r * r).sum::<f64>() / n).sqrt();
+        assert!((problem.evaluate(&x).unwrap() - rmse).abs() < 1e-12);
+        assert!(problem.evaluate(&[1.3]).unwrap() < 1e-5);
+
+        let (cost, grad) = problem.evaluate_with_gradient(&x).unwrap();
+        let grad = grad.expect("diffsol problems provide gradients")[0];
+        assert!((cost - rmse).abs() < 1e-10);
+        let eps = 1e-6;
+        let fd = (problem.evaluate(&[x[0] + eps]).unwrap()
+            - problem.evaluate(&[x[0] - eps]).unwrap())
+            / (2.0 * eps);
+        assert!((fd - grad).abs() < 1e-5, "fd={} grad={}", fd, grad);
+
+        data[(2, 0)] = f64::NAN;
+        let err = DiffsolProblemBuilder::new()
+            .with_diffsl(dsl.to_string())
+            .with_data(data)
+            .build()
+            .err()
+            .expect("missing time points should be rejected");
+        assert!(err.contains("Time column"), "{}", err);
+    }
+
+    #[test]
+    fn scalar_problem_has_no_residuals() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| x[0] * x[0])
+            .build()
+            .expect("failed to build scalar problem");
+
+        let err = problem.residuals(&[1.0]).unwrap_err();
+        assert!(err.contains("only available for Diffsol and Vector problems"));
+        assert!(problem.residuals_with_jacobian(&[1.0]).is_err());
+    }
 }
@@ -0,0 +1,249 @@
+//! Log-posterior view of a [`Problem`] for the samplers.
+//!
+//! Samplers read `Problem::evaluate` as a negative log-likelihood and combine it with
+//! the priors attached to the parameter specs. That reading is only meaningful when the
+//! objective is a normalised likelihood: callable objectives are trusted to be one,
+//! while Diffsol and Vector problems must reduce their residuals with cost metrics that
+//! report [`CostMetric::is_negative_log_likelihood`].
+//!
+//! [`CostMetric::is_negative_log_likelihood`]: crate::cost::CostMetric::is_negative_log_likelihood
+
+use super::Problem;
+
+/// Log-likelihood plus log-prior of a problem whose costs form a proper likelihood.
+#[derive(Clone, Copy)]
+pub struct LogPosterior<'a> {
+    problem: &'a Problem,
+}
+
+impl<'a> LogPosterior<'a> {
+    /// Wrap `problem`, failing if any of its cost metrics is not a negative
+    /// log-likelihood (e.g. the default sum of squared errors).
+    pub fn new(problem: &'a Problem) -> Result<Self, String> {
+        if let Some(metric) = problem
+            .cost_metrics()
+            .iter()
+            .find(|metric| !metric.is_negative_log_likelihood())
+        {
+            return Err(format!(
+                "Cost metric '{}' is not a negative log-likelihood, so sampling exp(-cost) \
+                 targets the wrong distribution; use GaussianNLL instead \
+                 (SSE with weight w matches GaussianNLL with variance 1 / (2w))",
+                metric.name()
+            ));
+        }
+        Ok(Self { problem })
+    }
+
+    pub fn problem(&self) -> &'a Problem {
+        self.problem
+    }
+
+    /// Negated objective; `-inf` where the objective fails or is not a number.
+    pub fn log_likelihood(&self, x: &[f64]) -> f64 {
+        match self.problem.evaluate(x) {
+            Ok(value) if !value.is_nan() => -value,
+            _ => f64::NEG_INFINITY,
+        }
+    }
+
+    /// [`LogPosterior::log_likelihood`] of several points through one
+    /// [`Problem::evaluate_population_strict`] call, so failed solves are `-inf` rather
+    /// than the optimisers' finite penalty.
+    pub fn log_likelihood_population(&self, xs: &[Vec<f64>]) -> Vec<f64> {
+        self.problem
+            .evaluate_population_strict(xs)
+            .into_iter()
+            .map(|result| match result {
+                Ok(value) if !value.is_nan() => -value,
+                _ => f64::NEG_INFINITY,
+            })
+            .collect()
+    }
+
+    /// Sum of the parameter priors' log-densities; zero when no prior is attached.
+    pub fn log_prior(&self, x: &[f64]) -> f64 {
+        self.problem.parameter_specs().log_prior(x)
+    }
+
+    /// Unnormalised log-posterior density. The objective is not evaluated outside the
+    /// prior's support.
+    pub fn log_density(&self, x: &[f64]) -> f64 {
+        let log_prior = self.log_prior(x);
+        if log_prior == f64::NEG_INFINITY {
+            return log_prior;
+        }
+        self.log_likelihood(x) + log_prior
+    }
+
+    /// [`LogPosterior::log_density`] of several points through one population
+    /// evaluation over those inside the prior's support.
+    pub fn log_density_population(&self, xs: &[Vec<f64>]) -> Vec<f64> {
+        let log_priors: Vec<f64> = xs.iter().map(|x| self.log_prior(x)).collect();
+        let supported: Vec<Vec<f64>> = xs
+            .iter()
+            .zip(&log_priors)
+            .filter(|(_, &log_prior)| log_prior > f64::NEG_INFINITY)
+            .map(|(x, _)| x.clone())
+            .collect();
+        let mut log_likelihoods = self.log_likelihood_population(&supported).into_iter();
+
+        log_priors
+            .into_iter()
+            .map(|log_prior| {
+                if log_prior == f64::NEG_INFINITY {
+                    log_prior
+                } else {
+                    log_likelihoods.next().unwrap_or(f64::NEG_INFINITY) + log_prior
+                }
+            })
+            .collect()
+    }
+
+    /// Log-posterior density together with its gradient, when the problem provides one.
+    /// Outside the prior's support the density is `-inf` with no gradient, and the
+    /// objective is not evaluated.
+    pub fn log_density_with_gradient(&self, x: &[f64]) -> Result<(f64, Option<Vec<f64>>), String> {
+        let log_prior = self.log_prior(x);
+        if log_prior == f64::NEG_INFINITY {
+            return Ok((log_prior, None));
+        }
+        let (value, gradient) = self.problem.evaluate_with_gradient(x)?;
+        let specs = self.problem.parameter_specs();
+        let log_density = if value.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            -value + log_prior
+        };
+        let gradient = gradient.map(|gradient| {
+            gradient
+                .iter()
+                .zip(specs.log_prior_gradient(x))
+                .map(|(g, prior_gradient)| prior_gradient - g)
+                .collect()
+        });
+        Ok((log_density, gradient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::{GaussianNll, SumSquaredError};
+    use crate::problem::{
+        BuilderParameterExt, ParameterSpec, Prior, ScalarProblemBuilder, VectorProblemBuilder,
+    };
+
+    fn vector_problem(builder: VectorProblemBuilder) -> Problem {
+        builder
+            .with_objective(|x: &[f64]| Ok(vec![x[0], 2.0 * x[0]]))
+            .with_data(vec![1.0, 2.0])
+            .with_parameter(
+                ParameterSpec::new("x", 1.0, None).with_prior(Prior::normal(0.0, 1.0).unwrap()),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn rejects_costs_that_are_not_likelihoods() {
+        let default_cost = vector_problem(VectorProblemBuilder::new());
+        let err = LogPosterior::new(&default_cost)
+            .err()
+            .expect("SSE is rejected");
+        assert!(err.contains("'sse'"), "{err}");
+
+        let tempered = vector_problem(
+            VectorProblemBuilder::new().with_cost_metric(GaussianNll::new(Some(0.5), 1.0)),
+        );
+        assert!(LogPosterior::new(&tempered).is_ok());
+
+        let mixed = vector_problem(
+            VectorProblemBuilder::new()
+                .with_cost_metric(GaussianNll::new(None, 1.0))
+                .with_cost_metric(SumSquaredError::default()),
+        );
+        assert!(LogPosterior::new(&mixed).is_err());
+    }
+
+    #[test]
+    fn combines_gaussian_likelihood_with_priors() {
+        let problem = vector_problem(
+            VectorProblemBuilder::new().with_cost_metric(GaussianNll::new(None, 0.5)),
+        );
+        let posterior = LogPosterior::new(&problem).unwrap();
+
+        let x = [1.5];
+        let residuals = [0.5, 1.0];
+        let log_likelihood = -(residuals.len() as f64) * 0.5 * (std::f64::consts::PI).ln()
+            - residuals.iter().map(|r| r * r).sum::<f64>();
+        let log_prior = Prior::normal(0.0, 1.0).unwrap().log_density(1.5);
+
+        assert!((posterior.log_likelihood(&x) - log_likelihood).abs() < 1e-12);
+        assert!((posterior.log_density(&x) - (log_likelihood + log_prior)).abs() < 1e-12);
+        assert_eq!(
+            posterior.log_density_population(&[x.to_vec()]),
+            vec![posterior.log_density(&x)]
+        );
+    }
+
+    #[test]
+    fn gradient_includes_prior_and_support_is_respected() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective_and_gradient(|x: &[f64]| 0.5 * x[0] * x[0], |x: &[f64]| vec![x[0]])
+            .with_parameter(
+                ParameterSpec::new("x", 1.0, None).with_prior(Prior::gamma(2.0, 1.0).unwrap()),
+            )
+            .build()
+            .unwrap();
+        let posterior = LogPosterior::new(&problem).unwrap();
+
+        let (log_density, gradient) = posterior.log_density_with_gradient(&[2.0]).unwrap();
+        assert!((log_density - posterior.log_density(&[2.0])).abs() < 1e-12);
+        // d/dx [-x^2 / 2 + ln x - x] at x = 2.
+        assert!((gradient.unwrap()[0] - (-2.0 + 0.5 - 1.0)).abs() < 1e-12);
+        assert_eq!(posterior.log_density(&[-1.0]), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn gradient_is_not_evaluated_outside_the_prior_support() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective_and_gradient(
+                |x: &[f64]| {
+                    assert!(x[0] > 0.0, "objective evaluated outside the support");
+                    x[0]
+                },
+                |_: &[f64]| vec![1.0],
+            )
+            .with_parameter(
+                ParameterSpec::new("x", 1.0, None).with_prior(Prior::gamma(2.0, 1.0).unwrap()),
+            )
+            .build()
+            .unwrap();
+        let posterior = LogPosterior::new(&problem).unwrap();
+
+        let (log_density, gradient) = posterior.log_density_with_gradient(&[-1.0]).unwrap();
+        assert_eq!(log_density, f64::NEG_INFINITY);
+        assert!(gradient.is_none());
+    }
+
+    #[test]
+    fn population_skips_points_outside_the_prior_support() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| {
+                assert!(x[0] > 0.0, "objective evaluated outside the support");
+                x[0]
+            })
+            .with_parameter(
+                ParameterSpec::new("x", 1.0, None).with_prior(Prior::gamma(2.0, 1.0).unwrap()),
+            )
+            .build()
+            .unwrap();
+        let posterior = LogPosterior::new(&problem).unwrap();
+
+        let densities = posterior.log_density_population(&[vec![-1.0], vec![2.0], vec![-3.0]]);
+        assert_eq!(densities[0], f64::NEG_INFINITY);
+        assert!((densities[1] - posterior.log_density(&[2.0])).abs() < 1e-12);
+        assert_eq!(densities[2], f64::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,472 @@
+//! Convergence and summary diagnostics for MCMC draws.
+//!
+//! R-hat, effective sample sizes and MCSE follow Vehtari et al. (2021), "Rank-normalization,
+//! folding, and localization: An improved R-hat for assessing convergence of MCMC", as
+//! implemented by Stan and ArviZ. Chains of unequal length are truncated to the shortest
+//! one, and statistics that need more than three draws per chain are `NaN` otherwise.
+
+use super::Samples;
+use crate::problem::prior::inverse_normal_cdf;
+
+impl Samples {
+    /// Drop the first `n` draws of every chain.
+    ///
+    /// Like [`Samples::thin`], this also drops the starting point that Metropolis-Hastings
+    /// chains record ahead of their draws, so the returned chains hold draws only and the
+    /// draw count is the number of retained entries. Acceptance rates are carried over
+    /// unchanged.
+    pub fn burn_in(&self, n: usize) -> Samples {
+        self.select(|chain| chain.iter().skip(n).cloned().collect())
+    }
+
+    /// Keep every `k`-th draw of every chain, starting with the first.
+    pub fn thin(&self, k: usize) -> Samples {
+        let k = k.max(1);
+        self.select(|chain| chain.iter().step_by(k).cloned().collect())
+    }
+
+    fn select(&self, keep: impl Fn(&[Vec<f64>]) -> Vec<Vec<f64>>) -> Samples {
+        // Entries beyond the draw count are per-chain starting points at the front.
+        let entries = self.chains.iter().map(Vec::len).sum::<usize>();
+        let leading = entries.saturating_sub(self.draws) / self.chains.len().max(1);
+        let chains: Vec<Vec<Vec<f64>>> = self
+            .chains
+            .iter()
+            .map(|chain| keep(&chain[leading.min(chain.len())..]))
+            .collect();
+        let draws = chains.iter().map(Vec::len).sum::<usize>();
+        let mean_x = if draws > 0 {
+            let mut mean = vec![0.0; self.mean_x.len()];
+            for sample in chains.iter().flatten() {
+                for (m, value) in mean.iter_mut().zip(sample) {
+                    *m += value;
+                }
+            }
+            mean.iter_mut().for_each(|m| *m /= draws as f64);
+            mean
+        } else {
+            self.mean_x.clone()
+        };
+
+        Samples {
+            chains,
+            mean_x,
+            draws,
+            time: self.time,
+            acceptance_rates: self.acceptance_rates.clone(),
+        }
+    }
+
+    /// Rank-normalised split R-hat of each parameter; values above about 1.01 suggest
+    /// the chains have not mixed.
+    pub fn rhat(&self) -> Vec<f64> {
+        self.per_parameter(|chains| {
+            let split = split_chains(&chains);
+            let bulk = basic_rhat(&rank_normalise(&split));
+            let median = quantile(&sorted(split.iter().flatten().copied()), 0.5);
+            let folded: Vec<Vec<f64>> = split
+                .iter()
+                .map(|chain| chain.iter().map(|x| (x - median).abs()).collect())
+                .collect();
+            bulk.max(basic_rhat(&rank_normalise(&folded)))
+        })
+    }
+
+    /// Bulk effective sample size of each parameter, from rank-normalised split chains.
+    pub fn ess_bulk(&self) -> Vec<f64> {
+        self.per_parameter(|chains| ess(&rank_normalise(&split_chains(&chains))).0)
+    }
+
+    /// Tail effective sample size of each parameter: the smaller of the ESS of the 5%
+    /// and 95% quantile indicators.
+    pub fn ess_tail(&self) -> Vec<f64> {
+        self.per_parameter(|chains| {
+            let all = sorted(chains.iter().flatten().copied());
+            let split = split_chains(&chains);
+            [0.05, 0.95]
+                .iter()
+                .map(|&p| {
+                    let threshold = quantile(&all, p);
+                    let indicators: Vec<Vec<f64>> = split
+                        .iter()
+                        .map(|chain| {
+                            chain
+                                .iter()
+                                .map(|&x| if x <= threshold { 1.0 } else { 0.0 })
+                                .collect()
+                        })
+                        .collect();
+                    ess(&indicators).0
+                })
+                .fold(f64::INFINITY, f64::min)
+        })
+    }
+
+    /// Monte Carlo standard error of each parameter's posterior mean.
+    pub fn mcse(&self) -> Vec<f64> {
+        self.per_parameter(|chains| {
+            let all: Vec<f64> = chains.iter().flatten().copied().collect();
+            let n = all.len() as f64;
+            let mean = all.iter().sum::<f64>() / n;
+            let variance = all.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (variance / ess(&split_chains(&chains)).0).sqrt()
+        })
+    }
+
+    /// Integrated autocorrelation time of each parameter, in iterations: the number of
+    /// draws per chain needed for one effectively independent draw.
+    pub fn autocorrelation_time(&self) -> Vec<f64> {
+        self.per_parameter(|chains| ess(&chains).1)
+    }
+
+    /// Mean acceptance rate of each chain, when the sampler records one.
+    ///
+    /// Hamiltonian Monte Carlo reports its mean acceptance statistic instead.
+    pub fn acceptance_rates(&self) -> Option<&[f64]> {
+        self.acceptance_rates.as_deref()
+    }
+
+    /// Quantiles of the pooled draws, indexed as `[probability][parameter]` and
+    /// linearly interpolated between order statistics.
+    pub fn quantiles(&self, probabilities: &[f64]) -> Vec<Vec<f64>> {
+        let columns: Vec<Vec<f64>> = (0..self.dimension())
+            .map(|k| sorted(self.chains.iter().flatten().map(|sample| sample[k])))
+            .collect();
+        probabilities
+            .iter()
+            .map(|&p| {
+                columns
+                    .iter()
+                    .map(|column| quantile(column, p.clamp(0.0, 1.0)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Sample covariance of the pooled draws.
+    pub fn covariance(&self) -> Vec<Vec<f64>> {
+        let dimension = self.dimension();
+        let all: Vec<&Vec<f64>> = self.chains.iter().flatten().collect();
+        let n = all.len() as f64;
+        let mean: Vec<f64> = (0..dimension)
+            .map(|k| all.iter().map(|sample| sample[k]).sum::<f64>() / n)
+            .collect();
+        (0..dimension)
+            .map(|i| {
+                (0..dimension)
+                    .map(|j| {
+                        all.iter()
+                            .map(|sample| (sample[i] - mean[i]) * (sample[j] - mean[j]))
+                            .sum::<f64>()
+                            / (n - 1.0)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn dimension(&self) -> usize {
+        self.chains
+            .iter()
+            .flatten()
+            .next()
+            .map_or(self.mean_x.len(), Vec::len)
+    }
+
+    /// Apply `statistic` to each parameter's chains, truncated to a common length.
+    fn per_parameter(&self, statistic: impl Fn(Vec<Vec<f64>>) -> f64) -> Vec<f64> {
+        let length = self.chains.iter().map(Vec::len).min().unwrap_or(0);
+        (0..self.dimension())
+            .map(|k| {
+                if length < 4 {
+                    return f64::NAN;
+                }
+                let chains = self
+                    .chains
+                    .iter()
+                    .map(|chain| chain[..length].iter().map(|sample| sample[k]).collect())
+                    .collect();
+                statistic(chains)
+            })
+            .collect()
+    }
+}
+
+fn sorted(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut values: Vec<f64> = values.collect();
+    values.sort_by(f64::total_cmp);
+    values
+}
+
+/// Linearly interpolated quantile of sorted values.
+fn quantile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let position = p * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = (lower + 1).min(sorted.len() - 1);
+    sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+/// Split each chain into its first and second halves, dropping a middle draw.
+fn split_chains(chains: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    chains
+        .iter()
+        .flat_map(|chain| {
+            let half = chain.len() / 2;
+            [chain[..half].to_vec(), chain[chain.len() - half..].to_vec()]
+        })
+        .collect()
+}
+
+/// Replace draws by the normal scores of their pooled ranks, averaging ties.
+fn rank_normalise(chains: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let values: Vec<f64> = chains.iter().flatten().copied().collect();
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let total = values.len() as f64;
+    let mut scores = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        let score = inverse_normal_cdf((rank - 0.375) / (total + 0.25));
+        for &index in &order[start..end] {
+            scores[index] = score;
+        }
+        start = end;
+    }
+
+    let mut scores = scores.into_iter();
+    chains
+        .iter()
+        .map(|chain| scores.by_ref().take(chain.len()).collect())
+        .collect()
+}
+
+/// Potential scale reduction of equal-length chains.
+fn basic_rhat(chains: &[Vec<f64>]) -> f64 {
+    let m = chains.len() as f64;
+    let n = chains[0].len() as f64;
+    let means: Vec<f64> = chains
+        .iter()
+        .map(|chain| chain.iter().sum::<f64>() / n)
+        .collect();
+    let within = chains
+        .iter()
+        .zip(&means)
+        .map(|(chain, mean)| chain.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0))
+        .sum::<f64>()
+        / m;
+    let grand = means.iter().sum::<f64>() / m;
+    let between = n * means.iter().map(|mean| (mean - grand).powi(2)).sum::<f64>() / (m - 1.0);
+    (((n - 1.0) / n * within + between / n) / within).sqrt()
+}
+
+/// Effective sample size and integrated autocorrelation time of equal-length chains,
+/// using Geyer's initial monotone sequence estimator.
+fn ess(chains: &[Vec<f64>]) -> (f64, f64) {
+    let m = chains.len() as f64;
+    let length = chains[0].len();
+    let n = length as f64;
+
+    let means: Vec<f64> = chains
+        .iter()
+        .map(|chain| chain.iter().sum::<f64>() / n)
+        .collect();
+    // Mean over chains of the biased autocovariance at `lag`.
+    let autocovariance = |lag: usize| {
+        chains
+            .iter()
+            .zip(&means)
+            .map(|(chain, mean)| {
+                chain[..length - lag]
+                    .iter()
+                    .zip(&chain[lag..])
+                    .map(|(a, b)| (a - mean) * (b - mean))
+                    .sum::<f64>()
+                    / n
+            })
+            .sum::<f64>()
+            / m
+    };
+
+    let mean_variance = autocovariance(0) * n / (n - 1.0);
+    let mut var_plus = mean_variance * (n - 1.0) / n;
+    if chains.len() > 1 {
+        let grand = means.iter().sum::<f64>() / m;
+        var_plus += means.iter().map(|mean| (mean - grand).powi(2)).sum::<f64>() / (m - 1.0);
+    }
+    if var_plus.is_nan() || var_plus <= 0.0 {
+        return (f64::NAN, f64::NAN);
+    }
+    let rho = |lag: usize| 1.0 - (mean_variance - autocovariance(lag)) / var_plus;
+
+    let mut rho_hat = vec![0.0; length];
+    rho_hat[0] = 1.0;
+    let mut rho_even = 1.0;
+    let mut rho_odd = rho(1);
+    rho_hat[1] = rho_odd;
+
+    let mut t = 1;
+    while t + 3 < length && rho_even + rho_odd > 0.0 {
+        rho_even = rho(t + 1);
+        rho_odd = rho(t + 2);
+        if rho_even + rho_odd >= 0.0 {
+            rho_hat[t + 1] = rho_even;
+            rho_hat[t + 2] = rho_odd;
+        }
+        t += 2;
+    }
+    let max_t = t.saturating_sub(2);
+    if rho_even > 0.0 {
+        rho_hat[max_t + 1] = rho_even;
+    }
+
+    // Enforce a monotonically decreasing sequence of pair sums.
+    let mut t = 1;
+    while t + 2 <= max_t {
+        let previous = rho_hat[t - 1] + rho_hat[t];
+        if rho_hat[t + 1] + rho_hat[t + 2] > previous {
+            rho_hat[t + 1] = previous / 2.0;
+            rho_hat[t + 2] = previous / 2.0;
+        }
+        t += 2;
+    }
+
+    let total = m * n;
+    let tau = (-1.0 + 2.0 * rho_hat[..=max_t].iter().sum::<f64>() + rho_hat[max_t + 1])
+        .max(1.0 / total.log10());
+    (total / tau, tau)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+    use std::time::Duration;
+
+    /// Chains of a stationary AR(1) process `x_t = phi x_{t-1} + e_t` with unit variance.
+    fn ar1_chains(phi: f64, chains: usize, length: usize, offset: f64) -> Samples {
+        let mut rng = StdRng::seed_from_u64(9);
+        let scale = (1.0 - phi * phi).sqrt();
+        let chains: Vec<Vec<Vec<f64>>> = (0..chains)
+            .map(|c| {
+                let mut x: f64 = rng.sample(StandardNormal);
+                (0..length)
+                    .map(|_| {
+                        let e: f64 = rng.sample(StandardNormal);
+                        x = phi * x + scale * e;
+                        vec![x + offset * c as f64, 2.0 * x]
+                    })
+                    .collect()
+            })
+            .collect();
+        let draws = chains.iter().map(Vec::len).sum();
+        Samples::new(chains, vec![0.0, 0.0], draws, Duration::default())
+    }
+
+    #[test]
+    fn independent_draws_have_full_ess_and_unit_rhat() {
+        let samples = ar1_chains(0.0, 4, 2_000, 0.0);
+
+        for rhat in samples.rhat() {
+            assert!((rhat - 1.0).abs() < 0.01, "rhat = {}", rhat);
+        }
+        for ess in samples.ess_bulk() {
+            assert!((ess / 8_000.0 - 1.0).abs() < 0.15, "bulk ess = {}", ess);
+        }
+        for ess in samples.ess_tail() {
+            assert!(ess > 5_000.0, "tail ess = {}", ess);
+        }
+        for tau in samples.autocorrelation_time() {
+            assert!((tau - 1.0).abs() < 0.15, "tau = {}", tau);
+        }
+        let mcse = samples.mcse();
+        assert!((mcse[0] - 1.0 / 8_000f64.sqrt()).abs() < 2e-3);
+        assert!((mcse[1] / mcse[0] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn autocorrelated_draws_match_ar1_autocorrelation_time() {
+        // The integrated autocorrelation time of AR(1) is (1 + phi) / (1 - phi) = 9.
+        let samples = ar1_chains(0.8, 4, 20_000, 0.0);
+
+        for tau in samples.autocorrelation_time() {
+            assert!((tau / 9.0 - 1.0).abs() < 0.15, "tau = {}", tau);
+        }
+        for ess in samples.ess_bulk() {
+            assert!((ess / (80_000.0 / 9.0) - 1.0).abs() < 0.2, "ess = {}", ess);
+        }
+    }
+
+    #[test]
+    fn separated_chains_are_flagged_by_rhat() {
+        let samples = ar1_chains(0.5, 4, 500, 3.0);
+        let rhat = samples.rhat();
+        assert!(rhat[0] > 1.5, "rhat = {}", rhat[0]);
+        assert!(rhat[1] < 1.05, "rhat = {}", rhat[1]);
+    }
+
+    #[test]
+    fn quantiles_and_covariance_summarise_pooled_draws() {
+        let chains = vec![
+            vec![vec![1.0, 2.0], vec![2.0, 4.0]],
+            vec![vec![3.0, 6.0], vec![4.0, 8.0], vec![5.0, 10.0]],
+        ];
+        let samples = Samples::new(chains, vec![3.0, 6.0], 5, Duration::default());
+
+        let quantiles = samples.quantiles(&[0.0, 0.5, 0.125, 1.0]);
+        assert_eq!(quantiles[0], vec![1.0, 2.0]);
+        assert_eq!(quantiles[1], vec![3.0, 6.0]);
+        assert_eq!(quantiles[2], vec![1.5, 3.0]);
+        assert_eq!(quantiles[3], vec![5.0, 10.0]);
+
+        let covariance = samples.covariance();
+        assert_eq!(covariance, vec![vec![2.5, 5.0], vec![5.0, 10.0]]);
+
+        // Too few draws per chain for convergence diagnostics.
+        assert!(samples.rhat().iter().all(|rhat| rhat.is_nan()));
+    }
+
+    #[test]
+    fn burn_in_and_thinning_return_new_samples() {
+        let chains = vec![(0..10).map(|i| vec![i as f64]).collect::<Vec<_>>(); 2];
+        let samples = Samples::new(chains, vec![4.5], 20, Duration::default())
+            .with_acceptance_rates(vec![0.25, 0.5]);
+
+        let burned = samples.burn_in(4);
+        assert_eq!(
+            burned.chains()[0],
+            (4..10).map(|i| vec![i as f64]).collect::<Vec<_>>()
+        );
+        assert_eq!(burned.draws(), 12);
+        assert_eq!(burned.mean_x(), &[6.5]);
+        assert_eq!(burned.acceptance_rates(), Some(&[0.25, 0.5][..]));
+
+        let thinned = samples.thin(3);
+        assert_eq!(
+            thinned.chains()[1],
+            vec![vec![0.0], vec![3.0], vec![6.0], vec![9.0]]
+        );
+        assert_eq!(thinned.draws(), 8);
+        assert_eq!(samples.burn_in(20).draws(), 0);
+        assert_eq!(samples.burn_in(20).mean_x(), &[4.5]);
+
+        // Metropolis-Hastings chains lead with their starting point, which is no draw.
+        let chains = vec![(0..=10).map(|i| vec![i as f64]).collect::<Vec<_>>(); 2];
+        let samples = Samples::new(chains, vec![5.5], 20, Duration::default());
+        let burned = samples.burn_in(4);
+        assert_eq!(burned.draws(), 12);
+        assert_eq!(burned.chains()[0][0], vec![5.0]);
+        assert_eq!(burned.mean_x(), &[7.5]);
+        assert_eq!(samples.thin(5).chains()[1], vec![vec![1.0], vec![6.0]]);
+    }
+}
@@ -5,17 +5,21 @@
 //! across dedicated submodules (state, proposals, scheduler, results) so each
 //! concern can evolve independently while preserving a consistent public API.
 
-use super::{evaluate, Sampler, Samples};
-use crate::problem::Problem;
+use super::{evaluate, posterior_and_start, problem_is_parallel, Sampler, Samples};
+use crate::checkpoint::{Checkpoint, CheckpointConfig, CheckpointRng, CheckpointWriter};
+use crate::problem::{LogPosterior, Problem};
 use rand::prelude::*;
-use rand::rngs::StdRng;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+mod bounds;
 mod proposals;
 mod results;
 mod scheduler;
 mod state;
 
+pub use bounds::Bounding;
+pub use proposals::NestedProposal;
 pub use results::{NestedSample, NestedSamples};
 
 const DEFAULT_LIVE_POINTS: usize = 64;
@@ -30,7 +34,10 @@ pub struct DynamicNestedSampler {
     live_points: usize,
     expansion_factor: f64,
     termination_tol: f64,
+    bounding: Bounding,
+    proposal: NestedProposal,
     seed: Option<u64>,
+    checkpoint: Option<CheckpointConfig>,
 }
 
 /// Builder-style configuration and execution entry points
@@ -41,7 +48,10 @@ impl DynamicNestedSampler {
             live_points: DEFAULT_LIVE_POINTS,
             expansion_factor: DEFAULT_EXPANSION_FACTOR,
             termination_tol: DEFAULT_TERMINATION_TOL,
+            bounding: Bounding::Perturbation,
+            proposal: NestedProposal::Uniform,
             seed: None,
+            checkpoint: None,
         }
     }
 
@@ -63,6 +73,21 @@ impl DynamicNestedSampler {
         self
     }
 
+    /// Choose the region new live points are drawn from. Ellipsoidal bounds suit correlated
+    /// posteriors and multiple ellipsoids suit multimodal ones; the expansion factor
+    /// enlarges their volume by `1 + expansion_factor`.
+    pub fn with_bounding(mut self, bounding: Bounding) -> Self {
+        self.bounding = bounding;
+        self
+    }
+
+    /// Choose how new live points are generated within the bound. Constrained-MCMC
+    /// proposals scale better with dimension than uniform draws from the bound.
+    pub fn with_proposal(mut self, proposal: NestedProposal) -> Self {
+        self.proposal = proposal;
+        self
+    }
+
     /// Fix the RNG seed for reproducible sampling runs.
     pub fn with_seed(mut self, seed: u64) -> Self {
         self.seed = Some(seed);
@@ -70,81 +95,128 @@ impl DynamicNestedSampler {
     }
 
     /// Run the Dynamic Nested Sampling loop starting from the supplied position.
-    pub fn run_nested(&self, problem: &Problem, mut initial: Vec<f64>) -> NestedSamples {
-        let dimension = match (problem.dimension(), initial.len()) {
-            (d, _) if d > 0 => d,
-            (0, len) if len > 0 => len,
-            _ => 1,
-        };
-
-        if initial.len() < dimension {
-            initial.resize(dimension, 0.0);
-        } else if initial.len() > dimension {
-            initial.truncate(dimension);
-        }
+    pub fn run_nested(
+        &self,
+        problem: &Problem,
+        initial: Vec<f64>,
+    ) -> Result<NestedSamples, String> {
+        let (_, initial) = posterior_and_start(problem, initial)?;
+        let dimension = initial.len();
 
         let mut rng = match self.seed {
-            Some(seed) => StdRng::seed_from_u64(seed),
-            None => StdRng::from_rng(&mut rand::rng()),
+            Some(seed) => CheckpointRng::seed_from_u64(seed),
+            None => CheckpointRng::from_rng(&mut rand::rng()),
         };
 
-        let parallel_enabled = problem
-            .get_config("parallel")
-            .copied()
-            .map(|value| value != 0.0)
-            .unwrap_or(false);
+        let parallel_enabled = problem_is_parallel(problem);
 
         #[cfg(test)]
         eprintln!("run_nested parallel enabled: {}", parallel_enabled);
 
         let start_time = Instant::now();
 
-        let bounds = state::Bounds::from_problem(problem, &initial, dimension);
+        let prior = state::PriorTransform::from_problem(problem, dimension)?;
         let live_points = state::initial_live_points(
             problem,
-            &bounds,
+            &prior,
             &mut rng,
             self.live_points,
-            self.expansion_factor,
             parallel_enabled,
         );
 
         if live_points.len() < MIN_LIVE_POINTS {
-            return NestedSamples::degenerate(initial);
+            return Ok(NestedSamples::degenerate(initial));
         }
 
-        let mut sampler_state = state::SamplerState::new(live_points);
-        let proposal_engine =
-            proposals::ProposalEngine::new(sampler_state.dimension(), self.expansion_factor);
-        let scheduler = scheduler::Scheduler::new(
+        let run = NestedRun {
+            initial,
+            prior,
+            state: state::SamplerState::new(live_points),
+            scheduler: self.scheduler(),
+            proposals: self.proposal_engine(dimension),
+            rng,
+            iteration: 0,
+        };
+
+        self.drive(problem, run, start_time)
+    }
+
+    /// Write the sampler state to `path` every `every` iterations so an interrupted
+    /// run can continue with [`DynamicNestedSampler::resume_from`]. The run fails if a
+    /// checkpoint cannot be written.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>, every: usize) -> Self {
+        self.checkpoint = Some(CheckpointConfig::new(path, every));
+        self
+    }
+
+    /// Continue a run from a checkpoint written by [`DynamicNestedSampler::with_checkpoint`].
+    ///
+    /// The sampler configuration should match the one that wrote the checkpoint for the
+    /// resumed run to reproduce an uninterrupted one.
+    pub fn resume_from(
+        &self,
+        problem: &Problem,
+        path: impl AsRef<Path>,
+    ) -> Result<NestedSamples, String> {
+        LogPosterior::new(problem)?;
+        let checkpoint = Checkpoint::load(path.as_ref(), "dynamic_nested")?;
+        let run =
+            NestedRun::from_checkpoint(&checkpoint, problem, self.scheduler(), |dimension| {
+                self.proposal_engine(dimension)
+            })?;
+
+        let elapsed = checkpoint.duration("elapsed")?;
+        let start_time = Instant::now()
+            .checked_sub(elapsed)
+            .unwrap_or_else(Instant::now);
+        self.drive(problem, run, start_time)
+    }
+
+    fn scheduler(&self) -> scheduler::Scheduler {
+        scheduler::Scheduler::new(
             self.live_points,
             self.expansion_factor,
             self.termination_tol,
-        );
+        )
+    }
+
+    fn proposal_engine(&self, dimension: usize) -> proposals::ProposalEngine {
+        proposals::ProposalEngine::new(
+            dimension,
+            self.expansion_factor,
+            self.bounding,
+            self.proposal,
+        )
+    }
 
+    /// Run the live-set loop to termination and assemble the nested samples, failing if
+    /// a due checkpoint cannot be written.
+    fn drive(
+        &self,
+        problem: &Problem,
+        mut run: NestedRun,
+        start_time: Instant,
+    ) -> Result<NestedSamples, String> {
         let max_iterations = MAX_ITERATION_MULTIPLIER
             .saturating_mul(self.live_points)
-            .saturating_mul(sampler_state.dimension().max(1));
+            .saturating_mul(run.state.dimension().max(1));
 
         let config = RunLoopConfig {
-            parallel: parallel_enabled,
+            parallel: problem_is_parallel(problem),
             max_iterations,
+            start_time,
+            checkpoint: self.checkpoint.as_ref(),
         };
 
-        let mut result = run_loop(
-            problem,
-            &bounds,
-            &mut sampler_state,
-            proposal_engine,
-            scheduler,
-            config,
-            &mut rng,
-        )
-        .unwrap_or_else(|state| NestedSamples::degenerate_with_state(initial, state));
+        let mut result = match run_loop(problem, &mut run, config)? {
+            Some(result) => result,
+            None => NestedSamples::degenerate_with_state(run.initial, run.state),
+        };
 
         result.set_time(start_time.elapsed());
+        result.set_proposal_counts(run.proposals.proposed(), run.proposals.accepted());
 
-        result
+        Ok(result)
     }
 }
 
@@ -155,103 +227,166 @@ impl Default for DynamicNestedSampler {
 }
 
 impl Sampler for DynamicNestedSampler {
-    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Samples {
-        self.run_nested(problem, initial).to_samples()
+    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Result<Samples, String> {
+        self.run_nested(problem, initial)
+            .map(|nested| nested.to_samples())
     }
 }
 
 #[derive(Clone, Copy)]
-struct RunLoopConfig {
+struct RunLoopConfig<'a> {
     parallel: bool,
     max_iterations: usize,
+    start_time: Instant,
+    checkpoint: Option<&'a CheckpointConfig>,
 }
 
-/// Execute the adaptive live-set loop, returning posterior samples on success.
+/// Everything a run carries between iterations, and therefore what a checkpoint holds.
+struct NestedRun {
+    initial: Vec<f64>,
+    prior: state::PriorTransform,
+    state: state::SamplerState,
+    scheduler: scheduler::Scheduler,
+    proposals: proposals::ProposalEngine,
+    rng: CheckpointRng,
+    iteration: usize,
+}
+
+impl NestedRun {
+    fn checkpoint(&self, start_time: Instant) -> CheckpointWriter {
+        let mut writer = CheckpointWriter::new("dynamic_nested");
+        writer.count("iteration", self.iteration);
+        writer.duration("elapsed", start_time.elapsed());
+        writer.values("initial", &self.initial);
+        writer.rng("rng", &self.rng);
+        self.state.write_checkpoint(&mut writer);
+        self.scheduler.write_checkpoint(&mut writer);
+        self.proposals.write_checkpoint(&mut writer);
+        writer
+    }
+
+    /// Rebuild a run from a checkpoint; the prior transform is re-derived from `problem`.
+    fn from_checkpoint(
+        checkpoint: &Checkpoint,
+        problem: &Problem,
+        mut scheduler: scheduler::Scheduler,
+        proposal_engine: impl FnOnce(usize) -> proposals::ProposalEngine,
+    ) -> Result<Self, String> {
+        let state = state::SamplerState::from_checkpoint(checkpoint)?;
+        if problem.dimension() > 0 && state.dimension() != problem.dimension() {
+            return Err(format!(
+                "Checkpoint dimension {} does not match problem dimension {}",
+                state.dimension(),
+                problem.dimension()
+            ));
+        }
+        scheduler.restore_checkpoint(checkpoint)?;
+        let mut proposals = proposal_engine(state.dimension());
+        proposals.restore_checkpoint(checkpoint)?;
+
+        let initial = checkpoint.values("initial")?;
+        let prior = state::PriorTransform::from_problem(problem, state.dimension())?;
+        Ok(Self {
+            initial,
+            prior,
+            state,
+            scheduler,
+            proposals,
+            rng: checkpoint.rng("rng")?,
+            iteration: checkpoint.count("iteration")?,
+        })
+    }
+}
+
+/// Execute the adaptive live-set loop, returning posterior samples on success and
+/// `None` once the iteration cap is hit. Fails if a due checkpoint cannot be written.
 fn run_loop(
     problem: &Problem,
-    bounds: &state::Bounds,
-    state: &mut state::SamplerState,
-    mut proposals: proposals::ProposalEngine,
-    mut scheduler: scheduler::Scheduler,
-    config: RunLoopConfig,
-    rng: &mut StdRng,
-) -> Result<NestedSamples, state::SamplerState> {
-    let mut iterations = 0usize;
-
-    while iterations < config.max_iterations {
-        iterations += 1;
-
-        let info_estimate = results::information_estimate(state.posterior());
-        let target_live = scheduler.target(info_estimate, state.live_point_count());
-        state.adjust_live_set(target_live);
-
-        if state.live_point_count() < target_live {
+    run: &mut NestedRun,
+    config: RunLoopConfig<'_>,
+) -> Result<Option<NestedSamples>, String> {
+    while run.iteration < config.max_iterations {
+        run.iteration += 1;
+
+        let info_estimate = results::information_estimate(run.state.posterior());
+        let target_live = run
+            .scheduler
+            .target(info_estimate, run.state.live_point_count());
+        run.state.adjust_live_set(target_live);
+
+        if run.state.live_point_count() < target_live {
             let mut attempts = 0usize;
             let max_attempts = target_live.saturating_mul(16).max(128);
 
-            while state.live_point_count() < target_live && attempts < max_attempts {
+            while run.state.live_point_count() < target_live && attempts < max_attempts {
                 attempts = attempts.saturating_add(1);
 
-                let threshold = state.min_log_likelihood();
-                if let Some(new_point) = proposals.draw(
-                    rng,
+                let threshold = run.state.min_log_likelihood();
+                if let Some(new_point) = run.proposals.draw(
+                    &mut run.rng,
                     problem,
-                    state.live_points(),
-                    bounds,
+                    run.state.live_points(),
+                    &run.prior,
                     threshold,
                     config.parallel,
                 ) {
-                    state.insert_live_point(new_point);
+                    run.state.insert_live_point(new_point);
                 } else {
                     break;
                 }
             }
         }
 
-        if state.live_points().is_empty() {
+        if run.state.live_points().is_empty() {
             break;
         }
 
-        let worst_index = match state.worst_index() {
+        let worst_index = match run.state.worst_index() {
             Some(idx) => idx,
             None => break,
         };
 
-        let removed = match state.remove_at(worst_index) {
+        let removed = match run.state.remove_at(worst_index) {
             Some(value) => value,
             None => break,
         };
         let threshold = removed.log_likelihood();
 
-        if let Some(new_point) = proposals.draw(
-            rng,
+        if let Some(new_point) = run.proposals.draw(
+            &mut run.rng,
             problem,
-            state.live_points(),
-            bounds,
+            run.state.live_points(),
+            &run.prior,
             threshold,
             config.parallel,
         ) {
-            state.accept_removed(removed);
-            state.insert_live_point(new_point);
+            run.state.accept_removed(removed);
+            run.state.insert_live_point(new_point);
         } else {
-            state.restore_removed(removed);
+            run.state.restore_removed(removed);
         }
 
-        if scheduler.should_terminate(state, info_estimate) {
+        if run.scheduler.should_terminate(&run.state, info_estimate) {
             break;
         }
+
+        if let Some(checkpoint) = config.checkpoint {
+            if run.iteration < config.max_iterations && checkpoint.is_due(run.iteration) {
+                run.checkpoint(config.start_time).save(checkpoint.path())?;
+            }
+        }
     }
 
-    if iterations >= config.max_iterations {
-        return Err(state.clone());
+    if run.iteration >= config.max_iterations {
+        return Ok(None);
     }
 
-    state.finalize();
+    run.state.finalize();
 
-    Ok(results::NestedSamples::build(
-        state.posterior(),
-        state.dimension(),
-    ))
+    Ok(Some(results::NestedSamples::build(
+        run.state.posterior(),
+        run.state.dimension(),
+    )))
 }
 
 /// Compute `log(exp(a) - exp(b))` while guarding against catastrophic cancellation.
@@ -281,7 +416,8 @@ pub(super) fn logspace_sub(a: f64, b: f64) -> Option<f64> {
 mod tests {
     use super::*;
     use crate::problem::builders::BuilderParameterExt;
-    use crate::problem::{ParameterSpec, ScalarProblemBuilder};
+    use crate::problem::{ParameterSpec, Prior, ScalarProblemBuilder};
+
     fn gaussian_problem(mean: f64, sigma: f64) -> crate::problem::Problem {
         let log_norm = sigma.ln() + 0.5 * (2.0 * std::f64::consts::PI).ln();
         ScalarProblemBuilder::new()
@@ -307,7 +443,7 @@ mod tests {
             .with_termination_tolerance(2e-4)
             .with_seed(7);
 
-        let nested = sampler.run_nested(&problem, vec![1.5]);
+        let nested = sampler.run_nested(&problem, vec![1.5]).unwrap();
 
         assert!(nested.draws() > 0, "expected posterior samples");
         assert!(nested.log_evidence().is_finite());
@@ -336,6 +472,203 @@ mod tests {
         assert!(evidence_sum.is_finite() && evidence_sum > 0.0);
     }
 
+    #[test]
+    fn dynamic_nested_uses_parameter_prior_transform() {
+        // Unit Gaussian likelihood for an observation at 1 under a N(3, 1) prior: the
+        // evidence is N(1; 3, sqrt(2)) and the posterior is N(2, 0.5).
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| {
+                0.5 * (x[0] - 1.0).powi(2) + 0.5 * (2.0 * std::f64::consts::PI).ln()
+            })
+            .with_parameter(
+                ParameterSpec::new("x", 0.0, None).with_prior(Prior::normal(3.0, 1.0).unwrap()),
+            )
+            .build()
+            .expect("failed to build conjugate problem");
+
+        let nested = DynamicNestedSampler::new()
+            .with_live_points(128)
+            .with_seed(11)
+            .run_nested(&problem, vec![0.0])
+            .unwrap();
+
+        let expected = -1.0 - 0.5 * (4.0 * std::f64::consts::PI).ln();
+        assert!(
+            (nested.log_evidence() - expected).abs() < 0.3,
+            "log evidence {} vs {}",
+            nested.log_evidence(),
+            expected
+        );
+        assert!(
+            (nested.mean()[0] - 2.0).abs() < 0.15,
+            "mean {:?}",
+            nested.mean()
+        );
+    }
+
+    /// Unit-variance bivariate Gaussian likelihood with correlation 0.99 inside a
+    /// `[-5, 5]^2` prior box, so the evidence is 1 / 100.
+    fn correlated_problem() -> crate::problem::Problem {
+        let rho: f64 = 0.99;
+        let log_norm = (2.0 * std::f64::consts::PI).ln() + 0.5 * (1.0 - rho * rho).ln();
+        ScalarProblemBuilder::new()
+            .with_objective(move |x: &[f64]| {
+                0.5 * (x[0] * x[0] - 2.0 * rho * x[0] * x[1] + x[1] * x[1]) / (1.0 - rho * rho)
+                    + log_norm
+            })
+            .with_parameter(ParameterSpec::new("x", 0.0, Some((-5.0, 5.0))))
+            .with_parameter(ParameterSpec::new("y", 0.0, Some((-5.0, 5.0))))
+            .build()
+            .expect("failed to build correlated problem")
+    }
+
+    #[test]
+    fn ellipsoidal_bounds_are_efficient_on_correlated_posteriors() {
+        let expected = -(100.0f64).ln();
+        let run = |bounding| {
+            DynamicNestedSampler::new()
+                .with_live_points(128)
+                .with_bounding(bounding)
+                .with_seed(5)
+                .run_nested(&correlated_problem(), vec![0.0, 0.0])
+                .unwrap()
+        };
+
+        let perturbation = run(Bounding::Perturbation);
+        assert!(perturbation.proposals() > 0);
+        for bounding in [Bounding::Ellipsoid, Bounding::MultiEllipsoid] {
+            let nested = run(bounding);
+            assert!(
+                (nested.log_evidence() - expected).abs() < 0.4,
+                "{:?} log evidence {} vs {}",
+                bounding,
+                nested.log_evidence(),
+                expected
+            );
+            assert!(
+                nested.efficiency() > 3.0 * perturbation.efficiency(),
+                "{:?} efficiency {} vs perturbation {}",
+                bounding,
+                nested.efficiency(),
+                perturbation.efficiency()
+            );
+            assert_eq!(
+                nested.accepted_proposals() as f64 / nested.proposals() as f64,
+                nested.efficiency()
+            );
+        }
+    }
+
+    #[test]
+    fn constrained_mcmc_proposals_recover_correlated_evidence() {
+        let expected = -(100.0f64).ln();
+        for bounding in [Bounding::Perturbation, Bounding::Ellipsoid] {
+            for proposal in [
+                NestedProposal::RandomWalk { steps: 20 },
+                NestedProposal::Slice { slices: 2 },
+            ] {
+                let nested = DynamicNestedSampler::new()
+                    .with_live_points(128)
+                    .with_bounding(bounding)
+                    .with_proposal(proposal)
+                    .with_seed(5)
+                    .run_nested(&correlated_problem(), vec![0.0, 0.0])
+                    .unwrap();
+                assert!(
+                    (nested.log_evidence() - expected).abs() < 0.4,
+                    "{:?}/{:?} log evidence {} vs {}",
+                    bounding,
+                    proposal,
+                    nested.log_evidence(),
+                    expected
+                );
+                assert!(
+                    nested.mean().iter().all(|value| value.abs() < 0.3),
+                    "{:?}/{:?} mean {:?}",
+                    bounding,
+                    proposal,
+                    nested.mean()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn simulated_evidence_error_matches_run_to_run_scatter() {
+        let problem = gaussian_problem(0.5, 0.8);
+        let runs: Vec<NestedSamples> = (0..12)
+            .map(|seed| {
+                DynamicNestedSampler::new()
+                    .with_live_points(64)
+                    .with_seed(seed)
+                    .run_nested(&problem, vec![0.5])
+                    .unwrap()
+            })
+            .collect();
+
+        let estimates: Vec<f64> = runs.iter().map(NestedSamples::log_evidence).collect();
+        let mean = estimates.iter().sum::<f64>() / estimates.len() as f64;
+        let scatter = (estimates.iter().map(|z| (z - mean).powi(2)).sum::<f64>()
+            / (estimates.len() - 1) as f64)
+            .sqrt();
+
+        for nested in &runs[..3] {
+            let simulated = nested.simulated_log_evidence_error(200, 1);
+            assert!(
+                (0.5 * scatter..2.0 * scatter).contains(&simulated),
+                "simulated error {} vs scatter {}",
+                simulated,
+                scatter
+            );
+            assert!(
+                (0.5 * scatter..3.0 * scatter).contains(&nested.log_evidence_error()),
+                "Skilling error {} vs scatter {}",
+                nested.log_evidence_error(),
+                scatter
+            );
+        }
+    }
+
+    #[test]
+    fn dynamic_nested_resume_matches_uninterrupted_run() {
+        let problem = gaussian_problem(0.5, 0.8);
+        for (idx, (bounding, proposal)) in [
+            (Bounding::Perturbation, NestedProposal::Uniform),
+            (Bounding::Ellipsoid, NestedProposal::Uniform),
+            (Bounding::Ellipsoid, NestedProposal::Slice { slices: 2 }),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let path = std::env::temp_dir().join(format!(
+                "chronopt-dns-resume-{}-{}.checkpoint",
+                std::process::id(),
+                idx
+            ));
+            let sampler = DynamicNestedSampler::new()
+                .with_live_points(32)
+                .with_bounding(bounding)
+                .with_proposal(proposal)
+                .with_seed(3);
+
+            let expected = sampler.run_nested(&problem, vec![0.5]).unwrap();
+            sampler
+                .clone()
+                .with_checkpoint(&path, 50)
+                .run_nested(&problem, vec![0.5])
+                .unwrap();
+            let resumed = sampler
+                .resume_from(&problem, &path)
+                .expect("checkpoint to resume");
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(resumed.draws(), expected.draws());
+            assert_eq!(resumed.mean(), expected.mean());
+            assert_eq!(resumed.log_evidence(), expected.log_evidence());
+            assert_eq!(resumed.proposals(), expected.proposals());
+        }
+    }
+
     #[test]
     fn logspace_sub_basic() {
         // Test basic functionality: log(exp(5) - exp(3)) = log(exp(5) * (1 - exp(-2)))
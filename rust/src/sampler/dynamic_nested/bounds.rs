@@ -0,0 +1,387 @@
+//! Ellipsoidal bounds on the live points in the unit hypercube.
+//!
+//! A single ellipsoid follows the live set's covariance, so correlated posteriors no
+//! longer waste proposals in the corners of an axis-aligned box. For multimodal or
+//! curved contours the live points are split recursively with 2-means and each cluster
+//! gets its own ellipsoid while that shrinks the bounded volume (Feroz, Hobson &
+//! Bridges, 2009).
+
+use crate::checkpoint::{Checkpoint, CheckpointRng, CheckpointWriter};
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// A split is kept only if the child ellipsoids fill at most this fraction of the
+/// parent's volume.
+const SPLIT_VOLUME_FRACTION: f64 = 0.5;
+/// Lloyd iterations used to split a cluster in two.
+const TWO_MEANS_ITERATIONS: usize = 20;
+/// Relative ridge added to covariances so flat clusters still give proper ellipsoids.
+const COVARIANCE_RIDGE: f64 = 1e-10;
+
+/// Region of the unit hypercube that new live points are drawn from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bounding {
+    /// Gaussian perturbations of a random live point, scaled by the live set's extent.
+    Perturbation,
+    /// Uniform draws from one ellipsoid enclosing the live points.
+    Ellipsoid,
+    /// Uniform draws from the union of ellipsoids enclosing clusters of live points.
+    MultiEllipsoid,
+}
+
+/// Ellipsoid `{x : (x - c)^T A^{-1} (x - c) <= 1}` with `A = L L^T`.
+#[derive(Clone, Debug)]
+pub(super) struct Ellipsoid {
+    centre: DVector<f64>,
+    factor: DMatrix<f64>,
+    precision: DMatrix<f64>,
+    /// `ln det L`, the log-volume up to the unit ball's volume.
+    log_volume: f64,
+}
+
+impl Ellipsoid {
+    /// Ellipsoid shaped by the points' covariance that just encloses them, with its
+    /// volume then enlarged by `enlargement`.
+    fn enclosing(points: &[DVector<f64>], enlargement: f64) -> Option<Self> {
+        let dimension = points.first()?.len();
+        let count = points.len() as f64;
+        let centre = points.iter().sum::<DVector<f64>>() / count;
+
+        let mut covariance = DMatrix::zeros(dimension, dimension);
+        for point in points {
+            let centred = point - &centre;
+            covariance += &centred * centred.transpose();
+        }
+        covariance /= count;
+        let ridge = (covariance.trace() / dimension as f64).max(f64::MIN_POSITIVE);
+        for i in 0..dimension {
+            covariance[(i, i)] += COVARIANCE_RIDGE * ridge;
+        }
+
+        let inverse = covariance.clone().cholesky()?.inverse();
+        let radius = points
+            .iter()
+            .map(|point| {
+                let centred = point - &centre;
+                (centred.transpose() * &inverse * &centred)[(0, 0)]
+            })
+            .fold(0.0, f64::max)
+            .max(f64::MIN_POSITIVE);
+        let scale = radius * enlargement.powf(2.0 / dimension as f64);
+
+        let shape = covariance * scale;
+        let factor = shape.clone().cholesky()?.l();
+        let log_volume = factor.diagonal().iter().map(|v| v.ln()).sum();
+        Some(Self {
+            centre,
+            factor,
+            precision: inverse / scale,
+            log_volume,
+        })
+    }
+
+    fn contains(&self, point: &DVector<f64>) -> bool {
+        let centred = point - &self.centre;
+        (centred.transpose() * &self.precision * &centred)[(0, 0)] <= 1.0
+    }
+
+    /// Uniform draw from the ellipsoid.
+    fn sample(&self, rng: &mut CheckpointRng) -> DVector<f64> {
+        let dimension = self.centre.len();
+        let direction =
+            DVector::from_fn(dimension, |_, _| rng.sample::<f64, _>(StandardNormal)).normalize();
+        let radius = rng.random::<f64>().powf(1.0 / dimension as f64);
+        &self.centre + &self.factor * (direction * radius)
+    }
+}
+
+/// Union of ellipsoids bounding the live points.
+#[derive(Clone, Debug)]
+pub(super) struct EllipsoidBound {
+    ellipsoids: Vec<Ellipsoid>,
+}
+
+impl EllipsoidBound {
+    /// Fit one ellipsoid, or with `multiple` a set of ellipsoids, to the unit-cube
+    /// positions of the live points, enlarging each by `enlargement` in volume.
+    pub fn fit(units: &[Vec<f64>], multiple: bool, enlargement: f64) -> Option<Self> {
+        let points: Vec<DVector<f64>> = units
+            .iter()
+            .map(|unit| DVector::from_column_slice(unit))
+            .collect();
+        let root = Ellipsoid::enclosing(&points, enlargement)?;
+
+        let mut ellipsoids = Vec::new();
+        if multiple {
+            split(points, root, enlargement, &mut ellipsoids);
+        } else {
+            ellipsoids.push(root);
+        }
+        Some(Self { ellipsoids })
+    }
+
+    /// Draw a point uniformly from the union of the ellipsoids, or `None` when the draw
+    /// is rejected for leaving the unit cube or to correct for overlapping ellipsoids.
+    pub fn sample(&self, rng: &mut CheckpointRng) -> Option<Vec<f64>> {
+        let largest = self
+            .ellipsoids
+            .iter()
+            .map(|ellipsoid| ellipsoid.log_volume)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = self
+            .ellipsoids
+            .iter()
+            .map(|ellipsoid| (ellipsoid.log_volume - largest).exp())
+            .collect();
+        let mut pick = rng.random::<f64>() * weights.iter().sum::<f64>();
+        let mut chosen = self.ellipsoids.len() - 1;
+        for (index, weight) in weights.iter().enumerate() {
+            if pick < *weight {
+                chosen = index;
+                break;
+            }
+            pick -= weight;
+        }
+
+        let point = self.ellipsoids[chosen].sample(rng);
+        if point.iter().any(|value| !(0.0..=1.0).contains(value)) {
+            return None;
+        }
+        if self.ellipsoids.len() > 1 {
+            let overlaps = self
+                .ellipsoids
+                .iter()
+                .filter(|ellipsoid| ellipsoid.contains(&point))
+                .count();
+            if rng.random::<f64>() * overlaps as f64 >= 1.0 {
+                return None;
+            }
+        }
+        Some(point.data.into())
+    }
+
+    /// Shape factor of the smallest ellipsoid containing `unit`, or of the largest
+    /// ellipsoid when none does; its columns scale constrained-MCMC moves.
+    pub fn axes(&self, unit: &[f64]) -> DMatrix<f64> {
+        let point = DVector::from_column_slice(unit);
+        let by_volume = |a: &&Ellipsoid, b: &&Ellipsoid| a.log_volume.total_cmp(&b.log_volume);
+        self.ellipsoids
+            .iter()
+            .filter(|ellipsoid| ellipsoid.contains(&point))
+            .min_by(by_volume)
+            .or_else(|| self.ellipsoids.iter().max_by(by_volume))
+            .map_or_else(
+                || DMatrix::identity(unit.len(), unit.len()),
+                |ellipsoid| ellipsoid.factor.clone(),
+            )
+    }
+
+    pub fn write_checkpoint(&self, writer: &mut CheckpointWriter) {
+        writer.count("bound.count", self.ellipsoids.len());
+        for (idx, ellipsoid) in self.ellipsoids.iter().enumerate() {
+            writer.vector(&format!("bound.centre.{}", idx), &ellipsoid.centre);
+            writer.matrix(&format!("bound.factor.{}", idx), &ellipsoid.factor);
+            writer.matrix(&format!("bound.precision.{}", idx), &ellipsoid.precision);
+            writer.scalar(&format!("bound.log_volume.{}", idx), ellipsoid.log_volume);
+        }
+    }
+
+    /// Restore a bound written by [`EllipsoidBound::write_checkpoint`], if there is one.
+    pub fn from_checkpoint(
+        checkpoint: &Checkpoint,
+        dimension: usize,
+    ) -> Result<Option<Self>, String> {
+        if !checkpoint.contains("bound.count") {
+            return Ok(None);
+        }
+        let ellipsoids = (0..checkpoint.count("bound.count")?)
+            .map(|idx| {
+                let factor = checkpoint.matrix(&format!("bound.factor.{}", idx))?;
+                let precision = checkpoint.matrix(&format!("bound.precision.{}", idx))?;
+                if factor.shape() != (dimension, dimension) || precision.shape() != factor.shape() {
+                    return Err("Checkpoint bound does not match the problem dimension".to_string());
+                }
+                Ok(Ellipsoid {
+                    centre: checkpoint.vector(&format!("bound.centre.{}", idx), dimension)?,
+                    factor,
+                    precision,
+                    log_volume: checkpoint.scalar(&format!("bound.log_volume.{}", idx))?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok((!ellipsoids.is_empty()).then_some(Self { ellipsoids }))
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.ellipsoids.len()
+    }
+}
+
+/// Recursively split `points` with 2-means while the child ellipsoids bound a
+/// substantially smaller volume than `parent`.
+fn split(
+    points: Vec<DVector<f64>>,
+    parent: Ellipsoid,
+    enlargement: f64,
+    ellipsoids: &mut Vec<Ellipsoid>,
+) {
+    let min_points = 2 * (parent.centre.len() + 1);
+    if points.len() < 2 * min_points {
+        ellipsoids.push(parent);
+        return;
+    }
+
+    let (first, second) = two_means(points);
+    if first.len() < min_points || second.len() < min_points {
+        ellipsoids.push(parent);
+        return;
+    }
+
+    match (
+        Ellipsoid::enclosing(&first, enlargement),
+        Ellipsoid::enclosing(&second, enlargement),
+    ) {
+        (Some(a), Some(b)) => {
+            let (high, low) = if a.log_volume > b.log_volume {
+                (a.log_volume, b.log_volume)
+            } else {
+                (b.log_volume, a.log_volume)
+            };
+            let children = high + (low - high).exp().ln_1p();
+            if children < parent.log_volume + SPLIT_VOLUME_FRACTION.ln() {
+                split(first, a, enlargement, ellipsoids);
+                split(second, b, enlargement, ellipsoids);
+            } else {
+                ellipsoids.push(parent);
+            }
+        }
+        _ => ellipsoids.push(parent),
+    }
+}
+
+/// Partition points into two clusters with Lloyd's algorithm, seeded by the point
+/// farthest from the centroid and the point farthest from that one.
+fn two_means(points: Vec<DVector<f64>>) -> (Vec<DVector<f64>>, Vec<DVector<f64>>) {
+    let centroid = points.iter().sum::<DVector<f64>>() / points.len() as f64;
+    let farthest_from = |origin: &DVector<f64>| {
+        points
+            .iter()
+            .max_by(|a, b| {
+                (*a - origin)
+                    .norm_squared()
+                    .total_cmp(&(*b - origin).norm_squared())
+            })
+            .cloned()
+            .unwrap_or_else(|| origin.clone())
+    };
+    let mut first_centre = farthest_from(&centroid);
+    let mut second_centre = farthest_from(&first_centre);
+
+    let mut labels = vec![false; points.len()];
+    for _ in 0..TWO_MEANS_ITERATIONS {
+        let assigned: Vec<bool> = points
+            .iter()
+            .map(|p| (p - &second_centre).norm_squared() < (p - &first_centre).norm_squared())
+            .collect();
+        let converged = assigned == labels;
+        labels = assigned;
+
+        let centre_of = |second: bool| {
+            let members: Vec<&DVector<f64>> = points
+                .iter()
+                .zip(&labels)
+                .filter(|(_, &label)| label == second)
+                .map(|(point, _)| point)
+                .collect();
+            (!members.is_empty())
+                .then(|| members.iter().copied().sum::<DVector<f64>>() / members.len() as f64)
+        };
+        match (centre_of(false), centre_of(true)) {
+            (Some(a), Some(b)) => {
+                first_centre = a;
+                second_centre = b;
+            }
+            _ => break,
+        }
+        if converged {
+            break;
+        }
+    }
+
+    let (second, first): (Vec<_>, Vec<_>) = points
+        .into_iter()
+        .zip(labels)
+        .partition(|(_, label)| *label);
+    (
+        first.into_iter().map(|(point, _)| point).collect(),
+        second.into_iter().map(|(point, _)| point).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn blob(rng: &mut CheckpointRng, centre: [f64; 2], spread: f64, count: usize) -> Vec<Vec<f64>> {
+        (0..count)
+            .map(|_| {
+                centre
+                    .iter()
+                    .map(|c| c + spread * rng.sample::<f64, _>(StandardNormal))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_ellipsoid_encloses_live_points() {
+        let mut rng = CheckpointRng::seed_from_u64(1);
+        let units = blob(&mut rng, [0.5, 0.5], 0.05, 64);
+        let bound = EllipsoidBound::fit(&units, false, 1.0).expect("fit");
+        assert_eq!(bound.len(), 1);
+        for unit in &units {
+            assert!(bound.ellipsoids[0].contains(&DVector::from_column_slice(unit)));
+        }
+
+        let draws: Vec<Vec<f64>> = (0..2_000).filter_map(|_| bound.sample(&mut rng)).collect();
+        assert!(draws.len() > 1_900);
+        assert!(draws
+            .iter()
+            .all(|draw| bound.ellipsoids[0].contains(&DVector::from_column_slice(draw))));
+    }
+
+    #[test]
+    fn separated_clusters_get_their_own_ellipsoids() {
+        let mut rng = CheckpointRng::seed_from_u64(2);
+        let mut units = blob(&mut rng, [0.2, 0.2], 0.02, 64);
+        units.extend(blob(&mut rng, [0.8, 0.7], 0.02, 64));
+
+        let single = EllipsoidBound::fit(&units, false, 1.0).expect("fit");
+        let multiple = EllipsoidBound::fit(&units, true, 1.0).expect("fit");
+        assert_eq!(multiple.len(), 2);
+        let volume = |bound: &EllipsoidBound| {
+            bound
+                .ellipsoids
+                .iter()
+                .map(|e| e.log_volume.exp())
+                .sum::<f64>()
+        };
+        assert!(volume(&multiple) < 0.1 * volume(&single));
+
+        // Draws stay near the clusters, which both receive a share.
+        let draws: Vec<Vec<f64>> = (0..4_000)
+            .filter_map(|_| multiple.sample(&mut rng))
+            .collect();
+        let near = |draw: &Vec<f64>, centre: [f64; 2]| {
+            (draw[0] - centre[0]).hypot(draw[1] - centre[1]) < 0.15
+        };
+        assert!(draws
+            .iter()
+            .all(|draw| near(draw, [0.2, 0.2]) || near(draw, [0.8, 0.7])));
+        let lower = draws.iter().filter(|draw| draw[0] < 0.5).count() as f64;
+        assert!((0.2..0.8).contains(&(lower / draws.len() as f64)));
+    }
+}
@@ -1,7 +1,10 @@
-use super::state::{Bounds, LivePoint};
+use super::bounds::{Bounding, EllipsoidBound};
+use super::state::{LivePoint, PriorTransform};
 use super::{evaluate, MIN_LIVE_POINTS};
+use crate::checkpoint::{Checkpoint, CheckpointRng, CheckpointWriter};
 use crate::problem::Problem;
-use rand::rngs::StdRng;
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+use rand::seq::SliceRandom;
 use rand::Rng;
 use rand_distr::StandardNormal;
 
@@ -9,30 +12,115 @@ const MAX_ATTEMPTS_FACTOR: usize = 64;
 const MAX_UNIFORM_ATTEMPTS: usize = 32;
 const MIN_BATCH_SIZE: usize = 4;
 const MAX_BATCH_SIZE: usize = 64;
+/// Ellipsoidal bounds are refitted once this fraction of the live set has been replaced.
+const REFIT_FRACTION: f64 = 0.1;
+/// Live points a constrained-MCMC proposal may start from before giving up.
+const MAX_CHAIN_STARTS: usize = 8;
+/// Acceptance rate the random-walk step scale is tuned towards.
+const TARGET_WALK_ACCEPTANCE: f64 = 0.5;
+/// Cap on stepping-out expansions and shrinkage contractions per slice.
+const MAX_SLICE_STEPS: usize = 64;
 
-/// Generates new live-point proposals using local perturbations and periodic uniform draws.
+/// How a new live point is generated from the region bounding the live set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NestedProposal {
+    /// Independent draws from the bounding region, kept when they beat the threshold.
+    Uniform,
+    /// Metropolis random walk of `steps` moves from a random live point, restricted to
+    /// the likelihood constraint. The step scale adapts towards 50% acceptance.
+    RandomWalk { steps: usize },
+    /// Slice sampling from a random live point, cycling `slices` times through the
+    /// principal directions of the bound (PolyChord-style). The initial bracket width
+    /// adapts so stepping out and shrinking balance.
+    Slice { slices: usize },
+}
+
+/// Generates new live-point proposals in the prior's unit hypercube. The bounding
+/// region (local perturbations with periodic prior draws, or ellipsoids fitted to the
+/// live points) is either sampled directly or shapes a constrained-MCMC move.
 #[derive(Clone, Debug)]
 pub(super) struct ProposalEngine {
     dimension: usize,
     expansion_factor: f64,
+    bounding: Bounding,
+    proposal: NestedProposal,
+    bound: Option<EllipsoidBound>,
+    accepted_since_fit: usize,
+    /// Step scale of the constrained-MCMC proposals, relative to the bound's axes.
+    scale: f64,
+    proposed: usize,
+    accepted: usize,
+}
+
+/// Unit-cube proposal generator fitted to the current live points.
+enum Region {
+    Perturbation {
+        units: Vec<Vec<f64>>,
+        scales: Vec<f64>,
+    },
+    Ellipsoids(EllipsoidBound),
 }
 
 impl ProposalEngine {
     /// Instantiate a proposal engine with dimensionality-aware scales.
-    pub fn new(dimension: usize, expansion_factor: f64) -> Self {
+    pub fn new(
+        dimension: usize,
+        expansion_factor: f64,
+        bounding: Bounding,
+        proposal: NestedProposal,
+    ) -> Self {
         Self {
             dimension: dimension.max(1),
             expansion_factor: expansion_factor.max(0.05),
+            bounding,
+            proposal,
+            bound: None,
+            accepted_since_fit: 0,
+            scale: 1.0,
+            proposed: 0,
+            accepted: 0,
         }
     }
 
+    /// Number of candidates whose likelihood has been evaluated.
+    pub fn proposed(&self) -> usize {
+        self.proposed
+    }
+
+    /// Number of candidates accepted as live points.
+    pub fn accepted(&self) -> usize {
+        self.accepted
+    }
+
+    pub fn write_checkpoint(&self, writer: &mut CheckpointWriter) {
+        writer.count("proposed", self.proposed);
+        writer.count("accepted", self.accepted);
+        writer.count("accepted_since_fit", self.accepted_since_fit);
+        writer.scalar("proposal_scale", self.scale);
+        if let Some(bound) = &self.bound {
+            bound.write_checkpoint(writer);
+        }
+    }
+
+    pub fn restore_checkpoint(&mut self, checkpoint: &Checkpoint) -> Result<(), String> {
+        self.proposed = checkpoint.count("proposed")?;
+        self.accepted = checkpoint.count("accepted")?;
+        self.accepted_since_fit = checkpoint.count("accepted_since_fit")?;
+        self.scale = checkpoint.scalar("proposal_scale")?;
+        self.bound = EllipsoidBound::from_checkpoint(checkpoint, self.dimension)?;
+        Ok(())
+    }
+
     /// Sample a new live point above the given likelihood threshold, if possible.
+    ///
+    /// Constrained-MCMC proposals evaluate one candidate at a time; `parallel` batches
+    /// only uniform draws, which are also used while the live set is being seeded.
     pub fn draw(
         &mut self,
-        rng: &mut StdRng,
+        rng: &mut CheckpointRng,
         problem: &Problem,
         live_points: &[LivePoint],
-        bounds: &Bounds,
+        prior: &PriorTransform,
         threshold: f64,
         parallel: bool,
     ) -> Option<LivePoint> {
@@ -40,143 +128,344 @@ impl ProposalEngine {
             return None;
         }
 
-        let step_sizes = compute_scales(live_points, bounds, self.expansion_factor);
-        let max_attempts = MAX_ATTEMPTS_FACTOR.saturating_mul(self.dimension).max(64);
+        let region = self.fit(live_points, prior);
+        let seeding = live_points.len() < MIN_LIVE_POINTS;
+        let point = match self.proposal {
+            NestedProposal::RandomWalk { steps } if !seeding => {
+                self.random_walk(rng, problem, live_points, prior, &region, threshold, steps)
+            }
+            NestedProposal::Slice { slices } if !seeding => {
+                self.slice(rng, problem, live_points, prior, &region, threshold, slices)
+            }
+            _ => self.uniform(
+                rng,
+                problem,
+                live_points,
+                prior,
+                &region,
+                threshold,
+                parallel,
+            ),
+        };
 
-        if parallel {
-            // Adaptive batch sizing based on problem dimension and available live points
-            let optimal_batch_size = (self.dimension * 2)
+        match point {
+            Some(point) => {
+                self.accepted += 1;
+                self.accepted_since_fit += 1;
+                Some(point)
+            }
+            None => {
+                // A stale bound that yields nothing is refitted on the next draw.
+                self.bound = None;
+                None
+            }
+        }
+    }
+
+    /// Draw candidates independently from the region until one beats the threshold.
+    #[allow(clippy::too_many_arguments)]
+    fn uniform(
+        &mut self,
+        rng: &mut CheckpointRng,
+        problem: &Problem,
+        live_points: &[LivePoint],
+        prior: &PriorTransform,
+        region: &Region,
+        threshold: f64,
+        parallel: bool,
+    ) -> Option<LivePoint> {
+        let max_attempts = MAX_ATTEMPTS_FACTOR.saturating_mul(self.dimension).max(64);
+        // Adaptive batch sizing based on problem dimension and available live points
+        let batch_size = if parallel {
+            (self.dimension * 2)
                 .clamp(MIN_BATCH_SIZE, MAX_BATCH_SIZE)
-                .min(live_points.len().max(MIN_BATCH_SIZE));
+                .min(live_points.len().max(MIN_BATCH_SIZE))
+        } else {
+            1
+        };
 
-            let mut attempts = 0usize;
-            let mut candidates = Vec::with_capacity(optimal_batch_size);
+        let mut attempts = 0usize;
+        let mut candidates = Vec::with_capacity(batch_size);
+        while attempts < max_attempts {
+            candidates.clear();
+            while candidates.len() < batch_size && attempts < max_attempts {
+                attempts += 1;
+                if let Some(candidate) = region.propose(rng, prior, attempts) {
+                    candidates.push(candidate);
+                }
+            }
+            if candidates.is_empty() {
+                continue;
+            }
 
-            while attempts < max_attempts {
-                // Generate a full batch of candidates
-                candidates.clear();
-                for _ in 0..optimal_batch_size {
-                    attempts += 1;
-                    if attempts > max_attempts {
-                        break;
-                    }
+            self.proposed += candidates.len();
+            let log_likelihoods: Vec<f64> = if parallel {
+                problem
+                    .evaluate_population_strict(&candidates)
+                    .into_iter()
+                    .map(|result| result.map_or(f64::NEG_INFINITY, |value| -value))
+                    .collect()
+            } else {
+                candidates
+                    .iter()
+                    .map(|candidate| -evaluate(problem, candidate))
+                    .collect()
+            };
 
-                    let candidate = if attempts.is_multiple_of(MAX_UNIFORM_ATTEMPTS) {
-                        let mut position = bounds.sample(rng, self.expansion_factor);
-                        bounds.clamp(&mut position);
-                        position
-                    } else {
-                        let anchor_idx = rng.random_range(0..live_points.len());
-                        let anchor = &live_points[anchor_idx];
-                        let mut proposal = anchor.position.clone();
-                        for (value, scale) in proposal.iter_mut().zip(step_sizes.iter()) {
-                            let perturb = rng.sample::<f64, _>(StandardNormal) * scale;
-                            *value += perturb;
-                        }
-                        bounds.clamp(&mut proposal);
-                        proposal
-                    };
-                    candidates.push(candidate);
+            for (position, log_likelihood) in candidates.drain(..).zip(log_likelihoods) {
+                if !log_likelihood.is_finite() {
+                    continue;
                 }
+                if log_likelihood > threshold || live_points.len() < MIN_LIVE_POINTS {
+                    return Some(LivePoint::new(position, log_likelihood));
+                }
+            }
+        }
 
-                if candidates.is_empty() {
-                    break;
+        None
+    }
+
+    /// Random-walk Metropolis within the likelihood constraint, started from a random
+    /// live point with Gaussian steps shaped by the bound's axes.
+    #[allow(clippy::too_many_arguments)]
+    fn random_walk(
+        &mut self,
+        rng: &mut CheckpointRng,
+        problem: &Problem,
+        live_points: &[LivePoint],
+        prior: &PriorTransform,
+        region: &Region,
+        threshold: f64,
+        steps: usize,
+    ) -> Option<LivePoint> {
+        let steps = steps.max(1);
+        for _ in 0..MAX_CHAIN_STARTS {
+            let start = &live_points[rng.random_range(0..live_points.len())];
+            let mut unit = DVector::from_vec(prior.to_unit(&start.position));
+            let axes = region.axes(unit.as_slice());
+            let mut current = None;
+            let mut moves = 0usize;
+            for _ in 0..steps {
+                let step =
+                    DVector::from_fn(unit.len(), |_, _| rng.sample::<f64, _>(StandardNormal));
+                let candidate = &unit + &axes * step * self.scale;
+                if let Some(point) = self.constrained(problem, prior, &candidate, threshold) {
+                    unit = candidate;
+                    current = Some(point);
+                    moves += 1;
                 }
+            }
 
-                // Evaluate entire batch in parallel
-                let results = problem.evaluate_population(&candidates);
-
-                // Process results and look for acceptable points
-                for (position, result) in candidates.drain(..).zip(results.into_iter()) {
-                    let log_likelihood = match result {
-                        Ok(value) => {
-                            let ll = -value;
-                            if ll.is_finite() {
-                                ll
-                            } else {
-                                continue;
-                            }
-                        }
-                        Err(_) => continue,
-                    };
-
-                    if log_likelihood > threshold || live_points.len() < MIN_LIVE_POINTS {
-                        return Some(LivePoint::new(position, log_likelihood));
+            let rate = moves as f64 / steps as f64;
+            self.scale *= ((rate - TARGET_WALK_ACCEPTANCE)
+                / (self.dimension as f64 * TARGET_WALK_ACCEPTANCE))
+                .exp();
+            if current.is_some() {
+                return current;
+            }
+        }
+        None
+    }
+
+    /// Slice sampling within the likelihood constraint along the principal directions
+    /// of the bound, with stepping out and shrinkage (Neal, 2003).
+    #[allow(clippy::too_many_arguments)]
+    fn slice(
+        &mut self,
+        rng: &mut CheckpointRng,
+        problem: &Problem,
+        live_points: &[LivePoint],
+        prior: &PriorTransform,
+        region: &Region,
+        threshold: f64,
+        slices: usize,
+    ) -> Option<LivePoint> {
+        let start = &live_points[rng.random_range(0..live_points.len())];
+        let mut unit = DVector::from_vec(prior.to_unit(&start.position));
+        let mut directions = principal_directions(&region.axes(unit.as_slice()));
+        let mut current = None;
+        let (mut expansions, mut contractions) = (0usize, 0usize);
+
+        for _ in 0..slices.max(1) {
+            directions.shuffle(rng);
+            for direction in &directions {
+                let width = direction * self.scale;
+                let offset = rng.random::<f64>();
+                let (mut left, mut right) = (-offset, 1.0 - offset);
+                let mut steps = 0usize;
+                while steps < MAX_SLICE_STEPS
+                    && self
+                        .constrained(problem, prior, &(&unit + &width * left), threshold)
+                        .is_some()
+                {
+                    left -= 1.0;
+                    steps += 1;
+                }
+                while steps < MAX_SLICE_STEPS
+                    && self
+                        .constrained(problem, prior, &(&unit + &width * right), threshold)
+                        .is_some()
+                {
+                    right += 1.0;
+                    steps += 1;
+                }
+                expansions += steps;
+
+                let mut shrinks = 0usize;
+                loop {
+                    if shrinks == MAX_SLICE_STEPS {
+                        return None;
+                    }
+                    let t = left + rng.random::<f64>() * (right - left);
+                    let candidate = &unit + &width * t;
+                    if let Some(point) = self.constrained(problem, prior, &candidate, threshold) {
+                        unit = candidate;
+                        current = Some(point);
+                        break;
+                    }
+                    if t < 0.0 {
+                        left = t;
+                    } else {
+                        right = t;
                     }
+                    shrinks += 1;
                 }
+                contractions += shrinks;
             }
+        }
 
-            None
-        } else {
-            for attempt in 0..max_attempts {
-                // Periodically try a fresh uniform draw in the bounding box to avoid stagnation.
-                let candidate = if attempt.is_multiple_of(MAX_UNIFORM_ATTEMPTS) {
-                    let mut position = bounds.sample(rng, self.expansion_factor);
-                    bounds.clamp(&mut position);
-                    position
-                } else {
-                    let anchor_idx = rng.random_range(0..live_points.len());
-                    let anchor = &live_points[anchor_idx];
-                    let mut proposal = anchor.position.clone();
-                    for (value, scale) in proposal.iter_mut().zip(step_sizes.iter()) {
-                        let perturb = rng.sample::<f64, _>(StandardNormal) * scale;
-                        *value += perturb;
-                    }
-                    bounds.clamp(&mut proposal);
-                    proposal
-                };
+        if expansions + contractions > 0 {
+            let balance = 2.0 * expansions as f64 / (expansions + contractions) as f64;
+            self.scale *= balance.clamp(0.5, 2.0);
+        }
+        current
+    }
 
-                let log_likelihood = -evaluate(problem, &candidate);
-                if !log_likelihood.is_finite() {
-                    continue;
-                }
+    /// Evaluate a unit-cube candidate, returning it as a live point when it lies inside
+    /// the cube and beats the likelihood threshold.
+    fn constrained(
+        &mut self,
+        problem: &Problem,
+        prior: &PriorTransform,
+        unit: &DVector<f64>,
+        threshold: f64,
+    ) -> Option<LivePoint> {
+        if unit.iter().any(|value| !(0.0..=1.0).contains(value)) {
+            return None;
+        }
+        let position = prior.transform(unit.as_slice());
+        self.proposed += 1;
+        let log_likelihood = -evaluate(problem, &position);
+        (log_likelihood.is_finite() && log_likelihood > threshold)
+            .then(|| LivePoint::new(position, log_likelihood))
+    }
 
-                if log_likelihood > threshold || live_points.len() < MIN_LIVE_POINTS {
-                    return Some(LivePoint::new(candidate, log_likelihood));
+    /// Fit the configured proposal region to the live points, falling back to local
+    /// perturbations when no ellipsoid can be fitted.
+    ///
+    /// Ellipsoidal bounds are kept between refits: the live points only move inwards,
+    /// so a stale bound still encloses them at the cost of some efficiency.
+    fn fit(&mut self, live_points: &[LivePoint], prior: &PriorTransform) -> Region {
+        let multiple = match self.bounding {
+            Bounding::Perturbation => None,
+            Bounding::Ellipsoid => Some(false),
+            Bounding::MultiEllipsoid => Some(true),
+        };
+        let refit_after = (REFIT_FRACTION * live_points.len() as f64).ceil() as usize;
+        if let Some(bound) = &self.bound {
+            if self.accepted_since_fit < refit_after {
+                return Region::Ellipsoids(bound.clone());
+            }
+        }
+
+        let units: Vec<Vec<f64>> = live_points
+            .iter()
+            .map(|point| prior.to_unit(&point.position))
+            .collect();
+        if let Some(multiple) = multiple {
+            self.bound = EllipsoidBound::fit(&units, multiple, 1.0 + self.expansion_factor);
+            self.accepted_since_fit = 0;
+            if let Some(bound) = &self.bound {
+                return Region::Ellipsoids(bound.clone());
+            }
+        }
+        let scales = compute_scales(&units, self.expansion_factor);
+        Region::Perturbation { units, scales }
+    }
+}
+
+impl Region {
+    /// Propose a position for the `attempt`-th try, or `None` when the draw was rejected
+    /// before evaluating the likelihood.
+    fn propose(
+        &self,
+        rng: &mut CheckpointRng,
+        prior: &PriorTransform,
+        attempt: usize,
+    ) -> Option<Vec<f64>> {
+        match self {
+            Region::Perturbation { units, scales } => {
+                // Periodically try a fresh draw from the prior to avoid stagnation.
+                if attempt.is_multiple_of(MAX_UNIFORM_ATTEMPTS) {
+                    return Some(prior.sample(rng));
+                }
+                let anchor_idx = rng.random_range(0..units.len());
+                let mut proposal = units[anchor_idx].clone();
+                for (value, scale) in proposal.iter_mut().zip(scales.iter()) {
+                    let perturb = rng.sample::<f64, _>(StandardNormal) * scale;
+                    *value += perturb;
                 }
+                Some(prior.transform(&proposal))
             }
+            Region::Ellipsoids(bound) => bound.sample(rng).map(|unit| prior.transform(&unit)),
+        }
+    }
 
-            None
+    /// Axes scaling constrained-MCMC moves from `unit`.
+    fn axes(&self, unit: &[f64]) -> DMatrix<f64> {
+        match self {
+            Region::Perturbation { scales, .. } => {
+                DMatrix::from_diagonal(&DVector::from_column_slice(scales))
+            }
+            Region::Ellipsoids(bound) => bound.axes(unit),
         }
     }
 }
 
-/// Compute Gaussian perturbation scales per dimension using the spread of the
-/// current live points, widened by the configured expansion factor. Falls back
-/// to a uniform, expansion-factor-based scale when live points are unavailable
-/// or bounds are degenerate.
-fn compute_scales(live_points: &[LivePoint], bounds: &Bounds, expansion_factor: f64) -> Vec<f64> {
-    let dimension = bounds.dimension();
-    if live_points.is_empty() {
+/// Principal directions of the ellipsoid `axes * axes^T`, scaled by their semi-axes.
+fn principal_directions(axes: &DMatrix<f64>) -> Vec<DVector<f64>> {
+    let eigen = SymmetricEigen::new(axes * axes.transpose());
+    eigen
+        .eigenvectors
+        .column_iter()
+        .zip(eigen.eigenvalues.iter())
+        .map(|(vector, value)| vector * value.max(0.0).sqrt())
+        .collect()
+}
+
+/// Compute Gaussian perturbation scales per unit-cube dimension using the spread of
+/// the current live points, widened by the configured expansion factor. Falls back
+/// to a uniform, expansion-factor-based scale when live points are unavailable.
+fn compute_scales(units: &[Vec<f64>], expansion_factor: f64) -> Vec<f64> {
+    let dimension = units.first().map_or(0, Vec::len);
+    if units.len() < 2 {
         return vec![expansion_factor.max(0.1); dimension];
     }
 
     let mut mins = vec![f64::INFINITY; dimension];
     let mut maxs = vec![f64::NEG_INFINITY; dimension];
 
-    for point in live_points {
-        for (i, value) in point.position.iter().enumerate() {
+    for unit in units {
+        for (i, value) in unit.iter().enumerate() {
             mins[i] = mins[i].min(*value);
             maxs[i] = maxs[i].max(*value);
         }
     }
 
-    mins.iter_mut().zip(maxs.iter_mut()).for_each(|(min, max)| {
-        if !min.is_finite() {
-            *min = -1.0;
-        }
-        if !max.is_finite() {
-            *max = 1.0;
-        }
-        if *max <= *min {
-            *max = *min + 1e-3;
-        }
-    });
-
     mins.iter()
         .zip(maxs.iter())
         .map(|(&min, &max)| {
-            let width = (max - min).abs().max(1e-3);
+            let width = (max - min).max(1e-9);
             width * expansion_factor.max(0.05)
         })
         .collect()
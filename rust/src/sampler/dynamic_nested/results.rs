@@ -1,5 +1,8 @@
+use super::logspace_sub;
 use super::state::PosteriorSample;
-use crate::sampler::Samples;
+use crate::sampler::{systematic_indices, Samples};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::time::Duration;
 
 /// Posterior sample with its likelihood and log-weight contribution.
@@ -24,17 +27,23 @@ impl NestedSample {
 #[derive(Clone, Debug)]
 pub struct NestedSamples {
     posterior: Vec<NestedSample>,
+    /// Live-set size each posterior entry was removed from; 0 for the final live set.
+    live_points: Vec<usize>,
     mean: Vec<f64>,
     draws: usize,
     log_z: f64,
+    log_z_error: f64,
     information: f64,
     time: Duration,
+    proposals: usize,
+    accepted_proposals: usize,
 }
 
 impl NestedSamples {
     /// Build a [`NestedSamples`] view from raw posterior entries and problem dimension.
     pub(super) fn build(posterior: &[PosteriorSample], dimension: usize) -> Self {
         let mut samples = Vec::with_capacity(posterior.len());
+        let mut live_points = Vec::with_capacity(posterior.len());
         let mut log_z = f64::NEG_INFINITY;
 
         for sample in posterior {
@@ -50,6 +59,7 @@ impl NestedSamples {
                 log_likelihood: sample.log_likelihood,
                 log_weight: weight,
             });
+            live_points.push(sample.live_points);
         }
 
         if samples.is_empty() {
@@ -87,13 +97,19 @@ impl NestedSamples {
             _ => 0.0,
         };
 
+        let log_z_error = skilling_error(&samples, &live_points);
+
         Self {
             posterior: samples,
+            live_points,
             mean,
             draws: posterior.len(),
             log_z,
+            log_z_error,
             information,
             time: Duration::default(),
+            proposals: 0,
+            accepted_proposals: 0,
         }
     }
 
@@ -101,11 +117,15 @@ impl NestedSamples {
     pub fn degenerate(mean: Vec<f64>) -> Self {
         Self {
             posterior: Vec::new(),
+            live_points: Vec::new(),
             mean,
             draws: 0,
             log_z: f64::NEG_INFINITY,
+            log_z_error: f64::NAN,
             information: 0.0,
             time: Duration::default(),
+            proposals: 0,
+            accepted_proposals: 0,
         }
     }
 
@@ -121,6 +141,12 @@ impl NestedSamples {
         self.time = time;
     }
 
+    /// Record how many proposals were evaluated and how many became live points.
+    pub(super) fn set_proposal_counts(&mut self, proposals: usize, accepted: usize) {
+        self.proposals = proposals;
+        self.accepted_proposals = accepted;
+    }
+
     /// Elapsed execution time for the run.
     pub fn time(&self) -> Duration {
         self.time
@@ -146,11 +172,121 @@ impl NestedSamples {
         self.log_z
     }
 
+    /// Standard error of [`NestedSamples::log_evidence`] from the uncertainty in the
+    /// prior-volume shrinkage, `sqrt(H / n)` for a constant live set of `n` points
+    /// (Skilling, 2006). NaN when the run produced no posterior.
+    pub fn log_evidence_error(&self) -> f64 {
+        self.log_z_error
+    }
+
+    /// Standard error of [`NestedSamples::log_evidence`] from `simulations` replays of
+    /// the run with the shrinkage of each removal drawn from its `Beta(n, 1)`
+    /// distribution instead of fixed at its mean. NaN with fewer than two simulations.
+    pub fn simulated_log_evidence_error(&self, simulations: usize, seed: u64) -> f64 {
+        if simulations < 2 || self.posterior.is_empty() {
+            return f64::NAN;
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let estimates: Vec<f64> = (0..simulations)
+            .map(|_| self.simulated_log_evidence(&mut rng))
+            .collect();
+        let mean = estimates.iter().sum::<f64>() / simulations as f64;
+        let variance = estimates
+            .iter()
+            .map(|estimate| (estimate - mean).powi(2))
+            .sum::<f64>()
+            / (simulations - 1) as f64;
+        variance.sqrt()
+    }
+
+    /// Log-evidence of the run with random shrinkage factors; the final live set shares
+    /// the remaining prior volume equally, as in the main estimate.
+    fn simulated_log_evidence(&self, rng: &mut StdRng) -> f64 {
+        let remaining = self.live_points.iter().filter(|&&n| n == 0).count();
+        let mut log_volume = 0.0;
+        let mut log_z = f64::NEG_INFINITY;
+        for (sample, &live_points) in self.posterior.iter().zip(&self.live_points) {
+            if live_points > 0 {
+                let shrunk = log_volume + rng.random::<f64>().ln() / live_points as f64;
+                if let Some(log_weight) = logspace_sub(log_volume, shrunk) {
+                    log_z = logsumexp(log_z, sample.log_likelihood + log_weight);
+                }
+                log_volume = shrunk;
+            }
+        }
+        if remaining > 0 {
+            let log_weight = log_volume - (remaining as f64).ln();
+            for (sample, _) in self
+                .posterior
+                .iter()
+                .zip(&self.live_points)
+                .filter(|(_, &n)| n == 0)
+            {
+                log_z = logsumexp(log_z, sample.log_likelihood + log_weight);
+            }
+        }
+        log_z
+    }
+
     /// Estimated information (Kullback–Leibler divergence) in nats.
     pub fn information(&self) -> f64 {
         self.information
     }
 
+    /// Kish effective sample size of the posterior importance weights.
+    pub fn effective_sample_size(&self) -> f64 {
+        let log_weights = self.normalised_log_weights();
+        if log_weights.is_empty() {
+            return 0.0;
+        }
+        let sum_squares: f64 = log_weights.iter().map(|w| (2.0 * w).exp()).sum();
+        1.0 / sum_squares
+    }
+
+    /// Equal-weight posterior draws, one per posterior entry, picked by systematic
+    /// resampling of the importance weights with a seeded comb offset.
+    pub fn resample(&self, seed: u64) -> Samples {
+        let offset = StdRng::seed_from_u64(seed).random::<f64>();
+        let chain: Vec<Vec<f64>> = systematic_indices(&self.normalised_log_weights(), offset)
+            .into_iter()
+            .map(|index| self.posterior[index].position.clone())
+            .collect();
+        let draws = chain.len();
+        Samples::new(vec![chain], self.mean.clone(), draws, self.time)
+    }
+
+    /// Posterior log-weights normalised to sum to one.
+    fn normalised_log_weights(&self) -> Vec<f64> {
+        let log_weights: Vec<f64> = self
+            .posterior
+            .iter()
+            .map(|sample| sample.log_weight + sample.log_likelihood)
+            .collect();
+        let total = log_weights
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, logsumexp);
+        log_weights.iter().map(|w| w - total).collect()
+    }
+
+    /// Number of proposed live points whose likelihood was evaluated.
+    pub fn proposals(&self) -> usize {
+        self.proposals
+    }
+
+    /// Number of proposals accepted as live points.
+    pub fn accepted_proposals(&self) -> usize {
+        self.accepted_proposals
+    }
+
+    /// Fraction of evaluated proposals accepted as live points; NaN before any proposal.
+    pub fn efficiency(&self) -> f64 {
+        if self.proposals == 0 {
+            return f64::NAN;
+        }
+        self.accepted_proposals as f64 / self.proposals as f64
+    }
+
     /// Convert nested-sampling posterior into the generic [`Samples`] view.
     pub fn to_samples(&self) -> Samples {
         let chains = vec![self
@@ -206,6 +342,31 @@ pub(super) fn log_evidence_estimate(posterior: &[PosteriorSample]) -> f64 {
     log_z
 }
 
+/// Skilling's shrinkage error on the log-evidence: each removal from `n` live points
+/// contributes its increment of the running information divided by `n`. Points from the
+/// final live set count against the size of that set.
+fn skilling_error(samples: &[NestedSample], live_points: &[usize]) -> f64 {
+    let remaining = live_points.iter().filter(|&&n| n == 0).count().max(1);
+    let mut log_z = f64::NEG_INFINITY;
+    let mut information = 0.0;
+    let mut variance = 0.0;
+    for (sample, &n) in samples.iter().zip(live_points) {
+        let evidence = sample.log_likelihood + sample.log_weight;
+        let updated = logsumexp(log_z, evidence);
+        let carried = if log_z.is_finite() {
+            (log_z - updated).exp() * (information + log_z)
+        } else {
+            0.0
+        };
+        let next = (evidence - updated).exp() * sample.log_likelihood + carried - updated;
+        let n = if n == 0 { remaining } else { n };
+        variance += (next - information) / n as f64;
+        information = next;
+        log_z = updated;
+    }
+    variance.max(0.0).sqrt()
+}
+
 /// Helper computing the nested-sampling information (estimated KL divergence)
 /// given log-evidence and pre-normalized sample entries.
 fn information_from_samples(log_z: f64, samples: &[NestedSample]) -> f64 {
@@ -322,6 +483,79 @@ mod tests {
         assert_eq!(samples.mean_x(), nested.mean());
     }
 
+    #[test]
+    fn skilling_error_is_root_information_over_live_points() {
+        // Deterministic shrinkage with a constant live set of 50 points, then the final
+        // 50 live points sharing the remaining volume.
+        let live = 50usize;
+        let mut posterior = Vec::new();
+        let mut log_volume = 0.0f64;
+        for i in 0..400 {
+            let shrunk = log_volume - 1.0 / live as f64;
+            let log_weight = log_volume + (1.0 - (shrunk - log_volume).exp()).ln();
+            posterior.push(
+                PosteriorSample::new(
+                    vec![i as f64],
+                    -0.5 * (40.0 * shrunk.exp()).powi(2),
+                    log_weight,
+                )
+                .with_live_points(live),
+            );
+            log_volume = shrunk;
+        }
+        for _ in 0..live {
+            posterior.push(PosteriorSample::new(
+                vec![0.0],
+                0.0,
+                log_volume - (live as f64).ln(),
+            ));
+        }
+
+        let nested = NestedSamples::build(&posterior, 1);
+        let expected = (nested.information() / live as f64).sqrt();
+        assert!(
+            (nested.log_evidence_error() - expected).abs() < 1e-9,
+            "{} vs {}",
+            nested.log_evidence_error(),
+            expected
+        );
+        assert!(nested.simulated_log_evidence_error(1, 0).is_nan());
+        let simulated = nested.simulated_log_evidence_error(500, 3);
+        assert!(
+            (simulated / expected - 1.0).abs() < 0.3,
+            "{} vs {}",
+            simulated,
+            expected
+        );
+    }
+
+    #[test]
+    fn resampling_follows_importance_weights() {
+        let posterior = posterior_samples();
+        let nested = NestedSamples::build(&posterior, 1);
+        let weights: Vec<f64> = nested
+            .posterior()
+            .iter()
+            .map(|sample| (sample.log_weight + sample.log_likelihood - nested.log_evidence()).exp())
+            .collect();
+
+        let kish = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+        assert!((nested.effective_sample_size() - kish).abs() < 1e-12);
+        assert!(nested.effective_sample_size() <= posterior.len() as f64);
+
+        let samples = nested.resample(7);
+        assert_eq!(samples.draws(), posterior.len());
+        assert_eq!(nested.resample(7).chains(), samples.chains());
+        for (index, weight) in weights.iter().enumerate() {
+            let count = samples.chains()[0]
+                .iter()
+                .filter(|draw| draw[0] == index as f64)
+                .count() as f64;
+            let expected = weight * posterior.len() as f64;
+            assert!((count - expected).abs() < 1.0, "{} vs {}", count, expected);
+        }
+    }
+
     #[test]
     fn evidence_weight_is_positive() {
         let nested = NestedSample {
@@ -1,6 +1,7 @@
 use super::results;
 use super::state::SamplerState;
 use super::MIN_LIVE_POINTS;
+use crate::checkpoint::{Checkpoint, CheckpointWriter};
 
 /// Adapts the live-point budget and termination checks during sampling.
 #[derive(Clone, Debug)]
@@ -32,6 +33,19 @@ impl Scheduler {
         }
     }
 
+    /// Record the evidence-stagnation tracking in a checkpoint.
+    pub fn write_checkpoint(&self, writer: &mut CheckpointWriter) {
+        writer.scalar("last_log_evidence", self.last_log_evidence);
+        writer.count("stagnation_counter", self.stagnation_counter);
+    }
+
+    /// Restore the tracking recorded by [`Scheduler::write_checkpoint`].
+    pub fn restore_checkpoint(&mut self, checkpoint: &Checkpoint) -> Result<(), String> {
+        self.last_log_evidence = checkpoint.scalar("last_log_evidence")?;
+        self.stagnation_counter = checkpoint.count("stagnation_counter")?;
+        Ok(())
+    }
+
     /// Compute the desired live-set size given the estimated information gain.
     pub fn target(&mut self, information: f64, current_live: usize) -> usize {
         let info = information.max(0.0);
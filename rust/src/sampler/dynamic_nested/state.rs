@@ -1,49 +1,79 @@
 use super::super::evaluate;
 use super::logspace_sub;
 use super::MIN_LIVE_POINTS;
-use crate::problem::Problem;
-use rand::rngs::StdRng;
+use crate::checkpoint::{Checkpoint, CheckpointRng, CheckpointWriter};
+use crate::problem::{Prior, Problem};
 use rand::Rng;
-use rand_distr::StandardNormal;
 use std::cmp::Ordering;
 
-/// Axis-aligned box defining sampling limits and proposal scales.
+/// Per-dimension prior used to map the unit hypercube onto parameter space.
 #[derive(Clone, Debug)]
-pub(super) struct Bounds {
-    lower: Vec<f64>,
-    upper: Vec<f64>,
+pub(super) struct PriorTransform {
+    priors: Vec<Prior>,
 }
 
+/// Unit-cube coordinates are kept this far from the edges so that unbounded priors
+/// map to finite positions.
+const UNIT_MARGIN: f64 = 1e-12;
+
 const INITIAL_EVAL_BATCH_SIZE: usize = 16;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::problem::ScalarProblemBuilder;
+    use crate::problem::{BuilderParameterExt, ParameterSpec, ScalarProblemBuilder};
     use rand::SeedableRng;
 
     fn scalar_problem() -> Problem {
         ScalarProblemBuilder::new()
             .with_objective(|x: &[f64]| (x[0] - 1.0).powi(2))
+            .with_parameter(ParameterSpec::new("x", 0.0, Some((-5.0, 5.0))))
             .build()
             .expect("failed to build test problem")
     }
 
     #[test]
-    fn bounds_clamp_respects_limits() {
-        let problem = scalar_problem();
-        let bounds = Bounds::from_problem(&problem, &[0.0], 1);
-        let mut position = vec![10.0];
-        bounds.clamp(&mut position);
-        assert!(position[0].is_finite());
+    fn prior_transform_uses_priors_then_bounds() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| x.iter().map(|v| v * v).sum())
+            .with_parameter(
+                ParameterSpec::new("a", 1.0, None).with_prior(Prior::normal(1.0, 2.0).unwrap()),
+            )
+            .with_parameter(ParameterSpec::new("b", 0.0, Some((3.0, -1.0))))
+            .build()
+            .expect("failed to build test problem");
+        let prior = PriorTransform::from_problem(&problem, 2).unwrap();
+
+        let position = prior.transform(&[0.5, 0.25]);
+        assert!((position[0] - 1.0).abs() < 1e-12);
+        assert_eq!(position[1], 0.0);
+
+        // Coordinates outside the cube are reflected back inside.
+        let reflected = prior.transform(&[0.5, 1.25]);
+        assert_eq!(reflected[1], 2.0);
+        assert_eq!(prior.to_unit(&reflected)[1], 0.75);
+    }
+
+    #[test]
+    fn prior_transform_requires_a_prior_or_bounds() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| x.iter().map(|v| v * v).sum())
+            .with_parameter(ParameterSpec::new("a", 0.0, Some((-1.0, 1.0))))
+            .with_parameter(ParameterSpec::new("c", 2.0, None))
+            .build()
+            .expect("failed to build test problem");
+
+        let error = PriorTransform::from_problem(&problem, 2).unwrap_err();
+        assert!(error.contains("'c'"), "{error}");
+        assert!(PriorTransform::from_problem(&scalar_problem(), 2).is_err());
     }
 
     #[test]
     fn sampler_state_removal_and_restoration() {
         let problem = scalar_problem();
-        let mut rng = StdRng::seed_from_u64(123);
-        let bounds = Bounds::from_problem(&problem, &[0.0], 1);
-        let live_points = initial_live_points(&problem, &bounds, &mut rng, 16, 0.1, false);
+        let mut rng = CheckpointRng::seed_from_u64(123);
+        let prior = PriorTransform::from_problem(&problem, 1).unwrap();
+        let live_points = initial_live_points(&problem, &prior, &mut rng, 16, false);
         let mut state = SamplerState::new(live_points);
 
         let original_count = state.live_point_count();
@@ -57,90 +87,82 @@ mod tests {
     #[test]
     fn initial_live_points_use_requested_count() {
         let problem = scalar_problem();
-        let mut rng = StdRng::seed_from_u64(42);
-        let bounds = Bounds::from_problem(&problem, &[0.0], 1);
-        let live_points = initial_live_points(&problem, &bounds, &mut rng, 12, 0.1, false);
+        let mut rng = CheckpointRng::seed_from_u64(42);
+        let prior = PriorTransform::from_problem(&problem, 1).unwrap();
+        let live_points = initial_live_points(&problem, &prior, &mut rng, 12, false);
         assert_eq!(live_points.len(), 12);
     }
 }
 
-impl Bounds {
-    /// Derive bounds from problem definitions or heuristics around the initial point.
-    pub fn from_problem(problem: &Problem, initial: &[f64], dimension: usize) -> Self {
-        let mut lower = vec![0.0; dimension];
-        let mut upper = vec![0.0; dimension];
-
-        for i in 0..dimension {
-            if let Some(spec) = problem.parameter_specs().specs().get(i) {
-                if let Some((lo, hi)) = spec.bounds {
-                    let (lo, hi) = if lo <= hi { (lo, hi) } else { (hi, lo) };
-                    lower[i] = lo;
-                    upper[i] = hi;
-                    continue;
-                }
-            }
-
-            let centre = initial.get(i).copied().unwrap_or(0.0);
-            let scale = (centre.abs().max(1.0)) * 5.0;
-            lower[i] = centre - scale;
-            upper[i] = centre + scale;
-        }
+impl PriorTransform {
+    /// Collect the prior of every parameter, failing if one has neither a prior nor
+    /// bounds.
+    ///
+    /// Parameters without a prior are given a uniform prior over their bounds. The prior
+    /// volume shapes the evidence, so no box is invented for unbounded parameters.
+    pub fn from_problem(problem: &Problem, dimension: usize) -> Result<Self, String> {
+        let specs = problem.parameter_specs().specs();
+        let priors = (0..dimension)
+            .map(|i| {
+                let Some(spec) = specs.get(i) else {
+                    return Err(format!(
+                        "Parameter {} needs a prior or bounds for dynamic nested sampling",
+                        i
+                    ));
+                };
+                spec.prior
+                    .or_else(|| {
+                        let (lo, hi) = spec.bounds?;
+                        Prior::uniform(lo.min(hi), lo.max(hi)).ok()
+                    })
+                    .ok_or_else(|| {
+                        format!(
+                            "Parameter '{}' needs a prior or bounds for dynamic nested sampling",
+                            spec.name
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { priors })
+    }
 
-        Self { lower, upper }
+    /// Map a position onto the unit hypercube.
+    pub fn to_unit(&self, position: &[f64]) -> Vec<f64> {
+        self.priors
+            .iter()
+            .zip(position)
+            .map(|(prior, &value)| prior.to_unit(value))
+            .collect()
     }
 
-    /// Clamp a position in-place to remain inside the bounding box.
-    pub fn clamp(&self, position: &mut [f64]) {
-        for (value, (&lo, &hi)) in position
-            .iter_mut()
-            .zip(self.lower.iter().zip(self.upper.iter()))
-        {
-            if lo.is_finite() && *value < lo {
-                *value = lo;
-            }
-            if hi.is_finite() && *value > hi {
-                *value = hi;
-            }
-        }
+    /// Map unit-hypercube coordinates onto parameter space, folding coordinates that
+    /// left the cube back inside it.
+    pub fn transform(&self, unit: &[f64]) -> Vec<f64> {
+        self.priors
+            .iter()
+            .zip(unit)
+            .map(|(prior, &u)| prior.from_unit(reflect_unit(u)))
+            .collect()
     }
 
-    /// Draw a random position inside the bounds with optional Gaussian expansion.
-    pub fn sample(&self, rng: &mut StdRng, expansion_factor: f64) -> Vec<f64> {
-        let mut position = Vec::with_capacity(self.lower.len());
-        let scale = expansion_factor.abs().max(0.05);
-        for (&lo, &hi) in self.lower.iter().zip(self.upper.iter()) {
-            if lo.is_finite() && hi.is_finite() && lo < hi {
-                let base = rng.random_range(lo..=hi);
-                let width = hi - lo;
-                let sigma = (width * scale).max(1e-6);
-                let draw = rng.sample::<f64, _>(StandardNormal);
-                let mut value = base + draw * sigma;
-                if value < lo {
-                    value = lo;
-                } else if value > hi {
-                    value = hi;
-                }
-                position.push(value);
-            } else {
-                let base = if lo.is_finite() {
-                    lo
-                } else if hi.is_finite() {
-                    hi
-                } else {
-                    0.0
-                };
-                let sigma = scale.max(0.1);
-                let offset = rng.sample::<f64, _>(StandardNormal);
-                position.push(base + offset * sigma);
-            }
-        }
-        position
+    /// Draw a position from the prior.
+    pub fn sample(&self, rng: &mut CheckpointRng) -> Vec<f64> {
+        let unit: Vec<f64> = (0..self.priors.len())
+            .map(|_| rng.random::<f64>())
+            .collect();
+        self.transform(&unit)
     }
+}
 
-    /// Return the dimensionality implied by the bounds.
-    pub fn dimension(&self) -> usize {
-        self.lower.len()
+/// Reflect a coordinate into the open unit interval.
+fn reflect_unit(u: f64) -> f64 {
+    if !u.is_finite() {
+        return 0.5;
     }
+    let folded = u.rem_euclid(2.0);
+    let reflected = if folded > 1.0 { 2.0 - folded } else { folded };
+    reflected.clamp(UNIT_MARGIN, 1.0 - UNIT_MARGIN)
 }
 
 /// Represents a candidate location that currently resides in the live set.
@@ -166,6 +188,9 @@ pub(super) struct PosteriorSample {
     pub position: Vec<f64>,
     pub log_likelihood: f64,
     pub log_weight: f64,
+    /// Size of the live set the sample was removed from, or 0 when it was still live
+    /// at termination.
+    pub live_points: usize,
 }
 
 impl PosteriorSample {
@@ -175,8 +200,15 @@ impl PosteriorSample {
             position,
             log_likelihood,
             log_weight,
+            live_points: 0,
         }
     }
+
+    /// Record the size of the live set the sample was removed from.
+    pub fn with_live_points(mut self, live_points: usize) -> Self {
+        self.live_points = live_points;
+        self
+    }
 }
 
 /// Aggregates live points, posterior archive, and log prior mass for DNS.
@@ -194,6 +226,7 @@ pub(super) struct RemovedPoint {
     pub(super) point: LivePoint,
     pub(super) log_weight: f64,
     pub(super) log_prior_before: f64,
+    pub(super) live_points: usize,
 }
 
 impl RemovedPoint {
@@ -294,15 +327,16 @@ impl SamplerState {
             return None;
         }
 
-        let n_live = self.live_points.len().max(1) as f64;
+        let live_points = self.live_points.len();
         let log_prev = self.log_prior_mass;
-        self.log_prior_mass += -1.0_f64 / n_live;
+        self.log_prior_mass += -1.0_f64 / live_points.max(1) as f64;
         let log_weight = logspace_sub(log_prev, self.log_prior_mass).unwrap_or(f64::NEG_INFINITY);
         let removed = self.live_points.swap_remove(index);
         Some(RemovedPoint {
             point: removed,
             log_weight,
             log_prior_before: log_prev,
+            live_points,
         })
     }
 
@@ -315,9 +349,15 @@ impl SamplerState {
     /// Commit a removed point to the posterior archive.
     pub fn accept_removed(&mut self, removal: RemovedPoint) {
         let RemovedPoint {
-            point, log_weight, ..
+            point,
+            log_weight,
+            live_points,
+            ..
         } = removal;
-        self.push_posterior(point.position, point.log_likelihood, log_weight);
+        self.posterior.push(
+            PosteriorSample::new(point.position, point.log_likelihood, log_weight)
+                .with_live_points(live_points),
+        );
     }
 
     /// Reinsert a previously removed point, restoring log prior mass.
@@ -336,6 +376,78 @@ impl SamplerState {
             .push(PosteriorSample::new(position, log_likelihood, log_weight));
     }
 
+    /// Record the live set, posterior archive and prior mass in a checkpoint.
+    pub fn write_checkpoint(&self, writer: &mut CheckpointWriter) {
+        let live: Vec<Vec<f64>> = self
+            .live_points
+            .iter()
+            .map(|p| p.position.clone())
+            .collect();
+        let live_log_likelihood: Vec<f64> =
+            self.live_points.iter().map(|p| p.log_likelihood).collect();
+        let posterior: Vec<Vec<f64>> = self.posterior.iter().map(|s| s.position.clone()).collect();
+        let posterior_log_likelihood: Vec<f64> =
+            self.posterior.iter().map(|s| s.log_likelihood).collect();
+        let posterior_log_weight: Vec<f64> = self.posterior.iter().map(|s| s.log_weight).collect();
+        let posterior_live_points: Vec<f64> = self
+            .posterior
+            .iter()
+            .map(|s| s.live_points as f64)
+            .collect();
+
+        writer.count("dimension", self.dimension);
+        writer.scalar("log_prior_mass", self.log_prior_mass);
+        writer.rows("live", &live);
+        writer.values("live_log_likelihood", &live_log_likelihood);
+        writer.rows("posterior", &posterior);
+        writer.values("posterior_log_likelihood", &posterior_log_likelihood);
+        writer.values("posterior_log_weight", &posterior_log_weight);
+        writer.values("posterior_live_points", &posterior_live_points);
+    }
+
+    /// Rebuild the state recorded by [`SamplerState::write_checkpoint`].
+    pub fn from_checkpoint(checkpoint: &Checkpoint) -> Result<Self, String> {
+        let dimension = checkpoint.count("dimension")?;
+        let live = checkpoint.rows("live")?;
+        let live_log_likelihood = checkpoint.values("live_log_likelihood")?;
+        let posterior = checkpoint.rows("posterior")?;
+        let posterior_log_likelihood = checkpoint.values("posterior_log_likelihood")?;
+        let posterior_log_weight = checkpoint.values("posterior_log_weight")?;
+        let posterior_live_points = checkpoint.values("posterior_live_points")?;
+
+        let consistent = live.len() == live_log_likelihood.len()
+            && posterior.len() == posterior_log_likelihood.len()
+            && posterior.len() == posterior_log_weight.len()
+            && posterior.len() == posterior_live_points.len()
+            && live
+                .iter()
+                .chain(&posterior)
+                .all(|position| position.len() == dimension);
+        if !consistent {
+            return Err("Checkpoint sampler state is inconsistent".to_string());
+        }
+
+        Ok(Self {
+            live_points: live
+                .into_iter()
+                .zip(live_log_likelihood)
+                .map(|(position, log_likelihood)| LivePoint::new(position, log_likelihood))
+                .collect(),
+            posterior: posterior
+                .into_iter()
+                .zip(posterior_log_likelihood)
+                .zip(posterior_log_weight)
+                .zip(posterior_live_points)
+                .map(|(((position, log_likelihood), log_weight), live_points)| {
+                    PosteriorSample::new(position, log_likelihood, log_weight)
+                        .with_live_points(live_points as usize)
+                })
+                .collect(),
+            log_prior_mass: checkpoint.scalar("log_prior_mass")?,
+            dimension,
+        })
+    }
+
     /// Convert remaining live points into posterior samples with residual weight.
     pub fn finalize(&mut self) {
         if self.live_points.is_empty() {
@@ -349,13 +461,12 @@ impl SamplerState {
     }
 }
 
-/// Generate an initial set of live points by sampling within bounds.
+/// Generate an initial set of live points by sampling from the prior.
 pub(super) fn initial_live_points(
     problem: &Problem,
-    bounds: &Bounds,
-    rng: &mut StdRng,
+    prior: &PriorTransform,
+    rng: &mut CheckpointRng,
     live_points: usize,
-    expansion_factor: f64,
     parallel: bool,
 ) -> Vec<LivePoint> {
     let mut samples = Vec::with_capacity(live_points);
@@ -370,16 +481,14 @@ pub(super) fn initial_live_points(
                 && batch.len() < INITIAL_EVAL_BATCH_SIZE
             {
                 attempts = attempts.saturating_add(1);
-                let mut position = bounds.sample(rng, expansion_factor);
-                bounds.clamp(&mut position);
-                batch.push(position);
+                batch.push(prior.sample(rng));
             }
 
             if batch.is_empty() {
                 continue;
             }
 
-            let results = problem.evaluate_population(&batch);
+            let results = problem.evaluate_population_strict(&batch);
             for (position, result) in batch.into_iter().zip(results.into_iter()) {
                 let log_likelihood = match result {
                     Ok(value) => {
@@ -402,8 +511,7 @@ pub(super) fn initial_live_points(
     } else {
         while samples.len() < live_points && attempts < max_attempts {
             attempts += 1;
-            let mut position = bounds.sample(rng, expansion_factor);
-            bounds.clamp(&mut position);
+            let position = prior.sample(rng);
             let log_likelihood = -evaluate(problem, &position);
             if !log_likelihood.is_finite() {
                 continue;
@@ -0,0 +1,385 @@
+//! Affine-invariant ensemble sampling in the style of emcee.
+//!
+//! A set of walkers explores the target together. The ensemble is split into two halves
+//! and each half moves using positions drawn from the other (Goodman & Weare, 2010), so
+//! the proposals of a half are independent of one another and are evaluated in a single
+//! [`Problem::evaluate_population_strict`] call. Both moves are invariant to affine
+//! transformations of the parameters, which leaves the sampler with nothing to tune for
+//! badly scaled or correlated targets.
+
+use super::{posterior_and_start, Sampler, Samples};
+use crate::problem::{LogPosterior, Problem};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rand_distr::StandardNormal;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// Relative spread of the differential-evolution scale around `2.38 / sqrt(2d)`.
+const DE_SCALE_JITTER: f64 = 1e-5;
+
+/// Ensemble MCMC sampler combining stretch and differential-evolution moves.
+#[derive(Clone, Debug)]
+pub struct EnsembleSampler {
+    walkers: Option<usize>,
+    iterations: usize,
+    stretch_scale: f64,
+    differential_evolution: f64,
+    initial_spread: f64,
+    seed: Option<u64>,
+}
+
+impl EnsembleSampler {
+    /// Create a stretch-move sampler with `max(2d, 8)` walkers.
+    pub fn new() -> Self {
+        Self {
+            walkers: None,
+            iterations: 1_000,
+            stretch_scale: 2.0,
+            differential_evolution: 0.0,
+            initial_spread: 1e-3,
+            seed: None,
+        }
+    }
+
+    /// Number of walkers; at least twice the number of parameters is required.
+    pub fn with_walkers(mut self, walkers: usize) -> Self {
+        self.walkers = Some(walkers);
+        self
+    }
+
+    /// Number of ensemble updates; every walker records one draw per update.
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Stretch parameter `a > 1` bounding the move length to `[1/a, a]`.
+    pub fn with_stretch_scale(mut self, scale: f64) -> Self {
+        self.stretch_scale = if scale.is_finite() {
+            scale.max(1.0 + 1e-6)
+        } else {
+            2.0
+        };
+        self
+    }
+
+    /// Probability that an update uses a differential-evolution move instead of a
+    /// stretch move. Mixing the two helps walkers cross between separated modes.
+    pub fn with_differential_evolution(mut self, probability: f64) -> Self {
+        self.differential_evolution = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Scatter of the initial walkers around the starting point, relative to
+    /// `max(|x_i|, 1)` for each parameter.
+    pub fn with_initial_spread(mut self, spread: f64) -> Self {
+        self.initial_spread = spread.abs().max(f64::MIN_POSITIVE);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+impl Default for EnsembleSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for EnsembleSampler {
+    /// Draw samples, failing if there are too few walkers or none of the initial walkers
+    /// has a finite objective.
+    ///
+    /// Each walker becomes one chain of `iterations` draws; the initial scatter is not
+    /// recorded. Walkers that start where the objective is not finite are pulled into
+    /// the ensemble by their first accepted move.
+    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Result<Samples, String> {
+        let start_time = Instant::now();
+        let (posterior, start) = posterior_and_start(problem, initial)?;
+        let dimension = start.len();
+
+        let walkers = self.walkers.unwrap_or((2 * dimension).max(8));
+        if walkers < (2 * dimension).max(4) {
+            return Err(format!(
+                "Ensemble sampling needs at least {} walkers for {} parameters, got {}",
+                (2 * dimension).max(4),
+                dimension,
+                walkers
+            ));
+        }
+
+        let mut rng: StdRng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        let positions: Vec<Vec<f64>> = (0..walkers)
+            .map(|_| {
+                start
+                    .iter()
+                    .map(|&x| {
+                        let z: f64 = rng.sample(StandardNormal);
+                        x + self.initial_spread * x.abs().max(1.0) * z
+                    })
+                    .collect()
+            })
+            .collect();
+        let values = evaluate_walkers(&posterior, &positions);
+        if !values.iter().any(|value| value.is_finite()) {
+            return Err("Objective is not finite at any initial walker position".to_string());
+        }
+
+        let mut ensemble = Ensemble {
+            positions,
+            values,
+            accepted: vec![0; walkers],
+            chains: vec![Vec::with_capacity(self.iterations); walkers],
+        };
+        for _ in 0..self.iterations {
+            let differential = rng.random::<f64>() < self.differential_evolution;
+            let half = walkers / 2;
+            for (active, complement) in [(0..half, half..walkers), (half..walkers, 0..half)] {
+                ensemble.update(self, &posterior, active, complement, differential, &mut rng);
+            }
+            ensemble.record();
+        }
+
+        Ok(ensemble.into_samples(start, start_time.elapsed()))
+    }
+}
+
+/// Current walker positions with their objective values and recorded draws.
+struct Ensemble {
+    positions: Vec<Vec<f64>>,
+    values: Vec<f64>,
+    accepted: Vec<usize>,
+    chains: Vec<Vec<Vec<f64>>>,
+}
+
+impl Ensemble {
+    /// Move every walker in `active` using the walkers in `complement`.
+    fn update(
+        &mut self,
+        sampler: &EnsembleSampler,
+        posterior: &LogPosterior<'_>,
+        active: Range<usize>,
+        complement: Range<usize>,
+        differential: bool,
+        rng: &mut StdRng,
+    ) {
+        let (proposals, log_factors): (Vec<Vec<f64>>, Vec<f64>) = active
+            .clone()
+            .map(|k| {
+                if differential {
+                    self.differential_move(k, complement.clone(), rng)
+                } else {
+                    self.stretch_move(k, complement.clone(), sampler.stretch_scale, rng)
+                }
+            })
+            .unzip();
+
+        let proposal_values = evaluate_walkers(posterior, &proposals);
+        for (((k, proposal), value), log_factor) in
+            active.zip(proposals).zip(proposal_values).zip(log_factors)
+        {
+            if !value.is_finite() {
+                continue;
+            }
+            let log_accept = log_factor + self.values[k] - value;
+            if log_accept >= 0.0 || rng.random::<f64>().ln() < log_accept {
+                self.positions[k] = proposal;
+                self.values[k] = value;
+                self.accepted[k] += 1;
+            }
+        }
+    }
+
+    /// Goodman-Weare stretch along the line to a random complementary walker.
+    fn stretch_move(
+        &self,
+        k: usize,
+        complement: Range<usize>,
+        scale: f64,
+        rng: &mut StdRng,
+    ) -> (Vec<f64>, f64) {
+        let u: f64 = rng.random();
+        let z = ((scale - 1.0) * u + 1.0).powi(2) / scale;
+        let anchor = &self.positions[rng.random_range(complement)];
+        let proposal = anchor
+            .iter()
+            .zip(&self.positions[k])
+            .map(|(a, x)| a + z * (x - a))
+            .collect();
+        let log_factor = (self.positions[k].len() as f64 - 1.0) * z.ln();
+        (proposal, log_factor)
+    }
+
+    /// Jump along the difference of two distinct complementary walkers (ter Braak, 2006).
+    fn differential_move(
+        &self,
+        k: usize,
+        complement: Range<usize>,
+        rng: &mut StdRng,
+    ) -> (Vec<f64>, f64) {
+        let dimension = self.positions[k].len() as f64;
+        let first = rng.random_range(complement.clone());
+        let mut second = rng.random_range(complement.start..complement.end - 1);
+        if second >= first {
+            second += 1;
+        }
+        let jitter: f64 = rng.sample(StandardNormal);
+        let gamma = 2.38 / (2.0 * dimension).sqrt() * (1.0 + DE_SCALE_JITTER * jitter);
+        let proposal = self.positions[k]
+            .iter()
+            .zip(&self.positions[first])
+            .zip(&self.positions[second])
+            .map(|((x, a), b)| x + gamma * (a - b))
+            .collect();
+        (proposal, 0.0)
+    }
+
+    fn record(&mut self) {
+        for (chain, position) in self.chains.iter_mut().zip(&self.positions) {
+            chain.push(position.clone());
+        }
+    }
+
+    fn into_samples(self, start: Vec<f64>, time: Duration) -> Samples {
+        let draws = self.chains.iter().map(Vec::len).sum::<usize>();
+        let mean_x = if draws > 0 {
+            let mut mean = vec![0.0; start.len()];
+            for sample in self.chains.iter().flatten() {
+                for (m, value) in mean.iter_mut().zip(sample) {
+                    *m += value;
+                }
+            }
+            mean.iter_mut().for_each(|m| *m /= draws as f64);
+            mean
+        } else {
+            start
+        };
+
+        let acceptance_rates = self
+            .accepted
+            .iter()
+            .zip(&self.chains)
+            .map(|(&accepted, chain)| accepted as f64 / chain.len().max(1) as f64)
+            .collect();
+        Samples::new(self.chains, mean_x, draws, time).with_acceptance_rates(acceptance_rates)
+    }
+}
+
+/// Negative log-posterior of a set of walkers in one population call, `+inf` where it
+/// cannot be evaluated.
+fn evaluate_walkers(posterior: &LogPosterior<'_>, positions: &[Vec<f64>]) -> Vec<f64> {
+    posterior
+        .log_density_population(positions)
+        .into_iter()
+        .map(|log_density| -log_density)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{BuilderParameterExt, ParameterSpec, ScalarProblemBuilder};
+
+    fn correlated_gaussian() -> Problem {
+        // Standard deviations of 1e-3 and 1e2 with correlation 0.9.
+        let (sx, sy, rho) = (1e-3, 1e2, 0.9);
+        ScalarProblemBuilder::new()
+            .with_objective(move |x: &[f64]| {
+                let (u, v) = (x[0] / sx, x[1] / sy);
+                0.5 * (u * u - 2.0 * rho * u * v + v * v) / (1.0 - rho * rho)
+            })
+            .with_parameter(ParameterSpec::new("x", 0.0, None))
+            .with_parameter(ParameterSpec::new("y", 0.0, None))
+            .build()
+            .expect("problem to build")
+    }
+
+    fn moments(samples: &Samples, burn_in: usize) -> (Vec<f64>, Vec<f64>, f64) {
+        let draws: Vec<&Vec<f64>> = samples
+            .chains()
+            .iter()
+            .flat_map(|chain| chain.iter().skip(burn_in))
+            .collect();
+        let n = draws.len() as f64;
+        let mean: Vec<f64> = (0..2)
+            .map(|k| draws.iter().map(|d| d[k]).sum::<f64>() / n)
+            .collect();
+        let cov = |a: usize, b: usize| {
+            draws
+                .iter()
+                .map(|d| (d[a] - mean[a]) * (d[b] - mean[b]))
+                .sum::<f64>()
+                / n
+        };
+        let sd = vec![cov(0, 0).sqrt(), cov(1, 1).sqrt()];
+        let corr = cov(0, 1) / (sd[0] * sd[1]);
+        (mean, sd, corr)
+    }
+
+    #[test]
+    fn ensemble_recovers_badly_scaled_correlated_gaussian() {
+        for differential_evolution in [0.0, 0.5] {
+            let samples = EnsembleSampler::new()
+                .with_walkers(16)
+                .with_iterations(3_000)
+                .with_differential_evolution(differential_evolution)
+                .with_seed(11)
+                .run(&correlated_gaussian(), vec![0.0, 0.0])
+                .expect("sampling to succeed");
+
+            assert_eq!(samples.chains().len(), 16);
+            assert!(samples.chains().iter().all(|chain| chain.len() == 3_000));
+            assert_eq!(samples.draws(), 16 * 3_000);
+
+            let (mean, sd, corr) = moments(&samples, 500);
+            assert!(mean[0].abs() < 3e-4, "mean x = {}", mean[0]);
+            assert!(mean[1].abs() < 30.0, "mean y = {}", mean[1]);
+            assert!((sd[0] / 1e-3 - 1.0).abs() < 0.2, "sd x = {}", sd[0]);
+            assert!((sd[1] / 1e2 - 1.0).abs() < 0.2, "sd y = {}", sd[1]);
+            assert!((corr - 0.9).abs() < 0.05, "correlation = {}", corr);
+        }
+    }
+
+    #[test]
+    fn ensemble_is_reproducible_and_validates_walkers() {
+        let problem = correlated_gaussian();
+        let sampler = EnsembleSampler::new()
+            .with_iterations(50)
+            .with_differential_evolution(0.3)
+            .with_seed(2);
+
+        let first = sampler.run(&problem, vec![0.0, 0.0]).unwrap();
+        let second = sampler.run(&problem, vec![0.0, 0.0]).unwrap();
+        assert_eq!(first.chains(), second.chains());
+        assert_eq!(first.chains().len(), 8);
+
+        let error = sampler
+            .clone()
+            .with_walkers(3)
+            .run(&problem, vec![0.0, 0.0])
+            .unwrap_err();
+        assert!(error.contains("walkers"));
+    }
+
+    #[test]
+    fn ensemble_rejects_infeasible_start() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| if x[0] > 10.0 { x[0] } else { f64::INFINITY })
+            .with_parameter(ParameterSpec::new("x", 0.0, None))
+            .build()
+            .expect("problem to build");
+
+        assert!(EnsembleSampler::new()
+            .with_seed(1)
+            .run(&problem, vec![0.0])
+            .is_err());
+    }
+}
@@ -0,0 +1,936 @@
+//! Hamiltonian Monte Carlo with an optional No-U-Turn trajectory.
+//!
+//! Trajectories are integrated with the leapfrog scheme using the gradients reported by
+//! [`LogPosterior::log_density_with_gradient`], i.e. the objective read as a negative
+//! log-likelihood plus the log prior of any parameter that carries one. Warmup
+//! follows the windowed scheme popularised by Stan: the step size is tuned by dual
+//! averaging throughout, while the mass matrix is re-estimated from the draws of a
+//! sequence of doubling windows.
+
+use super::{posterior_and_start, Sampler, Samples};
+use crate::problem::{LogPosterior, Problem};
+use nalgebra::{DMatrix, DVector};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rand_distr::StandardNormal;
+use std::time::Instant;
+
+/// Energy error beyond which a trajectory is considered divergent.
+const DIVERGENCE_THRESHOLD: f64 = 1000.0;
+/// Warmup shorter than this only adapts the step size.
+const MIN_METRIC_WARMUP: usize = 20;
+const INIT_BUFFER: usize = 75;
+const TERM_BUFFER: usize = 50;
+const BASE_WINDOW: usize = 25;
+const MAX_STEP_SIZE_SEARCH: usize = 100;
+
+/// How each transition integrates its trajectory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trajectory {
+    /// Classic HMC: a fixed number of leapfrog steps followed by a Metropolis correction.
+    Static { steps: usize },
+    /// No-U-Turn sampler: the trajectory doubles until it turns back on itself or
+    /// reaches `2^max_depth` leapfrog steps.
+    NoUTurn { max_depth: usize },
+}
+
+/// Form of the mass matrix adapted during warmup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MassMatrix {
+    /// Identity mass matrix; only the step size is adapted.
+    Unit,
+    /// Per-parameter scales estimated from warmup draws.
+    Diagonal,
+    /// Full covariance estimated from warmup draws, for correlated parameters.
+    Dense,
+}
+
+/// Gradient-based sampler for smooth negative log densities.
+#[derive(Clone, Debug)]
+pub struct HamiltonianMonteCarlo {
+    num_chains: usize,
+    iterations: usize,
+    warmup: usize,
+    step_size: f64,
+    target_acceptance: f64,
+    trajectory: Trajectory,
+    mass_matrix: MassMatrix,
+    seed: Option<u64>,
+}
+
+impl HamiltonianMonteCarlo {
+    /// Create a NUTS sampler with a diagonal mass matrix and 1000 warmup iterations.
+    pub fn new() -> Self {
+        Self {
+            num_chains: 1,
+            iterations: 1_000,
+            warmup: 1_000,
+            step_size: 0.1,
+            target_acceptance: 0.8,
+            trajectory: Trajectory::NoUTurn { max_depth: 10 },
+            mass_matrix: MassMatrix::Diagonal,
+            seed: None,
+        }
+    }
+
+    pub fn with_num_chains(mut self, num_chains: usize) -> Self {
+        self.num_chains = num_chains.max(1);
+        self
+    }
+
+    /// Number of draws kept per chain after warmup.
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Number of adaptation iterations per chain; these draws are discarded.
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Initial leapfrog step size, used unchanged when warmup is disabled.
+    pub fn with_step_size(mut self, step_size: f64) -> Self {
+        self.step_size = step_size.abs().max(f64::MIN_POSITIVE);
+        self
+    }
+
+    /// Mean acceptance statistic targeted by step-size adaptation.
+    pub fn with_target_acceptance(mut self, target: f64) -> Self {
+        self.target_acceptance = target.clamp(0.05, 0.99);
+        self
+    }
+
+    pub fn with_trajectory(mut self, trajectory: Trajectory) -> Self {
+        self.trajectory = match trajectory {
+            Trajectory::Static { steps } => Trajectory::Static {
+                steps: steps.max(1),
+            },
+            Trajectory::NoUTurn { max_depth } => Trajectory::NoUTurn {
+                max_depth: max_depth.max(1),
+            },
+        };
+        self
+    }
+
+    pub fn with_mass_matrix(mut self, mass_matrix: MassMatrix) -> Self {
+        self.mass_matrix = mass_matrix;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+impl Default for HamiltonianMonteCarlo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for HamiltonianMonteCarlo {
+    /// Draw samples, failing if the problem cannot provide a finite objective and
+    /// gradient at the starting point.
+    ///
+    /// Chains hold only the post-warmup draws. Evaluation failures later in a run are
+    /// treated as divergent trajectories and rejected.
+    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Result<Samples, String> {
+        let start_time = Instant::now();
+        let (posterior, start) = posterior_and_start(problem, initial)?;
+        let dimension = start.len();
+
+        let (potential, gradient) = match posterior.log_density_with_gradient(&start)? {
+            (_, None) => {
+                return Err("Hamiltonian Monte Carlo requires an available gradient".to_string())
+            }
+            (log_density, Some(gradient)) => (-log_density, negated(gradient)),
+        };
+        if gradient.len() != dimension {
+            return Err(format!(
+                "Gradient length {} does not match parameter dimension {}",
+                gradient.len(),
+                dimension
+            ));
+        }
+        if !potential.is_finite() {
+            return Err("Objective is not finite at the initial position".to_string());
+        }
+        let origin = PhasePoint {
+            position: start.clone(),
+            momentum: vec![0.0; dimension],
+            gradient,
+            potential,
+        };
+
+        let mut seed_rng: StdRng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        let (chains, acceptance_rates): (Vec<Vec<Vec<f64>>>, Vec<f64>) =
+            (0..self.num_chains.max(1))
+                .map(|_| {
+                    let rng = StdRng::seed_from_u64(seed_rng.random());
+                    Chain::new(self, posterior, dimension, rng).sample(origin.clone())
+                })
+                .unzip();
+
+        let draws = chains.iter().map(Vec::len).sum::<usize>();
+        let mean_x = if draws > 0 {
+            let mut mean = vec![0.0; dimension];
+            for sample in chains.iter().flatten() {
+                for (m, value) in mean.iter_mut().zip(sample) {
+                    *m += value;
+                }
+            }
+            mean.iter_mut().for_each(|m| *m /= draws as f64);
+            mean
+        } else {
+            start
+        };
+
+        Ok(Samples::new(chains, mean_x, draws, start_time.elapsed())
+            .with_acceptance_rates(acceptance_rates))
+    }
+}
+
+/// Position and momentum together with the potential energy and its gradient.
+#[derive(Clone, Debug)]
+struct PhasePoint {
+    position: Vec<f64>,
+    momentum: Vec<f64>,
+    gradient: Vec<f64>,
+    potential: f64,
+}
+
+/// Inverse mass matrix used for the kinetic energy.
+#[derive(Clone, Debug)]
+enum Metric {
+    Diagonal(Vec<f64>),
+    /// Inverse mass matrix together with its lower Cholesky factor.
+    Dense(DMatrix<f64>, DMatrix<f64>),
+}
+
+impl Metric {
+    fn velocity(&self, momentum: &[f64]) -> Vec<f64> {
+        match self {
+            Metric::Diagonal(inverse) => inverse.iter().zip(momentum).map(|(m, p)| m * p).collect(),
+            Metric::Dense(inverse, _) => {
+                (inverse * DVector::from_column_slice(momentum)).data.into()
+            }
+        }
+    }
+
+    fn kinetic_energy(&self, momentum: &[f64]) -> f64 {
+        0.5 * dot(momentum, &self.velocity(momentum))
+    }
+
+    /// Draw a momentum with covariance equal to the mass matrix.
+    fn sample_momentum(&self, rng: &mut StdRng) -> Vec<f64> {
+        match self {
+            Metric::Diagonal(inverse) => inverse
+                .iter()
+                .map(|m| rng.sample::<f64, _>(StandardNormal) / m.sqrt())
+                .collect(),
+            Metric::Dense(_, factor) => {
+                let noise =
+                    DVector::from_fn(factor.nrows(), |_, _| rng.sample::<f64, _>(StandardNormal));
+                factor
+                    .tr_solve_lower_triangular(&noise)
+                    .expect("Cholesky factor has a positive diagonal")
+                    .data
+                    .into()
+            }
+        }
+    }
+}
+
+/// Dual-averaging step-size adaptation (Hoffman & Gelman, 2014).
+struct DualAveraging {
+    mu: f64,
+    target: f64,
+    counter: f64,
+    h_bar: f64,
+    log_step_bar: f64,
+}
+
+impl DualAveraging {
+    const GAMMA: f64 = 0.05;
+    const T0: f64 = 10.0;
+    const KAPPA: f64 = 0.75;
+
+    fn new(step_size: f64, target: f64) -> Self {
+        Self {
+            mu: (10.0 * step_size).ln(),
+            target,
+            counter: 0.0,
+            h_bar: 0.0,
+            log_step_bar: 0.0,
+        }
+    }
+
+    fn restart(&mut self, step_size: f64) {
+        *self = Self::new(step_size, self.target);
+    }
+
+    /// Fold in the latest acceptance statistic and return the next step size.
+    fn update(&mut self, accept_stat: f64) -> f64 {
+        self.counter += 1.0;
+        let eta = 1.0 / (self.counter + Self::T0);
+        self.h_bar = (1.0 - eta) * self.h_bar + eta * (self.target - accept_stat);
+        let log_step = self.mu - self.counter.sqrt() / Self::GAMMA * self.h_bar;
+        let weight = self.counter.powf(-Self::KAPPA);
+        self.log_step_bar = weight * log_step + (1.0 - weight) * self.log_step_bar;
+        log_step.exp()
+    }
+
+    /// Averaged step size used once warmup ends.
+    fn final_step_size(&self) -> f64 {
+        self.log_step_bar.exp()
+    }
+}
+
+/// Doubling mass-matrix adaptation windows within warmup.
+struct WindowSchedule {
+    enabled: bool,
+    warmup: usize,
+    init_buffer: usize,
+    term_buffer: usize,
+    window_size: usize,
+    window_end: usize,
+    counter: usize,
+}
+
+impl WindowSchedule {
+    fn new(warmup: usize, enabled: bool) -> Self {
+        let (init_buffer, term_buffer, window_size) =
+            if INIT_BUFFER + TERM_BUFFER + BASE_WINDOW > warmup {
+                let init = warmup * 15 / 100;
+                let term = warmup / 10;
+                (init, term, warmup - init - term)
+            } else {
+                (INIT_BUFFER, TERM_BUFFER, BASE_WINDOW)
+            };
+
+        Self {
+            enabled: enabled && warmup >= MIN_METRIC_WARMUP,
+            warmup,
+            init_buffer,
+            term_buffer,
+            window_size,
+            window_end: init_buffer + window_size - 1,
+            counter: 0,
+        }
+    }
+
+    fn last_window_end(&self) -> usize {
+        self.warmup - self.term_buffer - 1
+    }
+
+    fn in_window(&self) -> bool {
+        self.enabled
+            && self.counter >= self.init_buffer
+            && self.counter < self.warmup - self.term_buffer
+    }
+
+    fn ends_window(&self) -> bool {
+        self.enabled && self.counter == self.window_end
+    }
+
+    fn next_window(&mut self) {
+        if self.window_end == self.last_window_end() {
+            return;
+        }
+        self.window_size *= 2;
+        self.window_end = self.counter + self.window_size;
+        // Stretch the next window rather than leave one too short to estimate from.
+        if self.window_end + 2 * self.window_size > self.last_window_end() {
+            self.window_end = self.last_window_end();
+        }
+    }
+}
+
+/// Streaming (Welford) estimate of the warmup draws' variance or covariance.
+struct MomentEstimator {
+    count: usize,
+    mean: DVector<f64>,
+    m2: DMatrix<f64>,
+}
+
+impl MomentEstimator {
+    fn new(dimension: usize) -> Self {
+        Self {
+            count: 0,
+            mean: DVector::zeros(dimension),
+            m2: DMatrix::zeros(dimension, dimension),
+        }
+    }
+
+    fn add(&mut self, position: &[f64]) {
+        self.count += 1;
+        let x = DVector::from_column_slice(position);
+        let delta = &x - &self.mean;
+        self.mean += &delta / self.count as f64;
+        self.m2 += &delta * (&x - &self.mean).transpose();
+    }
+
+    /// Regularised estimate shrunk towards a small multiple of the identity.
+    fn metric(&self, mass_matrix: MassMatrix) -> Option<Metric> {
+        if self.count < 3 {
+            return None;
+        }
+        let n = self.count as f64;
+        let covariance = &self.m2 / (n - 1.0);
+        let shrink = n / (n + 5.0);
+        let jitter = 1e-3 * 5.0 / (n + 5.0);
+
+        match mass_matrix {
+            MassMatrix::Unit => None,
+            MassMatrix::Diagonal => Some(Metric::Diagonal(
+                covariance
+                    .diagonal()
+                    .iter()
+                    .map(|variance| shrink * variance + jitter)
+                    .collect(),
+            )),
+            MassMatrix::Dense => {
+                let dimension = covariance.nrows();
+                let inverse =
+                    covariance * shrink + DMatrix::identity(dimension, dimension) * jitter;
+                let factor = inverse.clone().cholesky()?.l();
+                Some(Metric::Dense(inverse, factor))
+            }
+        }
+    }
+}
+
+/// Subtree of a No-U-Turn trajectory.
+struct Subtree {
+    /// Sum of momenta over the subtree.
+    rho: Vec<f64>,
+    /// Momentum of the first state integrated.
+    begin: Vec<f64>,
+    /// Last state integrated, from which the trajectory continues.
+    end: PhasePoint,
+    log_sum_weight: f64,
+    sample: PhasePoint,
+}
+
+/// Acceptance bookkeeping across the leapfrog steps of one transition.
+#[derive(Default)]
+struct TransitionStats {
+    sum_accept: f64,
+    leapfrog_steps: usize,
+}
+
+/// A single chain's adaptation state and random stream.
+struct Chain<'a> {
+    config: &'a HamiltonianMonteCarlo,
+    posterior: LogPosterior<'a>,
+    metric: Metric,
+    step_size: f64,
+    rng: StdRng,
+}
+
+impl<'a> Chain<'a> {
+    fn new(
+        config: &'a HamiltonianMonteCarlo,
+        posterior: LogPosterior<'a>,
+        dimension: usize,
+        rng: StdRng,
+    ) -> Self {
+        Self {
+            config,
+            posterior,
+            metric: Metric::Diagonal(vec![1.0; dimension]),
+            step_size: config.step_size,
+            rng,
+        }
+    }
+
+    /// Run warmup followed by the retained iterations, returning the kept draws and
+    /// their mean acceptance statistic.
+    fn sample(mut self, mut current: PhasePoint) -> (Vec<Vec<f64>>, f64) {
+        let warmup = self.config.warmup;
+        let mut draws = Vec::with_capacity(self.config.iterations);
+        let mut sum_accept = 0.0;
+
+        if warmup > 0 {
+            self.step_size = self.initial_step_size(&current);
+        }
+        let mut dual = DualAveraging::new(self.step_size, self.config.target_acceptance);
+        let mut windows = WindowSchedule::new(warmup, self.config.mass_matrix != MassMatrix::Unit);
+        let mut estimator = MomentEstimator::new(current.position.len());
+
+        for iteration in 0..warmup + self.config.iterations {
+            let (next, accept_stat) = self.transition(&current);
+            current = next;
+
+            if iteration >= warmup {
+                draws.push(current.position.clone());
+                sum_accept += accept_stat;
+                continue;
+            }
+
+            self.step_size = dual.update(accept_stat);
+            if windows.in_window() {
+                estimator.add(&current.position);
+            }
+            if windows.ends_window() {
+                if let Some(metric) = estimator.metric(self.config.mass_matrix) {
+                    self.metric = metric;
+                }
+                estimator = MomentEstimator::new(current.position.len());
+                self.step_size = self.initial_step_size(&current);
+                dual.restart(self.step_size);
+                windows.next_window();
+            }
+            windows.counter += 1;
+
+            if iteration + 1 == warmup {
+                self.step_size = dual.final_step_size();
+            }
+        }
+
+        let acceptance = sum_accept / draws.len().max(1) as f64;
+        (draws, acceptance)
+    }
+
+    fn potential_and_gradient(&self, position: &[f64]) -> Option<(f64, Vec<f64>)> {
+        match self.posterior.log_density_with_gradient(position) {
+            Ok((log_density, Some(gradient)))
+                if log_density.is_finite()
+                    && gradient.len() == position.len()
+                    && gradient.iter().all(|g| g.is_finite()) =>
+            {
+                Some((-log_density, negated(gradient)))
+            }
+            _ => None,
+        }
+    }
+
+    fn hamiltonian(&self, point: &PhasePoint) -> f64 {
+        point.potential + self.metric.kinetic_energy(&point.momentum)
+    }
+
+    /// One leapfrog step of size `step`, or `None` if the objective fails along the way.
+    fn leapfrog(&self, point: &PhasePoint, step: f64) -> Option<PhasePoint> {
+        let mut momentum: Vec<f64> = point
+            .momentum
+            .iter()
+            .zip(&point.gradient)
+            .map(|(p, g)| p - 0.5 * step * g)
+            .collect();
+        let position: Vec<f64> = point
+            .position
+            .iter()
+            .zip(self.metric.velocity(&momentum))
+            .map(|(q, v)| q + step * v)
+            .collect();
+        let (potential, gradient) = self.potential_and_gradient(&position)?;
+        for (p, g) in momentum.iter_mut().zip(&gradient) {
+            *p -= 0.5 * step * g;
+        }
+
+        Some(PhasePoint {
+            position,
+            momentum,
+            gradient,
+            potential,
+        })
+    }
+
+    fn with_fresh_momentum(&mut self, point: &PhasePoint) -> PhasePoint {
+        let mut point = point.clone();
+        point.momentum = self.metric.sample_momentum(&mut self.rng);
+        point
+    }
+
+    /// Heuristic step size at which one leapfrog step is accepted with probability ~0.8.
+    fn initial_step_size(&mut self, current: &PhasePoint) -> f64 {
+        let threshold = 0.8_f64.ln();
+        let mut step_size = self.step_size;
+        let mut direction = 0.0;
+
+        for _ in 0..MAX_STEP_SIZE_SEARCH {
+            let start = self.with_fresh_momentum(current);
+            let h0 = self.hamiltonian(&start);
+            let delta = self
+                .leapfrog(&start, step_size)
+                .map(|point| h0 - self.hamiltonian(&point))
+                .filter(|delta| delta.is_finite())
+                .unwrap_or(f64::NEG_INFINITY);
+
+            if direction == 0.0 {
+                direction = if delta > threshold { 1.0 } else { -1.0 };
+            } else if (direction > 0.0 && delta <= threshold)
+                || (direction < 0.0 && delta >= threshold)
+            {
+                break;
+            }
+
+            let next = step_size * 2.0_f64.powf(direction);
+            if !(next.is_finite() && next > 1e-12 && next < 1e7) {
+                break;
+            }
+            step_size = next;
+        }
+
+        step_size
+    }
+
+    /// Advance the chain by one transition, returning its acceptance statistic.
+    fn transition(&mut self, current: &PhasePoint) -> (PhasePoint, f64) {
+        match self.config.trajectory {
+            Trajectory::Static { steps } => self.static_transition(current, steps),
+            Trajectory::NoUTurn { max_depth } => self.no_u_turn_transition(current, max_depth),
+        }
+    }
+
+    fn static_transition(&mut self, current: &PhasePoint, steps: usize) -> (PhasePoint, f64) {
+        let start = self.with_fresh_momentum(current);
+        let h0 = self.hamiltonian(&start);
+
+        let mut point = start;
+        for _ in 0..steps {
+            match self.leapfrog(&point, self.step_size) {
+                Some(next) => point = next,
+                None => return (current.clone(), 0.0),
+            }
+        }
+
+        let energy_change = self.hamiltonian(&point) - h0;
+        let accept_prob = if energy_change.is_finite() {
+            (-energy_change).exp().min(1.0)
+        } else {
+            0.0
+        };
+
+        if self.rng.random::<f64>() < accept_prob {
+            (point, accept_prob)
+        } else {
+            (current.clone(), accept_prob)
+        }
+    }
+
+    /// Multinomial NUTS with the generalised no-U-turn criterion (Betancourt, 2017).
+    fn no_u_turn_transition(
+        &mut self,
+        current: &PhasePoint,
+        max_depth: usize,
+    ) -> (PhasePoint, f64) {
+        let start = self.with_fresh_momentum(current);
+        let h0 = self.hamiltonian(&start);
+
+        let mut minus = start.clone();
+        let mut plus = start.clone();
+        let mut rho = start.momentum.clone();
+        let mut log_sum_weight = 0.0;
+        let mut sample = start;
+        let mut stats = TransitionStats::default();
+
+        for depth in 0..max_depth {
+            let forward = self.rng.random::<bool>();
+            let (edge, outer) = if forward {
+                (plus.clone(), minus.momentum.clone())
+            } else {
+                (minus.clone(), plus.momentum.clone())
+            };
+            let direction = if forward { 1.0 } else { -1.0 };
+
+            let Some(subtree) = self.build_tree(depth, &edge, direction, h0, &mut stats) else {
+                break;
+            };
+
+            // Biased progressive sampling favours states from the new subtree.
+            let accept = (subtree.log_sum_weight - log_sum_weight).exp();
+            if accept >= 1.0 || self.rng.random::<f64>() < accept {
+                sample = subtree.sample.clone();
+            }
+            log_sum_weight = log_add_exp(log_sum_weight, subtree.log_sum_weight);
+
+            let combined = add(&rho, &subtree.rho);
+            let continues = self.no_u_turn(&combined, &outer, &subtree.end.momentum)
+                && self.no_u_turn(&add(&rho, &subtree.begin), &outer, &subtree.begin)
+                && self.no_u_turn(
+                    &add(&subtree.rho, &edge.momentum),
+                    &edge.momentum,
+                    &subtree.end.momentum,
+                );
+
+            if forward {
+                plus = subtree.end;
+            } else {
+                minus = subtree.end;
+            }
+            rho = combined;
+
+            if !continues {
+                break;
+            }
+        }
+
+        let accept_stat = stats.sum_accept / stats.leapfrog_steps.max(1) as f64;
+        (sample, accept_stat)
+    }
+
+    /// Build a subtree of `2^depth` leapfrog steps from `edge`, or `None` if it diverges
+    /// or makes a U-turn.
+    fn build_tree(
+        &mut self,
+        depth: usize,
+        edge: &PhasePoint,
+        direction: f64,
+        h0: f64,
+        stats: &mut TransitionStats,
+    ) -> Option<Subtree> {
+        if depth == 0 {
+            stats.leapfrog_steps += 1;
+            let point = self.leapfrog(edge, direction * self.step_size)?;
+            let energy_change = self.hamiltonian(&point) - h0;
+            if !energy_change.is_finite() || energy_change > DIVERGENCE_THRESHOLD {
+                return None;
+            }
+            stats.sum_accept += (-energy_change).exp().min(1.0);
+
+            return Some(Subtree {
+                rho: point.momentum.clone(),
+                begin: point.momentum.clone(),
+                log_sum_weight: -energy_change,
+                sample: point.clone(),
+                end: point,
+            });
+        }
+
+        let first = self.build_tree(depth - 1, edge, direction, h0, stats)?;
+        let second = self.build_tree(depth - 1, &first.end, direction, h0, stats)?;
+
+        let log_sum_weight = log_add_exp(first.log_sum_weight, second.log_sum_weight);
+        let sample = if self.rng.random::<f64>() < (second.log_sum_weight - log_sum_weight).exp() {
+            second.sample
+        } else {
+            first.sample
+        };
+
+        let rho = add(&first.rho, &second.rho);
+        let continues = self.no_u_turn(&rho, &first.begin, &second.end.momentum)
+            && self.no_u_turn(&add(&first.rho, &second.begin), &first.begin, &second.begin)
+            && self.no_u_turn(
+                &add(&second.rho, &first.end.momentum),
+                &first.end.momentum,
+                &second.end.momentum,
+            );
+        if !continues {
+            return None;
+        }
+
+        Some(Subtree {
+            rho,
+            begin: first.begin,
+            end: second.end,
+            log_sum_weight,
+            sample,
+        })
+    }
+
+    /// Whether a trajectory spanned by momentum sum `rho` is still moving apart.
+    fn no_u_turn(&self, rho: &[f64], first: &[f64], last: &[f64]) -> bool {
+        dot(&self.metric.velocity(first), rho) > 0.0 && dot(&self.metric.velocity(last), rho) > 0.0
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn add(a: &[f64], b: &[f64]) -> Vec<f64> {
+    a.iter().zip(b).map(|(x, y)| x + y).collect()
+}
+
+fn log_add_exp(a: f64, b: f64) -> f64 {
+    let max = a.max(b);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + ((a - max).exp() + (b - max).exp()).ln()
+}
+
+fn negated(values: Vec<f64>) -> Vec<f64> {
+    values.into_iter().map(|value| -value).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{BuilderParameterExt, ParameterSpec, Prior, ScalarProblemBuilder};
+
+    /// Bivariate normal with standard deviations (1, 2) and correlation 0.9.
+    fn correlated_gaussian() -> Problem {
+        let (s1, s2, r) = (1.0_f64, 2.0_f64, 0.9_f64);
+        let det = s1 * s1 * s2 * s2 * (1.0 - r * r);
+        let precision = [s2 * s2 / det, -r * s1 * s2 / det, s1 * s1 / det];
+        ScalarProblemBuilder::new()
+            .with_objective_and_gradient(
+                move |x: &[f64]| {
+                    0.5 * (precision[0] * x[0] * x[0]
+                        + 2.0 * precision[1] * x[0] * x[1]
+                        + precision[2] * x[1] * x[1])
+                },
+                move |x: &[f64]| {
+                    vec![
+                        precision[0] * x[0] + precision[1] * x[1],
+                        precision[1] * x[0] + precision[2] * x[1],
+                    ]
+                },
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn moments(samples: &Samples) -> (Vec<f64>, DMatrix<f64>) {
+        let draws: Vec<&Vec<f64>> = samples.chains().iter().flatten().collect();
+        let n = draws.len() as f64;
+        let mean = samples.mean_x().to_vec();
+        let mut covariance = DMatrix::zeros(2, 2);
+        for draw in draws {
+            for i in 0..2 {
+                for j in 0..2 {
+                    covariance[(i, j)] += (draw[i] - mean[i]) * (draw[j] - mean[j]) / (n - 1.0);
+                }
+            }
+        }
+        (mean, covariance)
+    }
+
+    #[test]
+    fn nuts_with_dense_metric_recovers_correlated_gaussian() {
+        let sampler = HamiltonianMonteCarlo::new()
+            .with_num_chains(2)
+            .with_warmup(500)
+            .with_iterations(1_000)
+            .with_mass_matrix(MassMatrix::Dense)
+            .with_seed(3);
+
+        let samples = sampler
+            .run(&correlated_gaussian(), vec![2.0, -2.0])
+            .unwrap();
+        let (mean, covariance) = moments(&samples);
+
+        assert_eq!(samples.chains().len(), 2);
+        assert!(samples.chains().iter().all(|chain| chain.len() == 1_000));
+        assert_eq!(samples.draws(), 2_000);
+        assert!(
+            mean[0].abs() < 0.2 && mean[1].abs() < 0.4,
+            "mean {:?}",
+            mean
+        );
+        assert!((covariance[(0, 0)] - 1.0).abs() < 0.25);
+        assert!((covariance[(1, 1)] - 4.0).abs() < 1.0);
+        assert!((covariance[(0, 1)] - 1.8).abs() < 0.5);
+    }
+
+    #[test]
+    fn static_hmc_with_diagonal_metric_recovers_gaussian() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective_and_gradient(
+                |x: &[f64]| 0.5 * ((x[0] - 1.0) / 0.5).powi(2),
+                |x: &[f64]| vec![(x[0] - 1.0) / 0.25],
+            )
+            .build()
+            .unwrap();
+        let sampler = HamiltonianMonteCarlo::new()
+            .with_trajectory(Trajectory::Static { steps: 8 })
+            .with_warmup(300)
+            .with_iterations(2_000)
+            .with_seed(9);
+
+        let samples = sampler.run(&problem, vec![0.0]).unwrap();
+        let draws: Vec<f64> = samples.chains()[0].iter().map(|x| x[0]).collect();
+        let variance = draws
+            .iter()
+            .map(|x| (x - samples.mean_x()[0]).powi(2))
+            .sum::<f64>()
+            / (draws.len() - 1) as f64;
+
+        assert!((samples.mean_x()[0] - 1.0).abs() < 0.1);
+        assert!((variance - 0.25).abs() < 0.08, "variance {}", variance);
+    }
+
+    #[test]
+    fn parameter_priors_enter_potential_and_gradient() {
+        // A flat likelihood leaves the Gamma(3, 2) prior as the target: mean 1.5, variance 0.75.
+        let problem = ScalarProblemBuilder::new()
+            .with_objective_and_gradient(|_: &[f64]| 0.0, |_: &[f64]| vec![0.0])
+            .with_parameter(
+                ParameterSpec::new("x", 1.0, None).with_prior(Prior::gamma(3.0, 2.0).unwrap()),
+            )
+            .build()
+            .unwrap();
+        let sampler = HamiltonianMonteCarlo::new()
+            .with_warmup(300)
+            .with_iterations(3_000)
+            .with_seed(4);
+
+        let samples = sampler.run(&problem, vec![1.0]).unwrap();
+        assert!(samples.chains()[0].iter().all(|x| x[0] > 0.0));
+        assert!((samples.mean_x()[0] - 1.5).abs() < 0.1);
+        let variance = samples.covariance()[0][0];
+        assert!((variance - 0.75).abs() < 0.15, "variance {}", variance);
+    }
+
+    #[test]
+    fn requires_gradient() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| x[0] * x[0])
+            .build()
+            .unwrap();
+        let sampler = HamiltonianMonteCarlo::new().with_seed(1);
+
+        let err = sampler.run(&problem, vec![1.0]).unwrap_err();
+        assert!(err.contains("gradient"));
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let sampler = HamiltonianMonteCarlo::new()
+            .with_warmup(50)
+            .with_iterations(50)
+            .with_seed(21);
+        let problem = correlated_gaussian();
+
+        let first = sampler.run(&problem, vec![0.5, 0.5]).unwrap();
+        let second = sampler.run(&problem, vec![0.5, 0.5]).unwrap();
+        assert_eq!(first.chains(), second.chains());
+    }
+
+    #[test]
+    fn adaptation_windows_double_until_terminal_buffer() {
+        let mut windows = WindowSchedule::new(1_000, true);
+        let mut ends = Vec::new();
+        for _ in 0..1_000 {
+            if windows.ends_window() {
+                ends.push(windows.counter);
+                windows.next_window();
+            }
+            windows.counter += 1;
+        }
+        assert_eq!(ends, vec![99, 149, 249, 449, 949]);
+
+        let disabled = WindowSchedule::new(10, true);
+        assert!(!disabled.in_window() && !disabled.ends_window());
+    }
+
+    #[test]
+    fn dual_averaging_shrinks_step_when_acceptance_is_low() {
+        let mut dual = DualAveraging::new(1.0, 0.8);
+        let mut step = 1.0;
+        for _ in 0..50 {
+            step = dual.update(0.1);
+        }
+        assert!(step < 1.0);
+        assert!(dual.final_step_size() < 1.0);
+    }
+}
@@ -1,17 +1,32 @@
-use crate::problem::Problem;
+use crate::checkpoint::{Checkpoint, CheckpointConfig, CheckpointRng, CheckpointWriter};
+use crate::problem::{LogPosterior, Problem};
+use nalgebra::{DMatrix, DVector};
 use rand::prelude::*;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use rand_distr::StandardNormal;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+mod diagnostics;
 mod dynamic_nested;
-
-pub use dynamic_nested::{DynamicNestedSampler, NestedSample, NestedSamples};
+mod ensemble;
+mod hamiltonian;
+mod smc;
+mod tempering;
+
+pub use dynamic_nested::{
+    Bounding, DynamicNestedSampler, NestedProposal, NestedSample, NestedSamples,
+};
+pub use ensemble::EnsembleSampler;
+pub use hamiltonian::{HamiltonianMonteCarlo, MassMatrix, Trajectory};
+pub use smc::{ParticleSamples, SequentialMonteCarlo};
+pub use tempering::{ParallelTempering, TemperedSamples};
 
 /// Core behaviour shared by all samplers.
 pub trait Sampler {
-    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Samples;
+    /// Draw samples starting from `initial`, or report why the problem cannot be sampled.
+    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Result<Samples, String>;
 }
 
 #[derive(Clone, Debug)]
@@ -20,6 +35,7 @@ pub struct Samples {
     mean_x: Vec<f64>,
     draws: usize,
     time: Duration,
+    acceptance_rates: Option<Vec<f64>>,
 }
 
 impl Samples {
@@ -29,9 +45,16 @@ impl Samples {
             mean_x,
             draws,
             time,
+            acceptance_rates: None,
         }
     }
 
+    /// Attach the mean acceptance rate of each chain.
+    pub fn with_acceptance_rates(mut self, rates: Vec<f64>) -> Self {
+        self.acceptance_rates = Some(rates);
+        self
+    }
+
     pub fn chains(&self) -> &[Vec<Vec<f64>>] {
         &self.chains
     }
@@ -54,7 +77,11 @@ pub struct MetropolisHastings {
     num_chains: usize,
     iterations: usize,
     step_size: f64,
+    proposal_covariance: Option<Vec<Vec<f64>>>,
+    warmup: usize,
+    target_acceptance: f64,
     seed: Option<u64>,
+    checkpoint: Option<CheckpointConfig>,
 }
 
 impl MetropolisHastings {
@@ -63,7 +90,11 @@ impl MetropolisHastings {
             num_chains: 1,
             iterations: 1_000,
             step_size: 0.1,
+            proposal_covariance: None,
+            warmup: 0,
+            target_acceptance: 0.234,
             seed: None,
+            checkpoint: None,
         }
     }
 
@@ -82,10 +113,114 @@ impl MetropolisHastings {
         self
     }
 
+    /// Shape proposals as `step_size * L z` with `L L^T = covariance`, for example the
+    /// `covariance` of an optimisation result. Defaults to the identity.
+    pub fn with_proposal_covariance(mut self, covariance: Vec<Vec<f64>>) -> Self {
+        self.proposal_covariance = Some(covariance);
+        self
+    }
+
+    /// Run `warmup` discarded iterations of adaptive Metropolis (Haario et al., 2001)
+    /// before sampling, learning each chain's proposal covariance from its draws and
+    /// tuning the step size towards the target acceptance rate.
+    pub fn with_adaptation(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Acceptance rate targeted by step-size adaptation during warm-up.
+    pub fn with_target_acceptance(mut self, target: f64) -> Self {
+        self.target_acceptance = target.clamp(0.01, 0.99);
+        self
+    }
+
     pub fn with_seed(mut self, seed: u64) -> Self {
         self.seed = Some(seed);
         self
     }
+
+    /// Write the chains and their RNG states to `path` every `every` iterations so an
+    /// interrupted run can continue with [`MetropolisHastings::resume_from`].
+    ///
+    /// The full chains are stored, so checkpoints grow with the number of draws. The run
+    /// fails if a checkpoint cannot be written.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>, every: usize) -> Self {
+        self.checkpoint = Some(CheckpointConfig::new(path, every));
+        self
+    }
+
+    /// Continue a run from a checkpoint written by [`MetropolisHastings::with_checkpoint`]
+    /// until every chain holds this sampler's number of iterations.
+    pub fn resume_from(
+        &self,
+        problem: &Problem,
+        path: impl AsRef<Path>,
+    ) -> Result<Samples, String> {
+        let posterior = LogPosterior::new(problem)?;
+        let checkpoint = Checkpoint::load(path.as_ref(), "metropolis_hastings")?;
+        let chains = ChainsState::from_checkpoint(&checkpoint)?;
+        if chains.dimension() != problem.dimension() && problem.dimension() > 0 {
+            return Err(format!(
+                "Checkpoint dimension {} does not match problem dimension {}",
+                chains.dimension(),
+                problem.dimension()
+            ));
+        }
+
+        let elapsed = checkpoint.duration("elapsed")?;
+        let start_time = Instant::now()
+            .checked_sub(elapsed)
+            .unwrap_or_else(Instant::now);
+        self.drive(posterior, chains, start_time)
+    }
+
+    /// Validated proposal covariance, `None` for the identity.
+    fn covariance(&self, dimension: usize) -> Result<Option<DMatrix<f64>>, String> {
+        let Some(rows) = &self.proposal_covariance else {
+            return Ok(None);
+        };
+        if rows.len() != dimension || rows.iter().any(|row| row.len() != dimension) {
+            return Err(format!(
+                "Proposal covariance must be a {} x {} matrix",
+                dimension, dimension
+            ));
+        }
+        let covariance = DMatrix::from_fn(dimension, dimension, |i, j| rows[i][j]);
+        if covariance.clone().cholesky().is_none() {
+            return Err("Proposal covariance must be symmetric positive definite".to_string());
+        }
+        Ok(Some(covariance))
+    }
+
+    /// Advance all chains in lockstep through warm-up and the configured iterations,
+    /// failing if a due checkpoint cannot be written.
+    fn drive(
+        &self,
+        posterior: LogPosterior<'_>,
+        mut chains: ChainsState,
+        start_time: Instant,
+    ) -> Result<Samples, String> {
+        let batched = chains.rngs.len() > 1 && problem_is_parallel(posterior.problem());
+        let adaptation = Adaptation {
+            warmup: self.warmup,
+            target_acceptance: self.target_acceptance,
+        };
+
+        while chains.iteration < self.iterations {
+            chains.step(&posterior, &adaptation, batched);
+
+            let steps = chains.warmup_iteration + chains.iteration;
+            if let Some(checkpoint) = &self.checkpoint {
+                if chains.iteration < self.iterations && checkpoint.is_due(steps) {
+                    chains
+                        .checkpoint(start_time.elapsed())
+                        .save(checkpoint.path())?;
+                }
+            }
+        }
+
+        Ok(chains.into_samples(self.iterations, start_time.elapsed()))
+    }
 }
 
 impl Default for MetropolisHastings {
@@ -95,211 +230,361 @@ impl Default for MetropolisHastings {
 }
 
 impl Sampler for MetropolisHastings {
-    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Samples {
+    /// Random-walk chains from `initial`; fails if the proposal covariance does not suit
+    /// the problem.
+    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Result<Samples, String> {
         let start_time = Instant::now();
+        let (posterior, start) = posterior_and_start(problem, initial)?;
+        let dimension = start.len();
 
-        let dimension = match (problem.dimension(), initial.len()) {
-            (d, _) if d > 0 => d,
-            (0, len) if len > 0 => len,
-            _ => 1,
-        };
-
-        let mut start = if initial.is_empty() {
-            vec![0.0; dimension]
-        } else {
-            let mut init = initial;
-            if init.len() < dimension {
-                init.resize(dimension, 0.0);
-            } else if init.len() > dimension {
-                init.truncate(dimension);
-            }
-            init
-        };
-
-        if start.len() != dimension {
-            start.resize(dimension, 0.0);
-        }
-
+        let proposal = Proposal::new(self.step_size, self.covariance(dimension)?);
         let num_chains = self.num_chains.max(1);
-        let iterations = self.iterations;
-        let step_size = self.step_size;
 
         let mut seed_rng: StdRng = match self.seed {
             Some(seed) => StdRng::seed_from_u64(seed),
             None => StdRng::from_os_rng(),
         };
 
-        let seeds: Vec<u64> = (0..num_chains).map(|_| seed_rng.random()).collect();
-        let initial_state = start.clone();
-        let problem_parallel = problem
-            .get_config("parallel")
-            .copied()
-            .map(|value| value != 0.0)
-            .unwrap_or(false);
-
-        let chains: Vec<Vec<Vec<f64>>> = if num_chains > 1 && problem_parallel {
-            run_chains_batched(problem, &initial_state, iterations, step_size, &seeds)
-        } else {
-            seeds
-                .into_iter()
-                .map(|seed| run_chain(problem, &initial_state, iterations, step_size, seed))
-                .collect()
-        };
+        let rngs: Vec<CheckpointRng> = (0..num_chains)
+            .map(|_| CheckpointRng::seed_from_u64(seed_rng.random()))
+            .collect();
+        let batched = num_chains > 1 && problem_is_parallel(problem);
+        let chains = ChainsState::new(&posterior, start, rngs, proposal, batched);
 
-        let draws = iterations.saturating_mul(num_chains);
-        let mut mean_x = initial_state.clone();
+        self.drive(posterior, chains, start_time)
+    }
+}
 
-        if draws > 0 {
-            mean_x.fill(0.0);
-            for chain in &chains {
-                for sample in chain.iter().skip(1) {
-                    for (i, value) in sample.iter().enumerate() {
-                        mean_x[i] += *value;
-                    }
-                }
-            }
+/// Log-posterior of `problem` and `initial` padded with zeros or truncated to the
+/// problem's dimension, failing if the problem's costs are not a likelihood (see
+/// [`LogPosterior`]). Problems without parameters take their dimension from `initial`.
+pub(super) fn posterior_and_start(
+    problem: &Problem,
+    initial: Vec<f64>,
+) -> Result<(LogPosterior<'_>, Vec<f64>), String> {
+    let posterior = LogPosterior::new(problem)?;
+    let dimension = match (problem.dimension(), initial.len()) {
+        (d, _) if d > 0 => d,
+        (0, len) if len > 0 => len,
+        _ => 1,
+    };
+    let mut start = initial;
+    start.resize(dimension, 0.0);
+    Ok((posterior, start))
+}
 
-            for value in &mut mean_x {
-                *value /= draws as f64;
-            }
-        } else if let Some(last) = chains.first().and_then(|chain| chain.last()).cloned() {
-            mean_x = last;
-        }
+pub(super) fn problem_is_parallel(problem: &Problem) -> bool {
+    problem
+        .get_config("parallel")
+        .copied()
+        .map(|value| value != 0.0)
+        .unwrap_or(false)
+}
 
-        let time = start_time.elapsed();
+/// Warm-up settings shared by every chain's proposal adaptation.
+struct Adaptation {
+    warmup: usize,
+    target_acceptance: f64,
+}
 
-        Samples::new(chains, mean_x, draws, time)
-    }
+/// Gaussian random-walk proposal `scale * L z` of a single chain.
+#[derive(Clone)]
+struct Proposal {
+    scale: f64,
+    /// Lower Cholesky factor `L` of the proposal covariance; `None` for the identity.
+    factor: Option<DMatrix<f64>>,
+    /// Running mean and scatter of the warm-up draws.
+    count: usize,
+    mean: DVector<f64>,
+    scatter: DMatrix<f64>,
 }
 
-fn run_chain(
-    problem: &Problem,
-    initial: &[f64],
-    iterations: usize,
-    step_size: f64,
-    seed: u64,
-) -> Vec<Vec<f64>> {
-    let mut rng = StdRng::seed_from_u64(seed);
-    let mut current = initial.to_vec();
-    let mut current_val = evaluate(problem, &current);
-    let mut samples = Vec::with_capacity(iterations.saturating_add(1));
-    samples.push(current.clone());
-
-    for _ in 0..iterations {
-        let mut proposal = current.clone();
-        for value in &mut proposal {
-            let noise: f64 = rng.sample(StandardNormal);
-            *value += step_size * noise;
+impl Proposal {
+    fn new(scale: f64, covariance: Option<DMatrix<f64>>) -> Self {
+        let dimension = covariance.as_ref().map_or(0, DMatrix::nrows);
+        Self {
+            scale,
+            factor: covariance.and_then(|covariance| Some(covariance.cholesky()?.l())),
+            count: 0,
+            mean: DVector::zeros(dimension),
+            scatter: DMatrix::zeros(dimension, dimension),
         }
+    }
 
-        let proposal_val = evaluate(problem, &proposal);
-
-        let accept = if !proposal_val.is_finite() {
-            false
-        } else {
-            let acceptance_log = current_val - proposal_val;
-            if acceptance_log >= 0.0 {
-                true
-            } else {
-                let u: f64 = rng.random();
-                u < acceptance_log.exp()
-            }
+    fn propose(&self, current: &[f64], rng: &mut CheckpointRng) -> Vec<f64> {
+        let noise: Vec<f64> = (0..current.len())
+            .map(|_| rng.sample(StandardNormal))
+            .collect();
+        let step = match &self.factor {
+            Some(factor) => (factor * DVector::from_vec(noise)).data.into(),
+            None => noise,
         };
+        current
+            .iter()
+            .zip(step)
+            .map(|(value, step)| value + self.scale * step)
+            .collect()
+    }
+
+    /// Robbins-Monro update of the scale, then refit the covariance to the draws so far.
+    fn adapt(&mut self, position: &[f64], accept_prob: f64, adaptation: &Adaptation) {
+        self.count += 1;
+        let n = self.count as f64;
+        self.scale *= ((accept_prob - adaptation.target_acceptance) * n.powf(-0.6)).exp();
 
-        if accept {
-            current = proposal;
-            current_val = proposal_val;
+        let x = DVector::from_column_slice(position);
+        if self.mean.len() != x.len() {
+            self.mean = DVector::zeros(x.len());
+            self.scatter = DMatrix::zeros(x.len(), x.len());
         }
+        let delta = &x - &self.mean;
+        self.mean += &delta / n;
+        self.scatter += &delta * (&x - &self.mean).transpose();
 
-        samples.push(current.clone());
+        if self.count < 2 {
+            return;
+        }
+        // A little jitter, relative to the draws' own scale, keeps the factorisation
+        // defined once a parameter has barely moved.
+        let mut covariance = &self.scatter / (n - 1.0);
+        let jitter = 1e-8 * covariance.trace() / covariance.nrows() as f64;
+        covariance += DMatrix::identity(covariance.nrows(), covariance.nrows()) * jitter;
+        if let Some(cholesky) = covariance.cholesky() {
+            self.factor = Some(cholesky.l());
+        }
     }
-
-    samples
 }
 
-fn run_chains_batched(
-    problem: &Problem,
-    initial: &[f64],
-    iterations: usize,
-    step_size: f64,
-    seeds: &[u64],
-) -> Vec<Vec<Vec<f64>>> {
-    let num_chains = seeds.len();
-
-    let mut rngs: Vec<StdRng> = seeds
-        .iter()
-        .map(|&seed| StdRng::seed_from_u64(seed))
-        .collect();
-
-    let mut currents: Vec<Vec<f64>> = (0..num_chains).map(|_| initial.to_vec()).collect();
+/// Metropolis-Hastings chains advanced in lockstep, each with its own RNG stream.
+struct ChainsState {
+    samples: Vec<Vec<Vec<f64>>>,
+    current_vals: Vec<f64>,
+    rngs: Vec<CheckpointRng>,
+    proposals: Vec<Proposal>,
+    /// Accepted proposals per chain after warm-up.
+    accepted: Vec<usize>,
+    warmup_iteration: usize,
+    iteration: usize,
+}
 
-    // Evaluate initial state for all chains in a single population call.
-    let initial_values: Vec<f64> = problem
-        .evaluate_population(&currents)
-        .into_iter()
-        .map(|res| match res {
-            Ok(value) => value,
-            Err(_) => f64::INFINITY,
-        })
-        .collect();
+impl ChainsState {
+    fn new(
+        posterior: &LogPosterior<'_>,
+        start: Vec<f64>,
+        rngs: Vec<CheckpointRng>,
+        proposal: Proposal,
+        batched: bool,
+    ) -> Self {
+        let currents = vec![start; rngs.len()];
+        let current_vals = evaluate_chains(posterior, &currents, batched);
+        Self {
+            samples: currents.into_iter().map(|current| vec![current]).collect(),
+            current_vals,
+            proposals: vec![proposal; rngs.len()],
+            accepted: vec![0; rngs.len()],
+            rngs,
+            warmup_iteration: 0,
+            iteration: 0,
+        }
+    }
 
-    let mut current_vals = initial_values;
+    fn dimension(&self) -> usize {
+        self.samples[0][0].len()
+    }
 
-    let mut samples: Vec<Vec<Vec<f64>>> = (0..num_chains)
-        .map(|idx| {
-            let mut chain = Vec::with_capacity(iterations.saturating_add(1));
-            chain.push(currents[idx].clone());
-            chain
-        })
-        .collect();
+    fn step(&mut self, posterior: &LogPosterior<'_>, adaptation: &Adaptation, batched: bool) {
+        let adapting = self.warmup_iteration < adaptation.warmup;
 
-    for _ in 0..iterations {
         // Propose one candidate for each chain.
-        let mut proposals: Vec<Vec<f64>> = Vec::with_capacity(num_chains);
-        for (idx, current) in currents.iter().enumerate() {
-            let mut proposal = current.clone();
-            for value in &mut proposal {
-                let noise: f64 = rngs[idx].sample(StandardNormal);
-                *value += step_size * noise;
-            }
-            proposals.push(proposal);
-        }
-
-        // Evaluate all proposals in a single batched call.
-        let proposal_vals: Vec<f64> = problem
-            .evaluate_population(&proposals)
-            .into_iter()
-            .map(|res| match res {
-                Ok(value) => value,
-                Err(_) => f64::INFINITY,
+        let proposals: Vec<Vec<f64>> = self
+            .samples
+            .iter()
+            .zip(&self.proposals)
+            .zip(self.rngs.iter_mut())
+            .map(|((chain, proposal), rng)| {
+                proposal.propose(chain.last().expect("chain holds its start"), rng)
             })
             .collect();
 
-        for idx in 0..num_chains {
-            let proposal_val = proposal_vals[idx];
+        let proposal_vals = evaluate_chains(posterior, &proposals, batched);
 
-            let accept = if !proposal_val.is_finite() {
-                false
+        for (idx, (proposal, proposal_val)) in proposals.into_iter().zip(proposal_vals).enumerate()
+        {
+            let (accept, accept_prob) = if !proposal_val.is_finite() {
+                (false, 0.0)
             } else {
-                let acceptance_log = current_vals[idx] - proposal_val;
+                let acceptance_log = self.current_vals[idx] - proposal_val;
                 if acceptance_log >= 0.0 {
-                    true
+                    (true, 1.0)
                 } else {
-                    let u: f64 = rngs[idx].random();
-                    u < acceptance_log.exp()
+                    let u: f64 = self.rngs[idx].random();
+                    (u < acceptance_log.exp(), acceptance_log.exp())
                 }
             };
 
-            if accept {
-                currents[idx] = proposals[idx].clone();
-                current_vals[idx] = proposal_val;
+            let chain = &mut self.samples[idx];
+            let next = if accept {
+                self.current_vals[idx] = proposal_val;
+                proposal
+            } else {
+                chain.last().expect("chain holds its start").clone()
+            };
+
+            if accept && !adapting {
+                self.accepted[idx] += 1;
             }
+            if adapting {
+                // Warm-up draws are discarded; the chain starts from the last of them.
+                self.proposals[idx].adapt(&next, accept_prob, adaptation);
+                *chain = vec![next];
+            } else {
+                chain.push(next);
+            }
+        }
 
-            samples[idx].push(currents[idx].clone());
+        if adapting {
+            self.warmup_iteration += 1;
+        } else {
+            self.iteration += 1;
         }
     }
 
-    samples
+    fn checkpoint(&self, elapsed: Duration) -> CheckpointWriter {
+        let mut writer = CheckpointWriter::new("metropolis_hastings");
+        writer.count("chains", self.samples.len());
+        writer.count("warmup_iteration", self.warmup_iteration);
+        writer.count("iteration", self.iteration);
+        writer.duration("elapsed", elapsed);
+        writer.values("current_values", &self.current_vals);
+        for (idx, ((chain, rng), proposal)) in self
+            .samples
+            .iter()
+            .zip(&self.rngs)
+            .zip(&self.proposals)
+            .enumerate()
+        {
+            writer.rows(&format!("chain.{}", idx), chain);
+            writer.rng(&format!("rng.{}", idx), rng);
+            writer.count(&format!("accepted.{}", idx), self.accepted[idx]);
+            writer.scalar(&format!("scale.{}", idx), proposal.scale);
+            if let Some(factor) = &proposal.factor {
+                writer.matrix(&format!("factor.{}", idx), factor);
+            }
+            writer.count(&format!("adapt_count.{}", idx), proposal.count);
+            writer.vector(&format!("adapt_mean.{}", idx), &proposal.mean);
+            writer.matrix(&format!("adapt_scatter.{}", idx), &proposal.scatter);
+        }
+        writer
+    }
+
+    fn from_checkpoint(checkpoint: &Checkpoint) -> Result<Self, String> {
+        let num_chains = checkpoint.count("chains")?;
+        let warmup_iteration = checkpoint.count("warmup_iteration")?;
+        let iteration = checkpoint.count("iteration")?;
+        let current_vals = checkpoint.values("current_values")?;
+        let samples = (0..num_chains)
+            .map(|idx| checkpoint.rows(&format!("chain.{}", idx)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rngs = (0..num_chains)
+            .map(|idx| checkpoint.rng(&format!("rng.{}", idx)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let proposals = (0..num_chains)
+            .map(|idx| {
+                let factor_key = format!("factor.{}", idx);
+                let mean = checkpoint.values(&format!("adapt_mean.{}", idx))?;
+                Ok(Proposal {
+                    scale: checkpoint.scalar(&format!("scale.{}", idx))?,
+                    factor: if checkpoint.contains(&format!("{}.shape", factor_key)) {
+                        Some(checkpoint.matrix(&factor_key)?)
+                    } else {
+                        None
+                    },
+                    count: checkpoint.count(&format!("adapt_count.{}", idx))?,
+                    scatter: checkpoint.matrix(&format!("adapt_scatter.{}", idx))?,
+                    mean: DVector::from_vec(mean),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let accepted = (0..num_chains)
+            .map(|idx| checkpoint.count(&format!("accepted.{}", idx)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let dimension = samples
+            .first()
+            .and_then(|chain| chain.first())
+            .map(Vec::len);
+        let consistent = num_chains > 0
+            && current_vals.len() == num_chains
+            && samples.iter().all(|chain| {
+                chain.len() == iteration + 1
+                    && chain.iter().all(|sample| Some(sample.len()) == dimension)
+            })
+            && proposals.iter().all(|proposal| {
+                proposal
+                    .factor
+                    .as_ref()
+                    .is_none_or(|factor| Some(factor.nrows()) == dimension)
+            });
+        if !consistent {
+            return Err("Checkpoint chains are inconsistent".to_string());
+        }
+
+        Ok(Self {
+            samples,
+            current_vals,
+            rngs,
+            proposals,
+            accepted,
+            warmup_iteration,
+            iteration,
+        })
+    }
+
+    fn into_samples(self, iterations: usize, time: Duration) -> Samples {
+        let chains = self.samples;
+        let draws = iterations.saturating_mul(chains.len());
+        let mut mean_x = vec![0.0; chains[0][0].len()];
+
+        if draws > 0 {
+            for chain in &chains {
+                for sample in chain.iter().skip(1) {
+                    for (i, value) in sample.iter().enumerate() {
+                        mean_x[i] += *value;
+                    }
+                }
+            }
+
+            for value in &mut mean_x {
+                *value /= draws as f64;
+            }
+        } else if let Some(last) = chains.first().and_then(|chain| chain.last()).cloned() {
+            mean_x = last;
+        }
+
+        let acceptance_rates = self
+            .accepted
+            .iter()
+            .map(|&accepted| accepted as f64 / iterations.max(1) as f64)
+            .collect();
+        Samples::new(chains, mean_x, draws, time).with_acceptance_rates(acceptance_rates)
+    }
+}
+
+/// Negative log-posterior of one point per chain, in a single population call when
+/// `batched`.
+fn evaluate_chains(posterior: &LogPosterior<'_>, points: &[Vec<f64>], batched: bool) -> Vec<f64> {
+    if !batched {
+        return points
+            .iter()
+            .map(|point| -posterior.log_density(point))
+            .collect();
+    }
+
+    posterior
+        .log_density_population(points)
+        .into_iter()
+        .map(|log_density| -log_density)
+        .collect()
 }
 
 pub(super) fn evaluate(problem: &Problem, x: &[f64]) -> f64 {
@@ -309,10 +594,30 @@ pub(super) fn evaluate(problem: &Problem, x: &[f64]) -> f64 {
     }
 }
 
+/// Indices chosen by systematic resampling of normalised log-weights, with the comb
+/// offset `offset` in `[0, 1)`.
+pub(super) fn systematic_indices(log_weights: &[f64], offset: f64) -> Vec<usize> {
+    let count = log_weights.len();
+    let mut indices = Vec::with_capacity(count);
+    let mut cumulative = 0.0;
+    let mut index = 0;
+    for k in 0..count {
+        let target = (k as f64 + offset) / count as f64;
+        while index + 1 < count && cumulative + log_weights[index].exp() < target {
+            cumulative += log_weights[index].exp();
+            index += 1;
+        }
+        indices.push(index);
+    }
+    indices
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::problem::{BuilderParameterExt, ParameterSpec, ScalarProblemBuilder};
+    use crate::problem::{
+        BuilderParameterExt, ParameterSpec, Prior, ScalarProblemBuilder, VectorProblemBuilder,
+    };
 
     #[test]
     fn metropolis_hastings_produces_samples() {
@@ -331,7 +636,7 @@ mod tests {
             .with_step_size(0.3)
             .with_seed(42);
 
-        let samples = sampler.run(&problem, vec![0.0]);
+        let samples = sampler.run(&problem, vec![0.0]).unwrap();
 
         assert_eq!(samples.chains().len(), 4);
         for chain in samples.chains() {
@@ -342,5 +647,229 @@ mod tests {
         assert_eq!(mean.len(), 1);
         assert!((mean[0] - 1.0).abs() < 0.2);
         assert_eq!(samples.draws(), 4 * 600);
+
+        let rates = samples
+            .acceptance_rates()
+            .expect("acceptance rates recorded");
+        assert_eq!(rates.len(), 4);
+        assert!(rates.iter().all(|rate| *rate > 0.5 && *rate < 1.0));
+
+        // Small steps mix slowly, so the effective sample size is far below the draws.
+        let kept = samples.burn_in(100);
+        assert!(kept.rhat()[0] < 1.2);
+        assert!(kept.ess_bulk()[0] > 10.0 && kept.ess_bulk()[0] < 500.0);
+        assert!(kept.autocorrelation_time()[0] > 5.0);
+    }
+
+    #[test]
+    fn metropolis_hastings_resume_matches_uninterrupted_run() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| 0.5 * (x[0] * x[0] + x[1] * x[1]))
+            .with_parameter(ParameterSpec::new("x", 1.0, None))
+            .with_parameter(ParameterSpec::new("y", 1.0, None))
+            .build()
+            .expect("problem to build");
+        let path = std::env::temp_dir().join(format!(
+            "chronopt-mh-resume-{}.checkpoint",
+            std::process::id()
+        ));
+        let sampler = MetropolisHastings::new()
+            .with_num_chains(3)
+            .with_step_size(0.5)
+            .with_seed(8);
+
+        let expected = sampler
+            .clone()
+            .with_iterations(80)
+            .run(&problem, vec![1.0, -1.0])
+            .unwrap();
+        sampler
+            .clone()
+            .with_iterations(40)
+            .with_checkpoint(&path, 30)
+            .run(&problem, vec![1.0, -1.0])
+            .unwrap();
+        let resumed = sampler
+            .with_iterations(80)
+            .resume_from(&problem, &path)
+            .expect("checkpoint to resume");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.chains(), expected.chains());
+        assert_eq!(resumed.mean_x(), expected.mean_x());
+        assert_eq!(resumed.draws(), expected.draws());
+    }
+
+    #[test]
+    fn metropolis_hastings_fails_when_checkpoints_cannot_be_written() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| 0.5 * x[0] * x[0])
+            .with_parameter(ParameterSpec::new("x", 1.0, None))
+            .build()
+            .expect("problem to build");
+        let path = std::env::temp_dir()
+            .join("chronopt-missing-directory")
+            .join("mh.checkpoint");
+
+        let err = MetropolisHastings::new()
+            .with_iterations(40)
+            .with_seed(2)
+            .with_checkpoint(&path, 10)
+            .run(&problem, vec![1.0])
+            .unwrap_err();
+        assert!(err.contains("Failed to write checkpoint"), "{}", err);
+    }
+
+    #[test]
+    fn metropolis_hastings_targets_posterior_when_priors_are_attached() {
+        // A unit Gaussian likelihood centred at 1 with a N(3, 1) prior gives a N(2, 0.5)
+        // posterior; the second parameter is only constrained by its uniform prior.
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| 0.5 * (x[0] - 1.0).powi(2))
+            .with_parameter(
+                ParameterSpec::new("x", 1.0, None).with_prior(Prior::normal(3.0, 1.0).unwrap()),
+            )
+            .with_parameter(
+                ParameterSpec::new("y", 0.5, None).with_prior(Prior::uniform(0.0, 1.0).unwrap()),
+            )
+            .build()
+            .expect("problem to build");
+
+        let samples = MetropolisHastings::new()
+            .with_num_chains(4)
+            .with_iterations(3000)
+            .with_step_size(0.5)
+            .with_adaptation(500)
+            .with_seed(21)
+            .run(&problem, vec![1.0, 0.5])
+            .expect("sampler to run")
+            .burn_in(500);
+
+        let draws: Vec<&Vec<f64>> = samples.chains().iter().flatten().collect();
+        assert!(draws.iter().all(|draw| (0.0..=1.0).contains(&draw[1])));
+        let mean = samples.mean_x();
+        assert!((mean[0] - 2.0).abs() < 0.1, "posterior mean {mean:?}");
+        assert!((mean[1] - 0.5).abs() < 0.05, "posterior mean {mean:?}");
+        let variance = samples.covariance()[0][0];
+        assert!(
+            (variance - 0.5).abs() < 0.1,
+            "posterior variance {variance}"
+        );
+    }
+
+    #[test]
+    fn samplers_refuse_costs_that_are_not_likelihoods() {
+        let problem = VectorProblemBuilder::new()
+            .with_objective(|x: &[f64]| Ok(vec![x[0], 2.0 * x[0]]))
+            .with_data(vec![1.0, 2.0])
+            .with_parameter(ParameterSpec::new("x", 1.0, None))
+            .build()
+            .expect("problem to build");
+
+        let refused = |result: Result<Samples, String>| {
+            let err = result.expect_err("sum of squares is not a likelihood");
+            assert!(err.contains("not a negative log-likelihood"), "{err}");
+        };
+        refused(MetropolisHastings::new().run(&problem, vec![1.0]));
+        refused(HamiltonianMonteCarlo::new().run(&problem, vec![1.0]));
+        refused(EnsembleSampler::new().run(&problem, vec![1.0]));
+        refused(ParallelTempering::new().run(&problem, vec![1.0]));
+        refused(SequentialMonteCarlo::new().run(&problem, vec![1.0]));
+        refused(DynamicNestedSampler::new().run(&problem, vec![1.0]));
+    }
+
+    fn badly_scaled_gaussian(parallel: bool) -> Problem {
+        // Standard deviations of 1e-3 and 1e2 with correlation 0.9.
+        let (sx, sy, rho) = (1e-3, 1e2, 0.9);
+        ScalarProblemBuilder::new()
+            .with_objective(move |x: &[f64]| {
+                let (u, v) = (x[0] / sx, x[1] / sy);
+                0.5 * (u * u - 2.0 * rho * u * v + v * v) / (1.0 - rho * rho)
+            })
+            .with_parameter(ParameterSpec::new("x", 0.0, None))
+            .with_parameter(ParameterSpec::new("y", 0.0, None))
+            .with_config("parallel".to_string(), if parallel { 1.0 } else { 0.0 })
+            .build()
+            .expect("problem to build")
+    }
+
+    #[test]
+    fn adaptive_metropolis_learns_badly_scaled_target() {
+        for parallel in [false, true] {
+            let samples = MetropolisHastings::new()
+                .with_num_chains(2)
+                .with_iterations(4_000)
+                .with_step_size(1.0)
+                .with_adaptation(2_000)
+                .with_seed(3)
+                .run(&badly_scaled_gaussian(parallel), vec![0.0, 0.0])
+                .expect("sampling to succeed");
+
+            assert_eq!(samples.chains().len(), 2);
+            for chain in samples.chains() {
+                assert_eq!(chain.len(), 4_001);
+            }
+
+            let draws: Vec<&Vec<f64>> = samples.chains().iter().flatten().collect();
+            let n = draws.len() as f64;
+            let sd = |k: usize| (draws.iter().map(|d| d[k] * d[k]).sum::<f64>() / n).sqrt();
+            assert!((sd(0) / 1e-3 - 1.0).abs() < 0.25, "sd x = {}", sd(0));
+            assert!((sd(1) / 1e2 - 1.0).abs() < 0.25, "sd y = {}", sd(1));
+        }
+    }
+
+    #[test]
+    fn initial_proposal_covariance_is_validated() {
+        let problem = badly_scaled_gaussian(false);
+        let sampler = MetropolisHastings::new().with_iterations(10).with_seed(1);
+
+        assert!(sampler
+            .clone()
+            .with_proposal_covariance(vec![vec![1.0]])
+            .run(&problem, vec![0.0, 0.0])
+            .is_err());
+        assert!(sampler
+            .clone()
+            .with_proposal_covariance(vec![vec![1.0, 2.0], vec![2.0, 1.0]])
+            .run(&problem, vec![0.0, 0.0])
+            .is_err());
+
+        let samples = sampler
+            .with_proposal_covariance(vec![vec![1e-6, 0.09], vec![0.09, 1e4]])
+            .run(&problem, vec![0.0, 0.0])
+            .expect("covariance to be accepted");
+        assert_eq!(samples.draws(), 10);
+    }
+
+    #[test]
+    fn adaptive_metropolis_resume_during_warmup_matches_uninterrupted_run() {
+        let problem = badly_scaled_gaussian(false);
+        let path = std::env::temp_dir().join(format!(
+            "chronopt-am-resume-{}.checkpoint",
+            std::process::id()
+        ));
+        let sampler = MetropolisHastings::new()
+            .with_num_chains(2)
+            .with_adaptation(50)
+            .with_seed(5);
+
+        let expected = sampler
+            .clone()
+            .with_iterations(60)
+            .run(&problem, vec![0.0, 0.0])
+            .unwrap();
+        sampler
+            .clone()
+            .with_iterations(60)
+            .with_checkpoint(&path, 30)
+            .run(&problem, vec![0.0, 0.0])
+            .unwrap();
+        let resumed = sampler
+            .with_iterations(60)
+            .resume_from(&problem, &path)
+            .expect("checkpoint to resume");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.chains(), expected.chains());
     }
 }
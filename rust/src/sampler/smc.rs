@@ -0,0 +1,547 @@
+//! Adaptive-tempering Sequential Monte Carlo.
+//!
+//! A population of particles drawn from the prior is moved to the posterior through a
+//! sequence of tempered targets `prior(x) * likelihood(x)^beta` with `beta` rising from 0
+//! to 1. Each increment of `beta` is chosen by bisection so the reweighted population
+//! keeps a target effective sample size; the particles are then resampled and
+//! rejuvenated by random-walk Metropolis moves shaped by their covariance. The product
+//! of the mean incremental weights estimates the evidence (Del Moral, Doucet & Jasra,
+//! 2006). The likelihoods of a whole population are evaluated in a single
+//! [`Problem::evaluate_population_strict`] call.
+
+use super::{systematic_indices, Sampler, Samples};
+use crate::problem::{LogPosterior, Prior, Problem};
+use nalgebra::{DMatrix, DVector};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rand_distr::StandardNormal;
+use std::time::{Duration, Instant};
+
+/// Acceptance rate the rejuvenation step size is steered towards between stages.
+const TARGET_ACCEPTANCE: f64 = 0.234;
+/// Bisection iterations used to choose each tempering increment.
+const BISECTION_STEPS: usize = 60;
+/// Upper bound on the number of tempering stages before the run is abandoned.
+const MAX_STAGES: usize = 1_000;
+
+/// Sequential Monte Carlo sampler with adaptive tempering.
+#[derive(Clone, Debug)]
+pub struct SequentialMonteCarlo {
+    particles: usize,
+    target_ess: f64,
+    mcmc_steps: usize,
+    seed: Option<u64>,
+}
+
+impl SequentialMonteCarlo {
+    /// Create a sampler with 512 particles, a target effective sample size of half the
+    /// population and 10 rejuvenation moves per stage.
+    pub fn new() -> Self {
+        Self {
+            particles: 512,
+            target_ess: 0.5,
+            mcmc_steps: 10,
+            seed: None,
+        }
+    }
+
+    /// Size of the particle population.
+    pub fn with_particles(mut self, particles: usize) -> Self {
+        self.particles = particles.max(2);
+        self
+    }
+
+    /// Fraction of the population kept as effective sample size by each tempering
+    /// increment; larger values take more, smaller steps.
+    pub fn with_target_ess(mut self, fraction: f64) -> Self {
+        self.target_ess = fraction.clamp(0.05, 0.95);
+        self
+    }
+
+    /// Number of Metropolis moves applied to every particle after each resampling.
+    pub fn with_mcmc_steps(mut self, steps: usize) -> Self {
+        self.mcmc_steps = steps.max(1);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Move the particles from the prior to the posterior, failing if a parameter has
+    /// neither a prior nor bounds or no particle drawn from the prior has a finite
+    /// likelihood.
+    ///
+    /// Parameters without a prior are given a uniform prior over their bounds.
+    pub fn run_particles(&self, problem: &Problem) -> Result<ParticleSamples, String> {
+        let start_time = Instant::now();
+        let posterior = LogPosterior::new(problem)?;
+        let priors = particle_priors(problem)?;
+        let count = self.particles;
+
+        let mut rng: StdRng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        let positions: Vec<Vec<f64>> = (0..count)
+            .map(|_| priors.iter().map(|prior| prior.sample(&mut rng)).collect())
+            .collect();
+        let log_likelihoods = posterior.log_likelihood_population(&positions);
+        if !log_likelihoods.iter().any(|value| value.is_finite()) {
+            return Err(
+                "Log-likelihood is not finite at any particle drawn from the prior".to_string(),
+            );
+        }
+
+        let mut population = Population {
+            log_priors: positions.iter().map(|x| log_prior(&priors, x)).collect(),
+            positions,
+            log_likelihoods,
+            log_weights: vec![-(count as f64).ln(); count],
+            scale: 2.38 / (priors.len() as f64).sqrt(),
+        };
+
+        let mut beta = 0.0;
+        let mut log_evidence = 0.0;
+        let mut schedule = vec![beta];
+        let mut acceptance_rates = Vec::new();
+        while beta < 1.0 {
+            if schedule.len() > MAX_STAGES {
+                return Err(format!(
+                    "Tempering did not reach the posterior within {} stages",
+                    MAX_STAGES
+                ));
+            }
+            let increment = population.next_increment(1.0 - beta, self.target_ess);
+            log_evidence += population.reweight(increment);
+            beta = if beta + increment >= 1.0 - 1e-12 {
+                1.0
+            } else {
+                beta + increment
+            };
+            schedule.push(beta);
+
+            if beta < 1.0 {
+                population.resample(&mut rng);
+                let acceptance =
+                    population.rejuvenate(&posterior, &priors, beta, self.mcmc_steps, &mut rng);
+                acceptance_rates.push(acceptance);
+            }
+        }
+
+        Ok(population.into_particle_samples(
+            log_evidence,
+            schedule,
+            acceptance_rates,
+            start_time.elapsed(),
+        ))
+    }
+}
+
+impl Default for SequentialMonteCarlo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for SequentialMonteCarlo {
+    /// Final particles of [`SequentialMonteCarlo::run_particles`]. The particles start
+    /// from the prior, so `initial` is ignored.
+    fn run(&self, problem: &Problem, _initial: Vec<f64>) -> Result<Samples, String> {
+        self.run_particles(problem)
+            .map(|particles| particles.to_samples())
+    }
+}
+
+/// Weighted particle population at the end of a Sequential Monte Carlo run.
+#[derive(Clone, Debug)]
+pub struct ParticleSamples {
+    particles: Vec<Vec<f64>>,
+    log_weights: Vec<f64>,
+    log_likelihoods: Vec<f64>,
+    mean: Vec<f64>,
+    log_evidence: f64,
+    schedule: Vec<f64>,
+    acceptance_rates: Vec<f64>,
+    time: Duration,
+}
+
+impl ParticleSamples {
+    /// Posterior particle positions.
+    pub fn particles(&self) -> &[Vec<f64>] {
+        &self.particles
+    }
+
+    /// Normalised log-weight of each particle.
+    pub fn log_weights(&self) -> &[f64] {
+        &self.log_weights
+    }
+
+    /// Log-likelihood of each particle.
+    pub fn log_likelihoods(&self) -> &[f64] {
+        &self.log_likelihoods
+    }
+
+    /// Weighted posterior mean.
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// Number of particles.
+    pub fn draws(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Estimated log-evidence (log marginal likelihood).
+    pub fn log_evidence(&self) -> f64 {
+        self.log_evidence
+    }
+
+    /// Inverse temperatures visited by the run, from 0 (prior) to 1 (posterior).
+    pub fn schedule(&self) -> &[f64] {
+        &self.schedule
+    }
+
+    /// Acceptance rate of the rejuvenation moves at each intermediate stage.
+    pub fn acceptance_rates(&self) -> &[f64] {
+        &self.acceptance_rates
+    }
+
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Equal-weight view of the particles as a single chain, drawn by systematic
+    /// resampling with a fixed offset so the result is deterministic.
+    pub fn to_samples(&self) -> Samples {
+        let chain = systematic_indices(&self.log_weights, 0.5)
+            .into_iter()
+            .map(|index| self.particles[index].clone())
+            .collect::<Vec<_>>();
+        let draws = chain.len();
+        Samples::new(vec![chain], self.mean.clone(), draws, self.time)
+    }
+}
+
+/// Current particles with their prior and likelihood values.
+struct Population {
+    positions: Vec<Vec<f64>>,
+    log_priors: Vec<f64>,
+    log_likelihoods: Vec<f64>,
+    log_weights: Vec<f64>,
+    /// Multiplier of the particle covariance's Cholesky factor for rejuvenation moves.
+    scale: f64,
+}
+
+impl Population {
+    /// Largest increment of `beta`, up to `remaining`, whose reweighted population keeps
+    /// an effective sample size of at least `target` times the population size.
+    fn next_increment(&self, remaining: f64, target: f64) -> f64 {
+        let goal = target * self.positions.len() as f64;
+        if self.effective_sample_size(remaining) >= goal {
+            return remaining;
+        }
+        let (mut low, mut high) = (0.0, remaining);
+        for _ in 0..BISECTION_STEPS {
+            let mid = 0.5 * (low + high);
+            if self.effective_sample_size(mid) >= goal {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        // `high` is never zero, so every stage makes progress.
+        high
+    }
+
+    fn effective_sample_size(&self, increment: f64) -> f64 {
+        let log_weights = self.incremental_weights(increment);
+        let doubled: Vec<f64> = log_weights.iter().map(|w| 2.0 * w).collect();
+        (2.0 * log_sum_exp(&log_weights) - log_sum_exp(&doubled)).exp()
+    }
+
+    fn incremental_weights(&self, increment: f64) -> Vec<f64> {
+        self.log_weights
+            .iter()
+            .zip(&self.log_likelihoods)
+            .map(|(log_weight, log_likelihood)| log_weight + increment * log_likelihood)
+            .collect()
+    }
+
+    /// Raise `beta` by `increment`, normalising the weights and returning the log of the
+    /// evidence ratio between the two tempered targets.
+    fn reweight(&mut self, increment: f64) -> f64 {
+        let log_weights = self.incremental_weights(increment);
+        let log_ratio = log_sum_exp(&log_weights);
+        self.log_weights = log_weights.iter().map(|w| w - log_ratio).collect();
+        log_ratio
+    }
+
+    /// Systematic resampling to equal weights.
+    fn resample(&mut self, rng: &mut StdRng) {
+        let indices = systematic_indices(&self.log_weights, rng.random());
+        self.positions = indices.iter().map(|&i| self.positions[i].clone()).collect();
+        self.log_priors = indices.iter().map(|&i| self.log_priors[i]).collect();
+        self.log_likelihoods = indices.iter().map(|&i| self.log_likelihoods[i]).collect();
+        self.log_weights = vec![-(indices.len() as f64).ln(); indices.len()];
+    }
+
+    /// Apply `steps` random-walk Metropolis moves targeting the tempered posterior at
+    /// `beta`, returning the acceptance rate and steering the step scale towards
+    /// [`TARGET_ACCEPTANCE`] for the next stage.
+    fn rejuvenate(
+        &mut self,
+        posterior: &LogPosterior<'_>,
+        priors: &[Prior],
+        beta: f64,
+        steps: usize,
+        rng: &mut StdRng,
+    ) -> f64 {
+        let factor = self.covariance_factor();
+        let dimension = priors.len();
+        let mut accepted = 0usize;
+
+        for _ in 0..steps {
+            let proposals: Vec<Vec<f64>> = self
+                .positions
+                .iter()
+                .map(|position| {
+                    let noise = DVector::from_fn(dimension, |_, _| rng.sample(StandardNormal));
+                    let step = &factor * noise;
+                    position
+                        .iter()
+                        .zip(step.iter())
+                        .map(|(x, dx)| x + self.scale * dx)
+                        .collect()
+                })
+                .collect();
+            let proposal_priors: Vec<f64> =
+                proposals.iter().map(|x| log_prior(priors, x)).collect();
+
+            // The likelihood is only evaluated inside the prior's support.
+            let supported: Vec<usize> = (0..proposals.len())
+                .filter(|&i| proposal_priors[i].is_finite())
+                .collect();
+            let batch: Vec<Vec<f64>> = supported.iter().map(|&i| proposals[i].clone()).collect();
+            let batch_likelihoods = posterior.log_likelihood_population(&batch);
+
+            for (&i, log_likelihood) in supported.iter().zip(batch_likelihoods) {
+                let log_accept = (proposal_priors[i] + beta * log_likelihood)
+                    - (self.log_priors[i] + beta * self.log_likelihoods[i]);
+                if log_accept.is_nan() || log_accept == f64::NEG_INFINITY {
+                    continue;
+                }
+                if log_accept >= 0.0 || rng.random::<f64>().ln() < log_accept {
+                    self.positions[i] = proposals[i].clone();
+                    self.log_priors[i] = proposal_priors[i];
+                    self.log_likelihoods[i] = log_likelihood;
+                    accepted += 1;
+                }
+            }
+        }
+
+        let acceptance = accepted as f64 / (steps * self.positions.len()) as f64;
+        self.scale *= (acceptance - TARGET_ACCEPTANCE).exp();
+        acceptance
+    }
+
+    /// Cholesky factor of the (equal-weight) particle covariance, falling back to its
+    /// diagonal when the population has collapsed onto a subspace.
+    fn covariance_factor(&self) -> DMatrix<f64> {
+        let dimension = self.positions[0].len();
+        let count = self.positions.len() as f64;
+        let mut mean = DVector::zeros(dimension);
+        for position in &self.positions {
+            mean += DVector::from_column_slice(position);
+        }
+        mean /= count;
+
+        let mut covariance = DMatrix::zeros(dimension, dimension);
+        for position in &self.positions {
+            let centred = DVector::from_column_slice(position) - &mean;
+            covariance += &centred * centred.transpose();
+        }
+        covariance /= (count - 1.0).max(1.0);
+
+        if let Some(cholesky) = covariance.clone().cholesky() {
+            return cholesky.l();
+        }
+        DMatrix::from_diagonal(&covariance.diagonal().map(|v| v.max(1e-12).sqrt()))
+    }
+
+    fn into_particle_samples(
+        self,
+        log_evidence: f64,
+        schedule: Vec<f64>,
+        acceptance_rates: Vec<f64>,
+        time: Duration,
+    ) -> ParticleSamples {
+        let mut mean = vec![0.0; self.positions[0].len()];
+        for (position, log_weight) in self.positions.iter().zip(&self.log_weights) {
+            let weight = log_weight.exp();
+            for (m, x) in mean.iter_mut().zip(position) {
+                *m += weight * x;
+            }
+        }
+
+        ParticleSamples {
+            particles: self.positions,
+            log_weights: self.log_weights,
+            log_likelihoods: self.log_likelihoods,
+            mean,
+            log_evidence,
+            schedule,
+            acceptance_rates,
+            time,
+        }
+    }
+}
+
+/// Prior of every parameter: its own prior, else a uniform prior over its bounds.
+fn particle_priors(problem: &Problem) -> Result<Vec<Prior>, String> {
+    let specs = problem.parameter_specs().specs();
+    if specs.is_empty() {
+        return Err("Sequential Monte Carlo needs parameters with priors or bounds".to_string());
+    }
+    specs
+        .iter()
+        .map(|spec| {
+            spec.prior
+                .or_else(|| {
+                    let (lo, hi) = spec.bounds?;
+                    Prior::uniform(lo.min(hi), lo.max(hi)).ok()
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "Parameter '{}' needs a prior or bounds for Sequential Monte Carlo",
+                        spec.name
+                    )
+                })
+        })
+        .collect()
+}
+
+fn log_prior(priors: &[Prior], x: &[f64]) -> f64 {
+    priors
+        .iter()
+        .zip(x)
+        .map(|(prior, &value)| prior.log_density(value))
+        .sum()
+}
+
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if !max.is_finite() {
+        return max;
+    }
+    max + values.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{BuilderParameterExt, ParameterSpec, ScalarProblemBuilder};
+
+    #[test]
+    fn conjugate_gaussian_evidence_and_posterior() {
+        // Unit Gaussian likelihood for an observation at 1 under a N(3, 1) prior: the
+        // evidence is N(1; 3, sqrt(2)) and the posterior is N(2, 0.5).
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| {
+                0.5 * (x[0] - 1.0).powi(2) + 0.5 * (2.0 * std::f64::consts::PI).ln()
+            })
+            .with_parameter(
+                ParameterSpec::new("x", 0.0, None).with_prior(Prior::normal(3.0, 1.0).unwrap()),
+            )
+            .build()
+            .expect("problem to build");
+
+        let particles = SequentialMonteCarlo::new()
+            .with_seed(4)
+            .run_particles(&problem)
+            .expect("sampling to succeed");
+
+        let expected = -1.0 - 0.5 * (4.0 * std::f64::consts::PI).ln();
+        assert!(
+            (particles.log_evidence() - expected).abs() < 0.1,
+            "log evidence {} vs {expected}",
+            particles.log_evidence()
+        );
+        assert!(
+            (particles.mean()[0] - 2.0).abs() < 0.1,
+            "{:?}",
+            particles.mean()
+        );
+
+        let schedule = particles.schedule();
+        assert_eq!(schedule.first(), Some(&0.0));
+        assert_eq!(schedule.last(), Some(&1.0));
+        assert!(schedule.windows(2).all(|pair| pair[1] > pair[0]));
+        assert_eq!(particles.acceptance_rates().len(), schedule.len() - 2);
+
+        let total: f64 = particles.log_weights().iter().map(|w| w.exp()).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        let samples = particles.to_samples();
+        assert_eq!(samples.draws(), 512);
+        let variance = samples.covariance()[0][0];
+        assert!(
+            (variance - 0.5).abs() < 0.1,
+            "posterior variance {variance}"
+        );
+    }
+
+    #[test]
+    fn bounds_act_as_uniform_priors() {
+        // A standard bivariate Gaussian likelihood inside a 20 x 20 box has evidence
+        // 1 / 400.
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| {
+                0.5 * (x[0] * x[0] + x[1] * x[1]) + (2.0 * std::f64::consts::PI).ln()
+            })
+            .with_parameter(ParameterSpec::new("x", 0.0, Some((-10.0, 10.0))))
+            .with_parameter(ParameterSpec::new("y", 0.0, Some((-10.0, 10.0))))
+            .build()
+            .expect("problem to build");
+
+        let particles = SequentialMonteCarlo::new()
+            .with_particles(256)
+            .with_seed(9)
+            .run_particles(&problem)
+            .expect("sampling to succeed");
+
+        let expected = -(400.0f64).ln();
+        assert!(
+            (particles.log_evidence() - expected).abs() < 0.15,
+            "log evidence {} vs {expected}",
+            particles.log_evidence()
+        );
+        assert!(particles
+            .particles()
+            .iter()
+            .flatten()
+            .all(|x| (-10.0..=10.0).contains(x)));
+    }
+
+    #[test]
+    fn parameters_need_a_prior_or_bounds() {
+        let problem = ScalarProblemBuilder::new()
+            .with_objective(|x: &[f64]| 0.5 * x[0] * x[0])
+            .with_parameter(ParameterSpec::new("x", 0.0, None))
+            .build()
+            .expect("problem to build");
+
+        let error = SequentialMonteCarlo::new()
+            .run_particles(&problem)
+            .unwrap_err();
+        assert!(error.contains("'x'"), "{error}");
+    }
+
+    #[test]
+    fn systematic_resampling_follows_weights() {
+        let log_weights = [0.5f64.ln(), f64::NEG_INFINITY, 0.25f64.ln(), 0.25f64.ln()];
+        assert_eq!(systematic_indices(&log_weights, 0.5), vec![0, 0, 2, 3]);
+    }
+}
@@ -0,0 +1,548 @@
+//! Parallel tempering (replica exchange) Metropolis sampling.
+//!
+//! One random-walk replica runs at each temperature of a ladder `1 = T_0 < ... < T_max`,
+//! targeting `prior(x) * likelihood(x)^(1 / T)`. Hot replicas see a flattened likelihood
+//! and cross between modes freely; swaps between neighbouring temperatures hand those
+//! positions down to the cold replica, whose draws are the posterior samples. The
+//! proposals of all replicas are evaluated in a single
+//! [`Problem::evaluate_population_strict`] call per iteration.
+//!
+//! During warmup each replica tunes its step size towards the target acceptance rate and
+//! the spacing of the ladder is adapted to equalise the swap acceptance rates between
+//! neighbours (Vousden, Farr & Mandel, 2016). The mean log-likelihood recorded at each
+//! temperature gives a thermodynamic-integration estimate of the evidence.
+
+use super::{posterior_and_start, Sampler, Samples};
+use crate::problem::{LogPosterior, Problem};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rand_distr::StandardNormal;
+use std::time::{Duration, Instant};
+
+/// Iteration scale over which ladder adaptation decays.
+const LADDER_ADAPTATION_LAG: f64 = 10_000.0;
+/// Number of iterations over which a swap-rate imbalance moves the ladder spacing.
+const LADDER_ADAPTATION_TIME: f64 = 100.0;
+
+/// Replica-exchange sampler returning the cold chain.
+#[derive(Clone, Debug)]
+pub struct ParallelTempering {
+    temperatures: Option<Vec<f64>>,
+    num_temperatures: usize,
+    max_temperature: f64,
+    iterations: usize,
+    warmup: usize,
+    step_size: f64,
+    target_acceptance: f64,
+    adapt_ladder: bool,
+    seed: Option<u64>,
+}
+
+impl ParallelTempering {
+    /// Create a sampler with a geometric ladder of 8 temperatures up to 100.
+    pub fn new() -> Self {
+        Self {
+            temperatures: None,
+            num_temperatures: 8,
+            max_temperature: 100.0,
+            iterations: 1_000,
+            warmup: 500,
+            step_size: 0.1,
+            target_acceptance: 0.234,
+            adapt_ladder: true,
+            seed: None,
+        }
+    }
+
+    /// Explicit temperature ladder; it must start at 1 and increase strictly.
+    pub fn with_temperatures(mut self, temperatures: Vec<f64>) -> Self {
+        self.temperatures = Some(temperatures);
+        self
+    }
+
+    /// Number of temperatures in the default geometric ladder.
+    pub fn with_num_temperatures(mut self, num_temperatures: usize) -> Self {
+        self.num_temperatures = num_temperatures.max(2);
+        self
+    }
+
+    /// Hottest temperature of the default geometric ladder.
+    pub fn with_max_temperature(mut self, max_temperature: f64) -> Self {
+        self.max_temperature = max_temperature;
+        self
+    }
+
+    /// Number of recorded iterations of the cold chain.
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Number of discarded iterations used to tune step sizes and the ladder.
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Random-walk step size of the cold replica; hotter replicas start from
+    /// `step_size * sqrt(T)`.
+    pub fn with_step_size(mut self, step_size: f64) -> Self {
+        self.step_size = step_size.abs().max(f64::MIN_POSITIVE);
+        self
+    }
+
+    /// Acceptance rate targeted by step-size adaptation during warmup.
+    pub fn with_target_acceptance(mut self, target: f64) -> Self {
+        self.target_acceptance = target.clamp(0.01, 0.99);
+        self
+    }
+
+    /// Whether warmup adapts the spacing of the interior temperatures. The coldest and
+    /// hottest temperatures are never moved.
+    pub fn with_ladder_adaptation(mut self, adapt: bool) -> Self {
+        self.adapt_ladder = adapt;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sample every rung of the ladder, failing if the ladder is invalid or the starting
+    /// point has no finite log-posterior density.
+    pub fn run_tempered(
+        &self,
+        problem: &Problem,
+        initial: Vec<f64>,
+    ) -> Result<TemperedSamples, String> {
+        let start_time = Instant::now();
+        let (posterior, start) = posterior_and_start(problem, initial)?;
+        let temperatures = self.ladder()?;
+
+        let log_prior = posterior.log_prior(&start);
+        let log_likelihood = posterior.log_likelihood(&start);
+        if !(log_prior + log_likelihood).is_finite() {
+            return Err("Log-posterior density is not finite at the initial position".to_string());
+        }
+
+        let mut rng: StdRng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        let mut ladder = Ladder {
+            replicas: temperatures
+                .iter()
+                .map(|temperature| Replica {
+                    position: start.clone(),
+                    log_prior,
+                    log_likelihood,
+                    step_size: self.step_size * temperature.sqrt(),
+                    accepted: 0,
+                })
+                .collect(),
+            betas: temperatures.iter().map(|t| 1.0 / t).collect(),
+            swaps_accepted: vec![0; temperatures.len() - 1],
+            log_likelihood_sums: vec![0.0; temperatures.len()],
+            chain: Vec::with_capacity(self.iterations),
+        };
+
+        for iteration in 0..self.warmup {
+            ladder.step(&posterior, &mut rng);
+            let accepted = ladder.swap(&mut rng);
+            ladder.adapt_step_sizes(iteration, self.target_acceptance);
+            if self.adapt_ladder {
+                ladder.adapt_temperatures(iteration, &accepted);
+            }
+        }
+        ladder.reset_counts();
+
+        for _ in 0..self.iterations {
+            ladder.step(&posterior, &mut rng);
+            let accepted = ladder.swap(&mut rng);
+            for (count, swapped) in ladder.swaps_accepted.iter_mut().zip(accepted) {
+                *count += usize::from(swapped);
+            }
+            ladder.record();
+        }
+
+        Ok(ladder.into_tempered_samples(start, self.iterations, start_time.elapsed()))
+    }
+
+    /// Validated temperature ladder, coldest first.
+    fn ladder(&self) -> Result<Vec<f64>, String> {
+        let temperatures = match &self.temperatures {
+            Some(temperatures) => temperatures.clone(),
+            None => {
+                if !(self.max_temperature.is_finite() && self.max_temperature > 1.0) {
+                    return Err(format!(
+                        "Maximum temperature must be finite and above 1, got {}",
+                        self.max_temperature
+                    ));
+                }
+                let steps = (self.num_temperatures - 1) as f64;
+                (0..self.num_temperatures)
+                    .map(|k| self.max_temperature.powf(k as f64 / steps))
+                    .collect()
+            }
+        };
+
+        if temperatures.len() < 2 {
+            return Err("Parallel tempering needs at least two temperatures".to_string());
+        }
+        if temperatures[0] != 1.0 {
+            return Err(format!(
+                "The temperature ladder must start at 1, got {}",
+                temperatures[0]
+            ));
+        }
+        if temperatures
+            .windows(2)
+            .any(|pair| !(pair[1] > pair[0] && pair[1].is_finite()))
+        {
+            return Err("Temperatures must be finite and strictly increasing".to_string());
+        }
+        Ok(temperatures)
+    }
+}
+
+impl Default for ParallelTempering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for ParallelTempering {
+    /// Cold-chain samples of [`ParallelTempering::run_tempered`].
+    fn run(&self, problem: &Problem, initial: Vec<f64>) -> Result<Samples, String> {
+        self.run_tempered(problem, initial)
+            .map(TemperedSamples::into_samples)
+    }
+}
+
+/// Cold-chain samples of a parallel-tempering run with its ladder statistics.
+#[derive(Clone, Debug)]
+pub struct TemperedSamples {
+    samples: Samples,
+    temperatures: Vec<f64>,
+    swap_acceptance_rates: Vec<f64>,
+    mean_log_likelihoods: Vec<f64>,
+    log_evidence: f64,
+}
+
+impl TemperedSamples {
+    /// Draws of the cold (`T = 1`) replica.
+    pub fn samples(&self) -> &Samples {
+        &self.samples
+    }
+
+    pub fn into_samples(self) -> Samples {
+        self.samples
+    }
+
+    /// Temperature ladder after warmup, coldest first.
+    pub fn temperatures(&self) -> &[f64] {
+        &self.temperatures
+    }
+
+    /// Fraction of accepted swaps between each pair of neighbouring temperatures.
+    pub fn swap_acceptance_rates(&self) -> &[f64] {
+        &self.swap_acceptance_rates
+    }
+
+    /// Mean log-likelihood of the recorded draws at each temperature.
+    pub fn mean_log_likelihoods(&self) -> &[f64] {
+        &self.mean_log_likelihoods
+    }
+
+    /// Thermodynamic-integration estimate of the log-evidence,
+    /// `int_0^1 E_beta[log L] d beta` with `beta = 1 / T`.
+    ///
+    /// The integral is taken with the trapezoidal rule over the ladder, holding the
+    /// integrand at its hottest value below `1 / T_max`. It is only meaningful when every
+    /// parameter has a proper prior, and its accuracy depends on the ladder resolving
+    /// the steep low-`beta` end of the curve.
+    pub fn log_evidence(&self) -> f64 {
+        self.log_evidence
+    }
+}
+
+/// State of one replica at a fixed rung of the ladder.
+struct Replica {
+    position: Vec<f64>,
+    log_prior: f64,
+    log_likelihood: f64,
+    step_size: f64,
+    accepted: usize,
+}
+
+/// Replicas ordered from coldest to hottest with their inverse temperatures.
+struct Ladder {
+    replicas: Vec<Replica>,
+    betas: Vec<f64>,
+    swaps_accepted: Vec<usize>,
+    log_likelihood_sums: Vec<f64>,
+    chain: Vec<Vec<f64>>,
+}
+
+impl Ladder {
+    /// Propose a random-walk move for every replica and accept each against its
+    /// tempered target.
+    fn step(&mut self, posterior: &LogPosterior<'_>, rng: &mut StdRng) {
+        let proposals: Vec<Vec<f64>> = self
+            .replicas
+            .iter()
+            .map(|replica| {
+                replica
+                    .position
+                    .iter()
+                    .map(|x| x + replica.step_size * rng.sample::<f64, _>(StandardNormal))
+                    .collect()
+            })
+            .collect();
+        let log_likelihoods = posterior.log_likelihood_population(&proposals);
+
+        for ((replica, beta), (proposal, log_likelihood)) in self
+            .replicas
+            .iter_mut()
+            .zip(&self.betas)
+            .zip(proposals.into_iter().zip(log_likelihoods))
+        {
+            let log_prior = posterior.log_prior(&proposal);
+            let log_accept = (log_prior + beta * log_likelihood)
+                - (replica.log_prior + beta * replica.log_likelihood);
+            if log_accept.is_nan() || log_accept == f64::NEG_INFINITY {
+                continue;
+            }
+            if log_accept >= 0.0 || rng.random::<f64>().ln() < log_accept {
+                replica.position = proposal;
+                replica.log_prior = log_prior;
+                replica.log_likelihood = log_likelihood;
+                replica.accepted += 1;
+            }
+        }
+    }
+
+    /// Offer swaps between neighbouring temperatures from the hottest pair down,
+    /// returning which pairs swapped.
+    fn swap(&mut self, rng: &mut StdRng) -> Vec<bool> {
+        let mut accepted = vec![false; self.betas.len() - 1];
+        for k in (0..self.betas.len() - 1).rev() {
+            let log_accept = (self.betas[k] - self.betas[k + 1])
+                * (self.replicas[k + 1].log_likelihood - self.replicas[k].log_likelihood);
+            if log_accept >= 0.0 || rng.random::<f64>().ln() < log_accept {
+                let (cold, hot) = self.replicas.split_at_mut(k + 1);
+                let (cold, hot) = (&mut cold[k], &mut hot[0]);
+                std::mem::swap(&mut cold.position, &mut hot.position);
+                std::mem::swap(&mut cold.log_prior, &mut hot.log_prior);
+                std::mem::swap(&mut cold.log_likelihood, &mut hot.log_likelihood);
+                accepted[k] = true;
+            }
+        }
+        accepted
+    }
+
+    /// Robbins-Monro update of each replica's step size from its running acceptance rate.
+    fn adapt_step_sizes(&mut self, iteration: usize, target: f64) {
+        let rate = 1.0 / (iteration as f64 + 1.0).powf(0.6);
+        for replica in &mut self.replicas {
+            let acceptance = replica.accepted as f64 / (iteration as f64 + 1.0);
+            replica.step_size *= (rate * (acceptance - target)).exp();
+        }
+    }
+
+    /// Widen the gaps whose swaps are accepted more often than the next hotter gap's and
+    /// narrow the others, keeping the coldest and hottest temperatures fixed.
+    fn adapt_temperatures(&mut self, iteration: usize, accepted: &[bool]) {
+        if self.betas.len() < 3 {
+            return;
+        }
+        let kappa = LADDER_ADAPTATION_LAG
+            / (iteration as f64 + LADDER_ADAPTATION_LAG)
+            / LADDER_ADAPTATION_TIME;
+        let temperatures: Vec<f64> = self.betas.iter().map(|beta| 1.0 / beta).collect();
+        let span = temperatures[temperatures.len() - 1] - temperatures[0];
+
+        let indicator = |swapped: bool| if swapped { 1.0 } else { 0.0 };
+        let mut gaps: Vec<f64> = temperatures.windows(2).map(|t| t[1] - t[0]).collect();
+        for (gap, pair) in gaps.iter_mut().zip(accepted.windows(2)) {
+            *gap *= (kappa * (indicator(pair[0]) - indicator(pair[1]))).exp();
+        }
+        let total: f64 = gaps.iter().sum();
+
+        let mut temperature = temperatures[0];
+        let interior = self.betas.len() - 2;
+        for (beta, gap) in self.betas.iter_mut().skip(1).take(interior).zip(&gaps) {
+            temperature += gap * span / total;
+            *beta = 1.0 / temperature;
+        }
+    }
+
+    fn reset_counts(&mut self) {
+        for replica in &mut self.replicas {
+            replica.accepted = 0;
+        }
+    }
+
+    fn record(&mut self) {
+        self.chain.push(self.replicas[0].position.clone());
+        for (sum, replica) in self.log_likelihood_sums.iter_mut().zip(&self.replicas) {
+            *sum += replica.log_likelihood;
+        }
+    }
+
+    fn into_tempered_samples(
+        self,
+        start: Vec<f64>,
+        iterations: usize,
+        time: Duration,
+    ) -> TemperedSamples {
+        let draws = self.chain.len();
+        let mean_x = if draws > 0 {
+            let mut mean = vec![0.0; start.len()];
+            for sample in &self.chain {
+                for (m, value) in mean.iter_mut().zip(sample) {
+                    *m += value;
+                }
+            }
+            mean.iter_mut().for_each(|m| *m /= draws as f64);
+            mean
+        } else {
+            start
+        };
+
+        let per_iteration = |count: f64| count / iterations.max(1) as f64;
+        let cold_acceptance = per_iteration(self.replicas[0].accepted as f64);
+        let mean_log_likelihoods: Vec<f64> = if iterations > 0 {
+            self.log_likelihood_sums
+                .iter()
+                .map(|&sum| per_iteration(sum))
+                .collect()
+        } else {
+            vec![f64::NAN; self.betas.len()]
+        };
+        let log_evidence = thermodynamic_integral(&self.betas, &mean_log_likelihoods);
+
+        TemperedSamples {
+            samples: Samples::new(vec![self.chain], mean_x, draws, time)
+                .with_acceptance_rates(vec![cold_acceptance]),
+            temperatures: self.betas.iter().map(|beta| 1.0 / beta).collect(),
+            swap_acceptance_rates: self
+                .swaps_accepted
+                .iter()
+                .map(|&count| per_iteration(count as f64))
+                .collect(),
+            mean_log_likelihoods,
+            log_evidence,
+        }
+    }
+}
+
+/// Trapezoidal `int_0^1 E_beta[log L] d beta` over inverse temperatures ordered from 1
+/// down, extending the hottest value to `beta = 0`.
+fn thermodynamic_integral(betas: &[f64], mean_log_likelihoods: &[f64]) -> f64 {
+    let hottest = betas.len() - 1;
+    let tail = betas[hottest] * mean_log_likelihoods[hottest];
+    betas
+        .windows(2)
+        .zip(mean_log_likelihoods.windows(2))
+        .map(|(beta, value)| 0.5 * (beta[0] - beta[1]) * (value[0] + value[1]))
+        .sum::<f64>()
+        + tail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{BuilderParameterExt, ParameterSpec, Prior, ScalarProblemBuilder};
+
+    /// Equal mixture of N(-4, 0.5^2) and N(4, 0.5^2) under a Uniform(-10, 10) prior, so
+    /// the evidence is 1 / 20.
+    fn bimodal() -> Problem {
+        let sd: f64 = 0.5;
+        ScalarProblemBuilder::new()
+            .with_objective(move |x: &[f64]| {
+                let density = |mu: f64| {
+                    (-0.5 * ((x[0] - mu) / sd).powi(2)).exp()
+                        / (sd * (2.0 * std::f64::consts::PI).sqrt())
+                };
+                -(0.5 * density(-4.0) + 0.5 * density(4.0)).ln()
+            })
+            .with_parameter(
+                ParameterSpec::new("x", -4.0, None)
+                    .with_prior(Prior::uniform(-10.0, 10.0).unwrap()),
+            )
+            .build()
+            .expect("problem to build")
+    }
+
+    #[test]
+    fn cold_chain_visits_both_modes_and_evidence_matches() {
+        let tempered = ParallelTempering::new()
+            .with_num_temperatures(12)
+            .with_iterations(6_000)
+            .with_warmup(1_000)
+            .with_step_size(0.5)
+            .with_seed(7)
+            .run_tempered(&bimodal(), vec![-4.0])
+            .expect("sampling to succeed");
+
+        let chain = &tempered.samples().chains()[0];
+        assert_eq!(chain.len(), 6_000);
+        let upper = chain.iter().filter(|draw| draw[0] > 0.0).count() as f64 / 6_000.0;
+        assert!((upper - 0.5).abs() < 0.15, "fraction in upper mode {upper}");
+
+        assert_eq!(tempered.swap_acceptance_rates().len(), 11);
+        assert!(tempered
+            .swap_acceptance_rates()
+            .iter()
+            .all(|&rate| rate > 0.05 && rate < 1.0));
+
+        let temperatures = tempered.temperatures();
+        assert_eq!(temperatures[0], 1.0);
+        assert_eq!(temperatures[11], 100.0);
+        assert!(temperatures.windows(2).all(|pair| pair[1] > pair[0]));
+
+        let expected = -(20.0f64).ln();
+        assert!(
+            (tempered.log_evidence() - expected).abs() < 0.3,
+            "log evidence {} vs {expected}",
+            tempered.log_evidence()
+        );
+    }
+
+    #[test]
+    fn ladder_is_validated() {
+        let problem = bimodal();
+        for temperatures in [vec![1.0], vec![2.0, 4.0], vec![1.0, 3.0, 3.0]] {
+            assert!(ParallelTempering::new()
+                .with_temperatures(temperatures)
+                .run_tempered(&problem, vec![-4.0])
+                .is_err());
+        }
+        assert!(ParallelTempering::new()
+            .with_max_temperature(f64::INFINITY)
+            .run_tempered(&problem, vec![-4.0])
+            .is_err());
+        assert!(ParallelTempering::new()
+            .run_tempered(&problem, vec![20.0])
+            .is_err());
+    }
+
+    #[test]
+    fn fixed_ladder_is_kept_and_runs_are_reproducible() {
+        let sampler = ParallelTempering::new()
+            .with_temperatures(vec![1.0, 2.0, 5.0])
+            .with_ladder_adaptation(false)
+            .with_iterations(200)
+            .with_warmup(100)
+            .with_seed(3);
+
+        let first = sampler.run_tempered(&bimodal(), vec![-4.0]).unwrap();
+        let second = sampler.run_tempered(&bimodal(), vec![-4.0]).unwrap();
+        assert_eq!(first.samples().chains(), second.samples().chains());
+        assert_eq!(first.temperatures(), &[1.0, 2.0, 5.0]);
+        assert_eq!(first.mean_log_likelihoods().len(), 3);
+    }
+}
@@ -97,3 +97,305 @@ F_i { a * y }
         "explicit optimiser should reduce cost"
     );
 }
+
+fn logistic_data(r: f64, k: f64, y0: f64, t_span: &[f64]) -> DMatrix<f64> {
+    let mut data = Vec::with_capacity(t_span.len() * 2);
+    for &t in t_span {
+        data.push(t);
+        data.push(k / (1.0 + (k / y0 - 1.0) * (-r * t).exp()));
+    }
+    DMatrix::from_row_slice(t_span.len(), 2, &data)
+}
+
+#[test]
+fn diffsol_builder_fits_multiple_experiments_jointly() {
+    let dsl = r#"
+in = [r, k, s]
+r { 1 }
+k { 1 }
+s { 1 }
+u_i { y = 0.1 }
+F_i { s * r * y * (1 - y / k) }
+"#;
+    let (r, k) = (1.2_f64, 2.0_f64);
+    let fast_t: Vec<f64> = (0..15).map(|i| i as f64 * 0.2).collect();
+    let slow_t: Vec<f64> = (0..10).map(|i| i as f64 * 0.5).collect();
+
+    let fast = DiffsolExperiment::new(logistic_data(r, k, 0.1, &fast_t)).with_input("s", 1.0);
+    let slow = DiffsolExperiment::new(logistic_data(0.5 * r, k, 0.4, &slow_t))
+        .with_input("s", 0.5)
+        .with_initial_condition("y", 0.4);
+
+    let builder = DiffsolProblemBuilder::new()
+        .with_diffsl(dsl.to_string())
+        .with_parameter(ParameterSpec::new("r", 0.8, Some((0.1, 5.0))))
+        .with_parameter(ParameterSpec::new("k", 1.5, Some((0.5, 5.0))))
+        .with_rtol(1e-8)
+        .with_atol(1e-10);
+    let problem = builder
+        .clone()
+        .with_experiment(fast.clone())
+        .with_experiment(slow.clone())
+        .build()
+        .expect("joint problem should build");
+
+    // Fixing `s` in every experiment leaves only r and k in the parameter vector.
+    let truth = [r, k];
+    assert!(problem.evaluate(&truth).unwrap() < 1e-10);
+    assert_eq!(
+        problem.residuals(&truth).unwrap().len(),
+        fast_t.len() + slow_t.len()
+    );
+
+    // The joint cost is the sum of the per-experiment costs.
+    let x = [0.9, 1.7];
+    let separate: f64 = [fast, slow]
+        .into_iter()
+        .map(|experiment| {
+            builder
+                .clone()
+                .with_experiment(experiment)
+                .build()
+                .unwrap()
+                .evaluate(&x)
+                .unwrap()
+        })
+        .sum();
+    let joint = problem.evaluate(&x).unwrap();
+    assert!((joint - separate).abs() < 1e-10 * separate.max(1.0));
+
+    let (cost, grad) = problem.evaluate_with_gradient(&x).unwrap();
+    let grad = grad.expect("diffsol problems provide gradients");
+    assert!((cost - joint).abs() < 1e-8);
+    let eps = 1e-6;
+    for i in 0..x.len() {
+        let (mut plus, mut minus) = (x, x);
+        plus[i] += eps;
+        minus[i] -= eps;
+        let fd =
+            (problem.evaluate(&plus).unwrap() - problem.evaluate(&minus).unwrap()) / (2.0 * eps);
+        assert!(
+            (fd - grad[i]).abs() < 1e-4 * fd.abs().max(1.0),
+            "gradient mismatch for parameter {}: fd={} grad={}",
+            i,
+            fd,
+            grad[i]
+        );
+    }
+
+    let result = LevenbergMarquardt::new().run(&problem, x.to_vec());
+    assert!(result.success, "{}", result.message);
+    assert!((result.x[0] - r).abs() < 1e-3, "r = {}", result.x[0]);
+    assert!((result.x[1] - k).abs() < 1e-3, "k = {}", result.x[1]);
+
+    for experiment in [
+        DiffsolExperiment::new(logistic_data(r, k, 0.1, &fast_t)).with_input("s", f64::NAN),
+        DiffsolExperiment::new(logistic_data(r, k, 0.1, &fast_t))
+            .with_initial_condition("y", f64::INFINITY),
+    ] {
+        let err = builder
+            .clone()
+            .with_experiment(experiment)
+            .build()
+            .err()
+            .expect("non-finite values should be rejected");
+        assert!(err.contains("finite"), "{}", err);
+    }
+
+    // Leaving `s` free in one experiment makes it a shared parameter as well.
+    let err = builder
+        .clone()
+        .with_experiment(DiffsolExperiment::new(logistic_data(r, k, 0.1, &fast_t)))
+        .build()
+        .err()
+        .expect("parameter specs must cover the shared inputs");
+    assert!(
+        err.contains("Expected 3 parameters") && err.contains("[r, k, s]"),
+        "{}",
+        err
+    );
+
+    let err = builder
+        .with_experiment(
+            DiffsolExperiment::new(logistic_data(r, k, 0.1, &fast_t)).with_input("q", 1.0),
+        )
+        .build()
+        .err()
+        .expect("unknown inputs should be rejected");
+    assert!(err.contains("'q'"), "{}", err);
+}
+
+#[test]
+fn diffsol_observations_map_outputs_onto_data_columns() {
+    let dsl = r#"
+in = [a, b]
+a { 1 }
+b { 1 }
+u_i { x = 1, y = 0 }
+F_i { -a * x, a * x - b * y }
+out_i { x, total = x + y }
+"#;
+    let (a, b) = (1.5_f64, 0.5_f64);
+    let t_span: Vec<f64> = (0..12).map(|i| i as f64 * 0.25).collect();
+    let x = |t: f64| (-a * t).exp();
+    let y = |t: f64| a / (b - a) * ((-a * t).exp() - (-b * t).exp());
+
+    let mut total_only = Vec::with_capacity(t_span.len() * 2);
+    let mut reordered = Vec::with_capacity(t_span.len() * 3);
+    for &t in &t_span {
+        total_only.extend([t, x(t) + y(t)]);
+        reordered.extend([t, x(t) + y(t), x(t)]);
+    }
+    let total_only = DMatrix::from_row_slice(t_span.len(), 2, &total_only);
+    let reordered = DMatrix::from_row_slice(t_span.len(), 3, &reordered);
+
+    let builder = DiffsolProblemBuilder::new()
+        .with_diffsl(dsl.to_string())
+        .with_parameter(ParameterSpec::new("a", 1.0, None))
+        .with_parameter(ParameterSpec::new("b", 1.0, None))
+        .with_rtol(1e-8)
+        .with_atol(1e-10);
+
+    // Without a mapping the single data column cannot match the two outputs.
+    let unmapped = builder
+        .clone()
+        .with_data(total_only.clone())
+        .build()
+        .unwrap();
+    let err = unmapped.evaluate(&[a, b]).unwrap_err();
+    assert!(err.contains("does not match data shape"), "{}", err);
+
+    let problem = builder
+        .clone()
+        .with_data(total_only)
+        .with_observations(["total"])
+        .build()
+        .expect("named observation should build");
+    assert_eq!(problem.residuals(&[a, b]).unwrap().len(), t_span.len());
+    assert!(problem.evaluate(&[a, b]).unwrap() < 1e-10);
+
+    // Indices and names can be mixed, and each experiment may map its own columns.
+    let problem = builder
+        .clone()
+        .with_experiment(
+            DiffsolExperiment::new(reordered.clone())
+                .with_observations([ObservedOutput::from("total"), ObservedOutput::from(0)]),
+        )
+        .build()
+        .unwrap();
+    assert!(problem.evaluate(&[a, b]).unwrap() < 1e-10);
+
+    let params = [1.2, 0.7];
+    let (residuals, jacobian) = problem.residuals_with_jacobian(&params).unwrap();
+    assert_eq!(jacobian.shape(), (2 * t_span.len(), 2));
+    let eps = 1e-6;
+    for j in 0..params.len() {
+        let (mut plus, mut minus) = (params, params);
+        plus[j] += eps;
+        minus[j] -= eps;
+        let r_plus = problem.residuals(&plus).unwrap();
+        let r_minus = problem.residuals(&minus).unwrap();
+        for i in 0..residuals.len() {
+            let fd = (r_plus[i] - r_minus[i]) / (2.0 * eps);
+            assert!(
+                (fd - jacobian[(i, j)]).abs() < 1e-5,
+                "Jacobian mismatch at ({}, {}): fd={} jac={}",
+                i,
+                j,
+                fd,
+                jacobian[(i, j)]
+            );
+        }
+    }
+
+    let err = builder
+        .clone()
+        .with_data(reordered.clone())
+        .with_observations(["y"])
+        .build()
+        .err()
+        .expect("column count mismatch should be rejected");
+    assert!(err.contains("2 observed columns"), "{}", err);
+
+    let err = builder
+        .with_data(reordered)
+        .with_observations(["total", "velocity"])
+        .build()
+        .err()
+        .expect("unknown outputs should be rejected");
+    assert!(err.contains("'velocity'"), "{}", err);
+}
+
+#[test]
+fn diffsol_builder_estimates_initial_states() {
+    let dsl = r#"
+in = [a]
+a { 1 }
+u_i { y = 0.1 }
+F_i { -a * y }
+"#;
+    let (a, y0) = (1.2_f64, 2.0_f64);
+    let t_span: Vec<f64> = (0..15).map(|i| i as f64 * 0.2).collect();
+    let mut data = Vec::with_capacity(t_span.len() * 2);
+    for &t in &t_span {
+        data.extend([t, y0 * (-a * t).exp()]);
+    }
+    let data = DMatrix::from_row_slice(t_span.len(), 2, &data);
+
+    let builder = DiffsolProblemBuilder::new()
+        .with_diffsl(dsl.to_string())
+        .with_data(data)
+        .with_parameter(ParameterSpec::new("a", 0.8, Some((0.1, 5.0))))
+        .with_rtol(1e-8)
+        .with_atol(1e-10);
+    let problem = builder
+        .clone()
+        .with_estimated_initial_state("y", ParameterSpec::new("y0", 1.0, Some((0.1, 5.0))))
+        .build()
+        .expect("initial state estimation should build");
+
+    // The estimated initial state follows the model parameters.
+    assert_eq!(problem.dimension(), 2);
+    assert_eq!(problem.default_parameters(), vec![0.8, 1.0]);
+    assert!(problem.evaluate(&[a, y0]).unwrap() < 1e-10);
+
+    let x = [0.9, 1.4];
+    let (cost, grad) = problem.evaluate_with_gradient(&x).unwrap();
+    let grad = grad.expect("diffsol problems provide gradients");
+    assert!((cost - problem.evaluate(&x).unwrap()).abs() < 1e-8);
+    let eps = 1e-6;
+    for i in 0..x.len() {
+        let (mut plus, mut minus) = (x, x);
+        plus[i] += eps;
+        minus[i] -= eps;
+        let fd =
+            (problem.evaluate(&plus).unwrap() - problem.evaluate(&minus).unwrap()) / (2.0 * eps);
+        assert!(
+            (fd - grad[i]).abs() < 1e-4 * fd.abs().max(1.0),
+            "gradient mismatch for parameter {}: fd={} grad={}",
+            i,
+            fd,
+            grad[i]
+        );
+    }
+
+    let result = LevenbergMarquardt::new().run(&problem, x.to_vec());
+    assert!(result.success, "{}", result.message);
+    assert!((result.x[0] - a).abs() < 1e-3, "a = {}", result.x[0]);
+    assert!((result.x[1] - y0).abs() < 1e-3, "y0 = {}", result.x[1]);
+
+    let err = builder
+        .clone()
+        .with_estimated_initial_state("y", ParameterSpec::new("a", 1.0, None))
+        .build()
+        .err()
+        .expect("parameter names must not clash with the program");
+    assert!(err.contains("clashes"), "{}", err);
+
+    let err = builder
+        .with_estimated_initial_state("z", ParameterSpec::new("z0", 1.0, None))
+        .build()
+        .err()
+        .expect("unknown states should be rejected");
+    assert!(err.contains("'z'"), "{}", err);
+}
@@ -26,6 +26,7 @@ def _build_diffsol_problem(parallel: bool) -> chron.Problem:
         .with_data(data)
         .with_parameter("r", 1.0, bounds=(0.1, 3.0))
         .with_parameter("k", 1.0, bounds=(0.5, 2.0))
+        .with_cost(chron.cost.GaussianNLL(variance=0.5))
         .with_parallel(parallel)
     )
     return builder.build()
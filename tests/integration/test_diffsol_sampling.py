@@ -35,7 +35,7 @@ stop {x}
         .with_parameter("h", h_true)
         .with_rtol(1e-6)
         .with_atol(1e-6)
-        .with_cost(chron.cost.SSE())
+        .with_cost(chron.cost.GaussianNLL(variance=0.5))
     )
 
     problem = builder.build()
@@ -91,11 +91,11 @@ stop {x}
         chron.DiffsolBuilder()
         .with_diffsl(dsl)
         .with_data(data)
-        .with_parameter("g", g_true)
-        .with_parameter("h", h_true)
+        .with_parameter("g", g_true, bounds=(1.0, 20.0))
+        .with_parameter("h", h_true, bounds=(1.0, 20.0))
         .with_rtol(1e-6)
         .with_atol(1e-6)
-        .with_cost(chron.cost.SSE())
+        .with_cost(chron.cost.GaussianNLL(variance=0.5))
     )
 
     problem = builder.build()
@@ -0,0 +1,92 @@
+import chronopt as chron
+import numpy as np
+import pytest
+
+
+def rosenbrock(x):
+    return (1 - x[0]) ** 2 + 100 * (x[1] - x[0] ** 2) ** 2
+
+
+def build_rosenbrock_problem():
+    return (
+        chron.ScalarBuilder()
+        .with_callable(lambda x: np.asarray([rosenbrock(x)], dtype=float))
+        .with_parameter("x", 1.0)
+        .with_parameter("y", 1.0)
+        .build()
+    )
+
+
+def drive(state, objective):
+    while not state.is_finished():
+        points = state.ask()
+        if not points:
+            break
+        state.tell(points, [objective(point) for point in points])
+    return state.result()
+
+
+@pytest.mark.parametrize(
+    "optimiser",
+    [
+        chron.CMAES().with_max_iter(200).with_sigma0(0.5).with_seed(5),
+        chron.NelderMead().with_max_iter(400).with_threshold(1e-10),
+    ],
+)
+def test_ask_tell_matches_run(optimiser):
+    expected = optimiser.run(build_rosenbrock_problem(), [-1.2, 1.0])
+
+    result = drive(optimiser.initialise([-1.2, 1.0]), rosenbrock)
+
+    assert np.allclose(result.x, expected.x)
+    assert result.nit == expected.nit
+    assert result.nfev == expected.nfev
+    assert result.termination_reason == expected.termination_reason
+
+
+def test_cmaes_ask_respects_bounds():
+    state = chron.CMAES().with_seed(3).initialise([0.5, 0.5], [(0.0, 1.0), (0.0, 1.0)])
+
+    assert state.ask() == [[0.5, 0.5]]
+    state.tell([[0.5, 0.5]], [1.0])
+
+    population = np.asarray(state.ask())
+    assert population.shape[0] > 1
+    assert np.all((population >= 0.0) & (population <= 1.0))
+
+
+def test_adam_ask_tell_minimises_quadratic():
+    target = np.array([1.5, -0.5])
+    state = (
+        chron.Adam()
+        .with_step_size(0.1)
+        .with_max_iter(500)
+        .with_threshold(1e-8)
+        .initialise([5.0, -4.0])
+    )
+
+    while not state.is_finished():
+        points = state.ask()
+        if not points:
+            break
+        residual = np.asarray(points[0]) - target
+        state.tell(points, [float(residual @ residual)], [list(2.0 * residual)])
+
+    result = state.result()
+    assert result.success
+    assert np.allclose(result.x, target, atol=1e-3)
+
+
+def test_tell_rejects_mismatched_points():
+    state = chron.NelderMead().initialise([0.0, 0.0])
+    points = state.ask()
+
+    with pytest.raises(ValueError):
+        state.tell(points, [1.0, 2.0])
+
+    with pytest.raises(ValueError):
+        chron.CMAES().initialise([0.0, 0.0], [(0.0, 1.0)])
+
+    adam = chron.Adam().initialise([1.0])
+    with pytest.raises(ValueError):
+        adam.tell([[1.0]], [1.0], [[2.0]])
@@ -81,3 +81,73 @@ def test_cmaes_result_covariance_available():
     assert covariance is not None
     assert len(covariance) == 2
     assert len(covariance[0]) == 2
+
+
+def rastrigin(x):
+    x = np.asarray(x, dtype=float)
+    value = 10.0 * x.size + np.sum(x**2 - 10.0 * np.cos(2.0 * np.pi * x))
+    return np.asarray([value], dtype=float)
+
+
+def build_rastrigin_problem():
+    return (
+        chron.ScalarBuilder()
+        .with_callable(rastrigin)
+        .with_parameter("x", 3.2)
+        .with_parameter("y", -2.7)
+        .build()
+    )
+
+
+@pytest.mark.parametrize("strategy", ["ipop", "bipop"])
+def test_cmaes_restarts_record_history(strategy):
+    problem = build_rastrigin_problem()
+
+    optimiser = (
+        chron.CMAES()
+        .with_max_iter(300)
+        .with_threshold(1e-10)
+        .with_sigma0(2.0)
+        .with_seed(11)
+        .with_restarts(strategy)
+        .with_max_restarts(4)
+    )
+
+    result = optimiser.run(problem, [3.2, -2.7])
+
+    assert len(result.restarts) == 5
+    assert result.restarts[0].x0 == [3.2, -2.7]
+    assert all(r.x0 != [3.2, -2.7] for r in result.restarts[1:])
+    assert result.fun == pytest.approx(min(r.fun for r in result.restarts))
+    assert result.nit == sum(r.nit for r in result.restarts)
+    assert result.nfev == 1 + sum(r.nfev for r in result.restarts)
+    default_size = result.restarts[0].population_size
+    assert all(r.population_size >= default_size for r in result.restarts)
+
+
+def test_cmaes_ipop_grows_population():
+    problem = build_rastrigin_problem()
+
+    optimiser = (
+        chron.CMAES()
+        .with_sigma0(2.0)
+        .with_seed(11)
+        .with_restarts("ipop")
+        .with_max_restarts(3)
+        .with_population_increase(3.0)
+    )
+
+    result = optimiser.run(problem, [3.2, -2.7])
+
+    sizes = [r.population_size for r in result.restarts]
+    assert sizes == [sizes[0] * 3**i for i in range(4)]
+
+
+def test_cmaes_without_restarts_has_empty_history():
+    result = chron.CMAES().with_seed(1).run(build_rosenbrock_problem(), [0.0, 0.0])
+    assert result.restarts == []
+
+
+def test_cmaes_rejects_unknown_restart_strategy():
+    with pytest.raises(ValueError, match="Unknown restart strategy"):
+        chron.CMAES().with_restarts("random")
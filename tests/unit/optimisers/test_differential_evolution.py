@@ -0,0 +1,89 @@
+import chronopt as chron
+import numpy as np
+import pytest
+
+
+def rastrigin(x):
+    x = np.asarray(x, dtype=float)
+    value = 10.0 * x.size + np.sum(x**2 - 10.0 * np.cos(2.0 * np.pi * x))
+    return np.asarray([value], dtype=float)
+
+
+def build_rastrigin_problem(bounds=(-5.12, 5.12)):
+    return (
+        chron.ScalarBuilder()
+        .with_callable(rastrigin)
+        .with_parameter("x", 3.2, bounds)
+        .with_parameter("y", -2.7, bounds)
+        .build()
+    )
+
+
+def test_differential_evolution_minimises_rastrigin():
+    problem = build_rastrigin_problem()
+
+    optimiser = (
+        chron.DifferentialEvolution()
+        .with_max_iter(500)
+        .with_threshold(1e-8)
+        .with_seed(13)
+    )
+
+    result = optimiser.run(problem, [3.2, -2.7])
+
+    assert result.success
+    assert result.fun < 1e-6
+    assert np.allclose(result.x, np.zeros(2), atol=1e-3)
+    population = np.asarray(result.final_simplex)
+    assert np.all(population >= -5.12) and np.all(population <= 5.12)
+
+
+@pytest.mark.parametrize("strategy", ["rand1bin", "best1bin"])
+def test_differential_evolution_strategies(strategy):
+    problem = build_rastrigin_problem(bounds=(-1.0, 1.0))
+
+    optimiser = (
+        chron.DifferentialEvolution()
+        .with_strategy(strategy)
+        .with_population_size(16)
+        .with_mutation(0.7)
+        .with_crossover(0.9)
+        .with_threshold(1e-8)
+        .with_seed(3)
+    )
+
+    result = optimiser.run(problem, [0.8, -0.6])
+
+    assert result.fun < 1e-6
+    assert len(result.final_simplex) == 16
+
+
+def test_differential_evolution_is_reproducible():
+    problem = build_rastrigin_problem()
+    optimiser = chron.DifferentialEvolution().with_max_iter(20).with_seed(99)
+
+    first = optimiser.run(problem, [3.2, -2.7])
+    second = optimiser.run(problem, [3.2, -2.7])
+
+    assert first.x == second.x
+    assert first.fun == second.fun
+
+
+def test_python_builder_optimise_with_differential_evolution_default():
+    builder = (
+        chron.ScalarBuilder()
+        .with_callable(rastrigin)
+        .with_parameter("x", 3.2, (-5.12, 5.12))
+        .with_parameter("y", -2.7, (-5.12, 5.12))
+    )
+    builder.with_optimiser(chron.DifferentialEvolution().with_seed(5))
+    problem = builder.build()
+
+    result = problem.optimize()
+
+    assert result.fun < 1e-4
+
+
+def test_differential_evolution_rejects_unknown_strategy():
+    with pytest.raises(ValueError, match="Unknown strategy"):
+        chron.DifferentialEvolution().with_strategy("current-to-best")
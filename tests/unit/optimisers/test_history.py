@@ -0,0 +1,61 @@
+import chronopt as chron
+import numpy as np
+import pytest
+
+
+def build_problem():
+    return (
+        chron.ScalarBuilder()
+        .with_callable(lambda x: np.asarray([float(np.sum(np.asarray(x) ** 2))]))
+        .with_gradient(lambda x: 2.0 * np.asarray(x))
+        .with_parameter("x", 1.0)
+        .with_parameter("y", 1.0)
+        .build()
+    )
+
+
+@pytest.mark.parametrize(
+    "optimiser",
+    [
+        chron.CMAES().with_seed(4),
+        chron.NelderMead(),
+        chron.Adam().with_step_size(0.05),
+        chron.LBFGSB(),
+        chron.DifferentialEvolution().with_seed(4),
+        chron.PSO().with_seed(4),
+    ],
+)
+def test_history_arrays_cover_each_iteration(optimiser):
+    result = optimiser.with_history(True).run(build_problem(), [1.5, -0.5])
+
+    history = result.history
+    assert history is not None
+    assert len(history) == result.nit
+    assert history.fun.shape == (result.nit,)
+    assert history.x.shape == (result.nit, 2)
+    assert history.step_size.shape == (result.nit,)
+    assert np.all(np.diff(history.nfev) > 0)
+    assert history.nfev[-1] <= result.nfev
+    assert np.all(history.fun >= result.fun - 1e-12)
+
+
+def test_adam_history_records_gradient_norm():
+    result = (
+        chron.Adam()
+        .with_step_size(0.05)
+        .with_history(True)
+        .run(build_problem(), [1.5, -0.5])
+    )
+
+    norms = result.history.gradient_norm
+    assert norms is not None
+    assert norms.shape == (result.nit,)
+    assert norms[0] > norms[-1]
+
+
+def test_history_disabled_by_default():
+    result = chron.NelderMead().run(build_problem(), [1.5, -0.5])
+
+    assert result.history is None
+    nm_history = chron.NelderMead().with_history(True).run(build_problem(), [1.5, -0.5])
+    assert nm_history.history.gradient_norm is None
@@ -0,0 +1,85 @@
+import chronopt as chron
+import numpy as np
+
+
+def rosenbrock(x):
+    return np.asarray([(1.0 - x[0]) ** 2 + 100.0 * (x[1] - x[0] ** 2) ** 2], dtype=float)
+
+
+def rosenbrock_grad(x):
+    return np.asarray(
+        [
+            -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] ** 2),
+            200.0 * (x[1] - x[0] ** 2),
+        ],
+        dtype=float,
+    )
+
+
+def build_rosenbrock_problem(bounds=None):
+    return (
+        chron.ScalarBuilder()
+        .with_callable(rosenbrock)
+        .with_gradient(rosenbrock_grad)
+        .with_parameter("x", -1.2, bounds)
+        .with_parameter("y", 1.0, bounds)
+        .build()
+    )
+
+
+def test_lbfgsb_direct_run_minimises_rosenbrock():
+    problem = build_rosenbrock_problem()
+
+    optimiser = chron.LBFGSB().with_max_iter(200).with_threshold(1e-10)
+
+    result = optimiser.run(problem, [-1.2, 1.0])
+
+    assert result.success
+    assert result.fun < 1e-8
+    assert np.allclose(result.x, np.array([1.0, 1.0]), atol=1e-4)
+
+
+def test_lbfgsb_respects_bounds():
+    problem = build_rosenbrock_problem(bounds=(-2.0, 0.5))
+
+    optimiser = chron.LBFGSB().with_memory(5).with_max_iter(200)
+
+    result = optimiser.run(problem, [-1.2, 0.0])
+
+    assert result.success
+    assert all(-2.0 <= xi <= 0.5 for xi in result.x)
+    assert np.isclose(result.x[0], 0.5, atol=1e-6)
+    assert np.isclose(result.x[1], 0.25, atol=1e-4)
+
+
+def test_python_builder_optimise_with_lbfgsb_default():
+    builder = (
+        chron.ScalarBuilder()
+        .with_callable(rosenbrock)
+        .with_gradient(rosenbrock_grad)
+        .with_parameter("x", -1.2)
+        .with_parameter("y", 1.0)
+    )
+
+    builder.with_optimiser(chron.LBFGSB().with_threshold(1e-10))
+    problem = builder.build()
+
+    result = problem.optimize()
+
+    assert result.success
+    assert np.allclose(result.x, np.array([1.0, 1.0]), atol=1e-4)
+
+
+def test_lbfgsb_requires_gradient():
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(rosenbrock)
+        .with_parameter("x", 0.0)
+        .with_parameter("y", 0.0)
+        .build()
+    )
+
+    result = chron.LBFGSB().with_max_iter(10).run(problem, [0.0, 0.0])
+
+    assert not result.success
+    assert "requires an available gradient" in result.message
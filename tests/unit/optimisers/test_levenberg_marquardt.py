@@ -0,0 +1,90 @@
+import chronopt as chron
+import numpy as np
+
+t_span = np.linspace(0.0, 2.0, 40)
+true_params = np.array([1.5, 0.5])
+
+
+def exponential_model(params):
+    rate, y0 = params
+    return y0 * np.exp(rate * t_span)
+
+
+def build_exponential_problem(bounds=None, noise=None):
+    data = exponential_model(true_params)
+    if noise is not None:
+        data = data + noise
+    return (
+        chron.VectorBuilder()
+        .with_objective(exponential_model)
+        .with_data(data)
+        .with_parameter("rate", 1.0, bounds)
+        .with_parameter("y0", 1.0, bounds)
+        .build()
+    )
+
+
+def test_levenberg_marquardt_fits_exponential():
+    problem = build_exponential_problem()
+
+    optimiser = chron.LevenbergMarquardt().with_max_iter(200).with_threshold(1e-10)
+
+    result = optimiser.run(problem, [1.0, 1.0])
+
+    assert result.success
+    assert result.fun < 1e-8
+    assert np.allclose(result.x, true_params, atol=1e-4)
+
+
+def test_levenberg_marquardt_reports_covariance():
+    rng = np.random.default_rng(8)
+    noise = rng.normal(0.0, 0.05, size=t_span.size)
+    problem = build_exponential_problem(noise=noise)
+
+    result = chron.LevenbergMarquardt().run(problem, [1.0, 1.0])
+
+    assert result.success
+    covariance = np.asarray(result.covariance)
+    assert covariance.shape == (2, 2)
+    assert np.allclose(covariance, covariance.T)
+    assert np.all(np.diag(covariance) > 0.0)
+
+
+def test_levenberg_marquardt_respects_bounds():
+    problem = build_exponential_problem(bounds=(0.0, 1.2))
+
+    result = chron.LevenbergMarquardt().with_max_iter(200).run(problem, [1.0, 1.0])
+
+    assert all(0.0 <= xi <= 1.2 for xi in result.x)
+    assert np.isclose(result.x[0], 1.2, atol=1e-6)
+
+
+def test_python_builder_optimise_with_levenberg_marquardt_default():
+    builder = (
+        chron.VectorBuilder()
+        .with_objective(exponential_model)
+        .with_data(exponential_model(true_params))
+        .with_parameter("rate", 1.0)
+        .with_parameter("y0", 1.0)
+    )
+
+    builder.with_optimiser(chron.LevenbergMarquardt())
+    problem = builder.build()
+
+    result = problem.optimize()
+
+    assert result.success
+    assert np.allclose(result.x, true_params, atol=1e-4)
+
+
+def test_levenberg_marquardt_requires_residuals():
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(lambda x: np.asarray([float(np.sum(np.square(x)))]))
+        .with_parameter("x", 1.0)
+        .build()
+    )
+
+    result = chron.LevenbergMarquardt().run(problem, [1.0])
+
+    assert not result.success
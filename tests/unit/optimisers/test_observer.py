@@ -0,0 +1,85 @@
+import sys
+
+import chronopt as chron
+import numpy as np
+import pytest
+
+
+def sphere(x):
+    return np.asarray([float(np.sum(np.asarray(x) ** 2))])
+
+
+def build_problem(with_gradient=False):
+    builder = (
+        chron.ScalarBuilder()
+        .with_callable(sphere)
+        .with_parameter("x", 1.0)
+        .with_parameter("y", 1.0)
+    )
+    if with_gradient:
+        builder = builder.with_gradient(lambda x: 2.0 * np.asarray(x))
+    return builder.build()
+
+
+@pytest.mark.parametrize(
+    ("optimiser", "with_gradient"),
+    [
+        (chron.CMAES().with_seed(1), False),
+        (chron.NelderMead(), False),
+        (chron.Adam().with_step_size(0.1), True),
+        (chron.DifferentialEvolution().with_seed(1), False),
+        (chron.PSO().with_seed(1), False),
+    ],
+)
+def test_observer_receives_progress_and_stops_run(optimiser, with_gradient):
+    seen = []
+
+    def observer(progress):
+        seen.append(progress)
+        return progress.iteration >= 4
+
+    result = optimiser.with_observer(observer).run(
+        build_problem(with_gradient), [2.0, -1.5]
+    )
+
+    assert [p.iteration for p in seen] == [1, 2, 3, 4]
+    assert all(len(p.x) == 2 and p.step_size >= 0.0 for p in seen)
+    assert all(b.nfev > a.nfev for a, b in zip(seen, seen[1:]))
+    assert seen[-1].elapsed.total_seconds() >= 0.0
+    assert result.nit == 4
+    assert not result.success
+    assert result.termination_reason == "Stopped by observer"
+
+
+def test_observer_returning_none_continues():
+    calls = []
+    result = (
+        chron.NelderMead()
+        .with_max_iter(500)
+        .with_observer(lambda progress: calls.append(progress.fun))
+        .run(build_problem(), [2.0, -1.5])
+    )
+
+    assert result.success
+    assert len(calls) == result.nit
+    assert calls == sorted(calls, reverse=True)
+
+
+def test_observer_exception_stops_run(monkeypatch):
+    def observer(progress):
+        raise RuntimeError("abort")
+
+    reported = []
+    monkeypatch.setattr(sys, "unraisablehook", reported.append)
+
+    result = chron.CMAES().with_observer(observer).run(build_problem(), [1.0, 1.0])
+
+    assert len(reported) == 1
+    assert isinstance(reported[0].exc_value, RuntimeError)
+    assert result.nit == 1
+    assert result.termination_reason == "Stopped by observer"
+
+
+def test_observer_must_be_callable():
+    with pytest.raises(TypeError):
+        chron.Adam().with_observer(3)
@@ -0,0 +1,70 @@
+import chronopt as chron
+import numpy as np
+
+
+def quadratic(x):
+    return np.asarray([(x[0] - 1.5) ** 2 + (x[1] + 0.5) ** 2], dtype=float)
+
+
+def build_quadratic_problem(bounds=None):
+    return (
+        chron.ScalarBuilder()
+        .with_callable(quadratic)
+        .with_parameter("x", 5.0, bounds)
+        .with_parameter("y", -4.0, bounds)
+        .build()
+    )
+
+
+def test_pso_direct_run_minimises_quadratic():
+    problem = build_quadratic_problem()
+
+    optimiser = (
+        chron.PSO()
+        .with_max_iter(500)
+        .with_threshold(1e-10)
+        .with_sigma0(2.0)
+        .with_seed(42)
+    )
+
+    result = optimiser.run(problem, [5.0, -4.0])
+
+    assert result.success
+    assert np.allclose(result.x, [1.5, -0.5], atol=1e-4)
+    assert result.nfev == len(result.final_simplex) * (result.nit + 1)
+
+
+def test_pso_respects_bounds():
+    problem = build_quadratic_problem(bounds=(-0.2, 0.2))
+
+    optimiser = (
+        chron.PSO()
+        .with_population_size(20)
+        .with_inertia(0.6)
+        .with_cognitive(1.2)
+        .with_social(1.8)
+        .with_seed(7)
+    )
+
+    result = optimiser.run(problem, [0.0, 0.0])
+
+    swarm = np.asarray(result.final_simplex)
+    assert swarm.shape == (20, 2)
+    assert np.all(swarm >= -0.2) and np.all(swarm <= 0.2)
+    assert np.allclose(result.x, [0.2, -0.2], atol=1e-6)
+
+
+def test_python_builder_optimise_with_pso_default():
+    builder = (
+        chron.ScalarBuilder()
+        .with_callable(quadratic)
+        .with_parameter("x", 5.0)
+        .with_parameter("y", -4.0)
+    )
+    builder.with_optimiser(chron.PSO().with_sigma0(2.0).with_seed(1))
+    problem = builder.build()
+
+    result = problem.optimize()
+
+    assert result.success
+    assert np.allclose(result.x, [1.5, -0.5], atol=1e-3)
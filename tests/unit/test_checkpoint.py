@@ -0,0 +1,68 @@
+import chronopt as chron
+import numpy as np
+import pytest
+
+
+def rosenbrock(x):
+    return np.asarray(
+        [(1 - x[0]) ** 2 + 100 * (x[1] - x[0] ** 2) ** 2], dtype=float
+    )
+
+
+def build_problem():
+    return (
+        chron.ScalarBuilder()
+        .with_callable(rosenbrock)
+        .with_parameter("x", 1.0)
+        .with_parameter("y", 1.0)
+        .build()
+    )
+
+
+@pytest.mark.parametrize(
+    "optimiser",
+    [
+        chron.CMAES().with_sigma0(0.5).with_threshold(1e-12).with_seed(11),
+        chron.NelderMead().with_threshold(1e-12),
+    ],
+)
+def test_optimiser_resume_matches_uninterrupted_run(optimiser, tmp_path):
+    path = tmp_path / "run.checkpoint"
+    problem = build_problem()
+
+    expected = optimiser.with_max_iter(40).run(problem, [-1.2, 1.0])
+    optimiser.with_max_iter(20).with_checkpoint(path, 15).run(problem, [-1.2, 1.0])
+    assert path.exists()
+
+    resumed = optimiser.with_max_iter(40).resume_from(problem, path)
+
+    assert resumed.x == expected.x
+    assert resumed.nit == expected.nit
+    assert resumed.nfev == expected.nfev
+
+
+def test_metropolis_hastings_resume_matches_uninterrupted_run(tmp_path):
+    path = tmp_path / "chains.checkpoint"
+    problem = build_problem()
+    sampler = chron.sampler.MetropolisHastings().with_num_chains(2).with_seed(4)
+
+    expected = sampler.with_iterations(60).run(problem, [0.5, 0.5])
+    sampler.with_iterations(30).with_checkpoint(str(path), 20).run(problem, [0.5, 0.5])
+    resumed = sampler.with_iterations(60).resume_from(problem, str(path))
+
+    assert resumed.chains == expected.chains
+    assert resumed.mean_x == expected.mean_x
+
+
+def test_resume_rejects_missing_or_mismatched_checkpoint(tmp_path):
+    problem = build_problem()
+
+    with pytest.raises(ValueError):
+        chron.CMAES().resume_from(problem, tmp_path / "missing.checkpoint")
+
+    path = tmp_path / "simplex.checkpoint"
+    chron.NelderMead().with_max_iter(10).with_checkpoint(path, 5).run(
+        problem, [-1.2, 1.0]
+    )
+    with pytest.raises(ValueError):
+        chron.sampler.DynamicNestedSampler().resume_from(problem, path)
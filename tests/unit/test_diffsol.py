@@ -245,3 +245,255 @@ F_i {
     assert result.success
     assert pytest.approx(true_L, rel=1e-2, abs=1e-2) == result.x[0]
     assert result.fun < 1e-6
+
+
+def test_diffsol_residuals_and_jacobian():
+    ds = """
+in = [a]
+a { 1 }
+u_i { y = 0.1 }
+F_i { a * y }
+"""
+
+    t_span = np.linspace(0, 1, 6)
+    data = 0.1 * np.exp(2.0 * t_span)
+    stacked_data = np.column_stack((t_span, data))
+
+    problem = (
+        chron.DiffsolBuilder()
+        .with_diffsl(ds)
+        .with_data(stacked_data)
+        .with_rtol(1e-8)
+        .with_atol(1e-10)
+        .with_parameter("a", 1.5)
+        .build()
+    )
+
+    x = [1.5]
+    residuals = np.asarray(problem.residuals(x))
+    expected = 0.1 * np.exp(1.5 * t_span) - data
+    np.testing.assert_allclose(residuals, expected, rtol=1e-5, atol=1e-8)
+    assert pytest.approx(problem.evaluate(x), rel=1e-8) == np.sum(residuals**2)
+
+    residuals_j, jacobian = problem.residuals_with_jacobian(x)
+    jacobian = np.asarray(jacobian)
+    assert jacobian.shape == (t_span.size, 1)
+    np.testing.assert_allclose(residuals_j, residuals)
+    np.testing.assert_allclose(
+        jacobian[:, 0], 0.1 * t_span * np.exp(1.5 * t_span), rtol=1e-4, atol=1e-8
+    )
+
+
+def test_diffsol_multi_experiment_joint_fit():
+    ds = """
+in = [a, b]
+a { 1 }
+b { 0 }
+u_i { y = 0.1 }
+F_i { a * y + b }
+"""
+
+    a_true = 1.5
+    early = np.linspace(0, 1, 6)
+    late = np.linspace(0, 2, 9)
+    # The second experiment starts higher and has no forcing term.
+    early_data = np.column_stack((early, 0.1 * np.exp(a_true * early)))
+    late_data = np.column_stack((late, 0.3 * np.exp(a_true * late)))
+
+    problem = (
+        chron.DiffsolBuilder()
+        .with_diffsl(ds)
+        .with_rtol(1e-8)
+        .with_atol(1e-10)
+        .with_parameter("a", 1.0)
+        .with_experiment(early_data, inputs={"b": 0.0})
+        .with_experiment(
+            late_data,
+            inputs={"b": 0.0},
+            initial_conditions={"y": 0.3},
+            cost=chron.cost.SSE(weight=2.0),
+        )
+        .build()
+    )
+
+    residuals = np.asarray(problem.residuals([1.2]))
+    assert residuals.shape == (early.size + late.size,)
+    expected = 0.1 * np.exp(1.2 * early) - early_data[:, 1]
+    np.testing.assert_allclose(residuals[: early.size], expected, rtol=1e-5)
+    late_residuals = residuals[early.size :]
+    assert pytest.approx(problem.evaluate([1.2]), rel=1e-6) == np.sum(
+        residuals[: early.size] ** 2
+    ) + 2.0 * np.sum(late_residuals**2)
+
+    result = (
+        chron.NelderMead().with_max_iter(300).with_threshold(1e-12).run(problem, [1.0])
+    )
+    assert abs(result.x[0] - a_true) < 1e-3
+
+    with pytest.raises(ValueError, match="not defined in the u_i block"):
+        (
+            chron.DiffsolBuilder()
+            .with_diffsl(ds)
+            .with_parameter("a", 1.0)
+            .with_experiment(early_data, initial_conditions={"z": 1.0})
+            .build()
+        )
+
+    with pytest.raises(ValueError, match=r"Expected 2 parameters .*\[a, b\], got 1"):
+        (
+            chron.DiffsolBuilder()
+            .with_diffsl(ds)
+            .with_parameter("a", 1.0)
+            .with_data(early_data)
+            .build()
+        )
+
+
+def test_diffsol_observations_select_outputs():
+    ds = """
+in = [a, b]
+a { 1 }
+b { 1 }
+u_i { x = 1, y = 0 }
+F_i { -a * x, a * x - b * y }
+out_i { x, total = x + y }
+"""
+
+    a, b = 1.5, 0.5
+    t_span = np.linspace(0, 3, 13)
+    x = np.exp(-a * t_span)
+    total = x + a / (b - a) * (np.exp(-a * t_span) - np.exp(-b * t_span))
+    builder = (
+        chron.DiffsolBuilder()
+        .with_diffsl(ds)
+        .with_rtol(1e-8)
+        .with_atol(1e-10)
+        .with_parameter("a", 1.0)
+        .with_parameter("b", 1.0)
+    )
+
+    problem = (
+        copy.copy(builder)
+        .with_data(np.column_stack((t_span, total)))
+        .with_observations(["total"])
+        .build()
+    )
+    assert len(problem.residuals([a, b])) == t_span.size
+    assert problem.evaluate([a, b]) < 1e-10
+
+    problem = (
+        copy.copy(builder)
+        .with_data(np.column_stack((t_span, x, total)))
+        .with_observations([0, "total"])
+        .build()
+    )
+    assert problem.evaluate([a, b]) < 1e-10
+
+    with pytest.raises(ValueError, match="'speed'"):
+        (
+            copy.copy(builder)
+            .with_data(np.column_stack((t_span, total)))
+            .with_observations(["speed"])
+            .build()
+        )
+    with pytest.raises(TypeError):
+        builder.with_observations([1.5])
+
+
+def test_diffsol_missing_observations_and_masks():
+    ds = """
+in = [a]
+a { 1 }
+u_i { x = 1, y = 0 }
+F_i { -a * x, a * x }
+"""
+
+    a = 1.3
+    t_span = np.linspace(0, 1, 9)
+    x = np.exp(-a * t_span)
+    data = np.column_stack((t_span, x, 1.0 - x))
+    data[3, 1] = np.nan
+    # y is only measured at every third time point.
+    mask = np.ones((t_span.size, 2), dtype=bool)
+    mask[:, 1] = np.arange(t_span.size) % 3 == 0
+
+    builder = (
+        chron.DiffsolBuilder()
+        .with_diffsl(ds)
+        .with_rtol(1e-8)
+        .with_atol(1e-10)
+        .with_parameter("a", 1.0)
+        .with_cost(chron.cost.GaussianNLL(0.01))
+    )
+    problem = copy.copy(builder).with_data(data).with_mask(mask).build()
+    joint = (
+        copy.copy(builder)
+        .with_experiment(data[:5], mask=mask[:5])
+        .with_experiment(data[5:], mask=mask[5:])
+        .build()
+    )
+
+    residuals = np.asarray(problem.residuals([1.0]))
+    n_observed = (t_span.size - 1) + 3
+    assert residuals.shape == (n_observed,)
+    assert np.all(np.isfinite(residuals))
+    expected = 0.5 * n_observed * np.log(2 * np.pi * 0.01) + 0.5 * np.sum(
+        residuals**2 / 0.01
+    )
+    assert problem.evaluate([1.0]) == pytest.approx(expected, rel=1e-10)
+    assert joint.evaluate([1.0]) == pytest.approx(expected, rel=1e-8)
+
+    residuals_j, jacobian = problem.residuals_with_jacobian([1.0])
+    np.testing.assert_allclose(residuals_j, residuals)
+    assert np.asarray(jacobian).shape == (n_observed, 1)
+
+
+def test_diffsol_estimated_initial_state():
+    ds = """
+in = [a]
+a { 1 }
+u_i { y = 0.1 }
+F_i { -a * y }
+"""
+
+    a_true, y0_true = 1.2, 2.0
+    t_span = np.linspace(0, 3, 16)
+    data = np.column_stack((t_span, y0_true * np.exp(-a_true * t_span)))
+
+    problem = (
+        chron.DiffsolBuilder()
+        .with_diffsl(ds)
+        .with_data(data)
+        .with_rtol(1e-8)
+        .with_atol(1e-10)
+        .with_parameter("a", 0.8, bounds=(0.1, 5.0))
+        .with_estimated_initial_state("y", "y0", 1.0, bounds=(0.1, 5.0))
+        .build()
+    )
+
+    # The initial state follows the model parameters in the parameter vector.
+    assert problem.dimension() == 2
+    assert problem.evaluate([a_true, y0_true]) < 1e-10
+
+    x = [0.9, 1.4]
+    residuals, jacobian = problem.residuals_with_jacobian(x)
+    jacobian = np.asarray(jacobian)
+    assert jacobian.shape == (t_span.size, 2)
+    # Each residual is linear in the initial state.
+    np.testing.assert_allclose(jacobian[:, 1], np.exp(-x[0] * t_span), rtol=1e-5)
+
+    result = (
+        chron.NelderMead().with_max_iter(500).with_threshold(1e-12).run(problem, x)
+    )
+    assert abs(result.x[0] - a_true) < 1e-3
+    assert abs(result.x[1] - y0_true) < 1e-3
+
+    with pytest.raises(ValueError, match="clashes"):
+        (
+            chron.DiffsolBuilder()
+            .with_diffsl(ds)
+            .with_data(data)
+            .with_parameter("a", 1.0)
+            .with_estimated_initial_state("y", "a", 1.0)
+            .build()
+        )
@@ -583,5 +583,115 @@ def test_information_increases_with_constraint():
     assert narrow_nested.information > wide_nested.information
 
 
+def correlated_problem():
+    """Bivariate Gaussian with correlation 0.99 in a [-5, 5]^2 box; Z = 1/100."""
+    rho = 0.99
+    log_norm = np.log(2 * np.pi) + 0.5 * np.log(1 - rho**2)
+
+    def nll(x: list[float]) -> float:
+        quad = x[0] ** 2 - 2 * rho * x[0] * x[1] + x[1] ** 2
+        return 0.5 * quad / (1 - rho**2) + log_norm
+
+    return (
+        chron.ScalarBuilder()
+        .with_callable(nll)
+        .with_parameter("x", 0.0, bounds=(-5.0, 5.0))
+        .with_parameter("y", 0.0, bounds=(-5.0, 5.0))
+        .build()
+    )
+
+
+def test_ellipsoidal_bounding_improves_efficiency_on_correlated_posterior():
+    """Ellipsoids follow a strongly correlated posterior that perturbations miss."""
+    problem = correlated_problem()
+
+    def run(bounding: str):
+        return (
+            chron.sampler.DynamicNestedSampler()
+            .with_live_points(128)
+            .with_bounding(bounding)
+            .with_seed(5)
+            .run(problem)
+        )
+
+    perturbation = run("perturbation")
+    ellipsoid = run("ellipsoid")
+    multi = run("multi_ellipsoid")
+
+    assert perturbation.proposals >= perturbation.accepted_proposals > 0
+    for nested in (ellipsoid, multi):
+        assert abs(nested.log_evidence + np.log(100.0)) < 0.4
+        assert nested.efficiency > 3 * perturbation.efficiency
+        assert nested.efficiency == pytest.approx(
+            nested.accepted_proposals / nested.proposals
+        )
+
+
+@pytest.mark.parametrize("bounding", ["perturbation", "ellipsoid"])
+@pytest.mark.parametrize(
+    "configure",
+    [
+        lambda sampler: sampler.with_random_walk(20),
+        lambda sampler: sampler.with_slice_sampling(2),
+    ],
+    ids=["random_walk", "slice"],
+)
+def test_constrained_mcmc_proposals_recover_evidence(bounding, configure):
+    sampler = (
+        chron.sampler.DynamicNestedSampler()
+        .with_live_points(128)
+        .with_bounding(bounding)
+        .with_seed(5)
+    )
+    nested = configure(sampler).run(correlated_problem())
+
+    assert abs(nested.log_evidence + np.log(100.0)) < 0.4
+    assert np.all(np.abs(nested.mean) < 0.3)
+    assert nested.proposals > nested.accepted_proposals > 0
+
+
+def test_unknown_bounding_is_rejected():
+    with pytest.raises(ValueError, match="Unknown bounding"):
+        chron.sampler.DynamicNestedSampler().with_bounding("sphere")
+
+
+def test_evidence_error_bars_and_equal_weight_resampling():
+    """Evidence comes with error bars and the posterior resamples to equal weights."""
+    sigma = 0.8
+    log_norm = np.log(sigma) + 0.5 * np.log(2 * np.pi)
+
+    def nll(x: list[float]) -> float:
+        return 0.5 * (x[0] / sigma) ** 2 + log_norm
+
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(nll)
+        .with_parameter("x", 0.0, bounds=(-10.0, 10.0))
+        .build()
+    )
+    nested = (
+        chron.sampler.DynamicNestedSampler()
+        .with_live_points(128)
+        .with_seed(3)
+        .run(problem)
+    )
+
+    skilling = nested.log_evidence_error
+    simulated = nested.simulated_log_evidence_error(simulations=200, seed=1)
+    assert 0.0 < simulated < 0.5
+    assert 0.0 < skilling < 0.5
+    assert nested.simulated_log_evidence_error(simulations=200, seed=1) == simulated
+    assert abs(nested.log_evidence + np.log(20.0)) < 4 * max(skilling, simulated)
+
+    ess = nested.effective_sample_size
+    assert 1.0 < ess <= len(nested.posterior)
+
+    samples = nested.resample(seed=5)
+    draws = np.asarray(samples.chains[0])
+    assert samples.draws == len(nested.posterior)
+    assert abs(draws[:, 0].mean()) < 0.2
+    assert abs(draws[:, 0].std() - sigma) < 0.15
+
+
 if __name__ == "__main__":
     pytest.main([__file__, "-v"])
@@ -0,0 +1,102 @@
+import math
+
+import chronopt as chron
+import numpy as np
+import pytest
+
+
+def test_prior_transforms_round_trip():
+    priors = [
+        chron.Prior.uniform(-1.0, 2.0),
+        chron.Prior.normal(0.5, 2.0),
+        chron.Prior.log_normal(0.0, 0.5),
+        chron.Prior.truncated_normal(0.0, 1.0, 0.0, math.inf),
+        chron.Prior.half_cauchy(1.0),
+        chron.Prior.beta(2.0, 3.0),
+        chron.Prior.gamma(2.0, 1.5),
+    ]
+
+    for prior in priors:
+        for u in (0.01, 0.3, 0.5, 0.9):
+            x = prior.from_unit(u)
+            assert prior.to_unit(x) == pytest.approx(u, abs=1e-9)
+            assert math.isfinite(prior.log_density(x))
+
+
+def test_prior_log_density_and_support():
+    normal = chron.Prior.normal(1.0, 2.0)
+    expected = -0.5 * math.log(2.0 * math.pi) - math.log(2.0)
+    assert normal.log_density(1.0) == pytest.approx(expected)
+    assert normal.support == (-math.inf, math.inf)
+
+    beta = chron.Prior.beta(2.0, 2.0)
+    assert beta.support == (0.0, 1.0)
+    assert beta.log_density(1.5) == -math.inf
+    assert repr(beta) == "Prior.beta(2, 2)"
+
+
+def test_prior_sampling_is_reproducible():
+    prior = chron.Prior.gamma(3.0, 2.0)
+
+    first = prior.sample(5000, seed=3)
+    second = prior.sample(5000, seed=3)
+
+    assert first == second
+    assert all(value > 0.0 for value in first)
+    assert float(np.mean(first)) == pytest.approx(1.5, abs=0.05)
+
+
+@pytest.mark.parametrize(
+    "factory, args",
+    [
+        (chron.Prior.uniform, (1.0, 0.0)),
+        (chron.Prior.normal, (0.0, -1.0)),
+        (chron.Prior.half_cauchy, (0.0,)),
+        (chron.Prior.beta, (1.0, 0.0)),
+        (chron.Prior.gamma, (-1.0, 1.0)),
+    ],
+)
+def test_prior_rejects_invalid_parameters(factory, args):
+    with pytest.raises(ValueError):
+        factory(*args)
+
+
+def conjugate_problem():
+    # Unit Gaussian likelihood for an observation at 1 with a N(3, 1) prior:
+    # the posterior is N(2, 0.5).
+    def negative_log_likelihood(x):
+        return 0.5 * (x[0] - 1.0) ** 2 + 0.5 * math.log(2.0 * math.pi)
+
+    return (
+        chron.ScalarBuilder()
+        .with_callable(negative_log_likelihood)
+        .with_parameter("x", 1.0, prior=chron.Prior.normal(3.0, 1.0))
+        .build()
+    )
+
+
+def test_metropolis_hastings_samples_posterior_with_prior():
+    problem = conjugate_problem()
+    sampler = (
+        chron.sampler.MetropolisHastings()
+        .with_num_chains(4)
+        .with_iterations(3000)
+        .with_step_size(0.8)
+        .with_seed(5)
+    )
+
+    samples = sampler.run(problem, [1.0]).burn_in(500)
+
+    assert samples.mean_x[0] == pytest.approx(2.0, abs=0.15)
+    assert samples.covariance()[0][0] == pytest.approx(0.5, abs=0.1)
+
+
+def test_dynamic_nested_sampler_uses_prior_transform():
+    problem = conjugate_problem()
+    sampler = chron.sampler.DynamicNestedSampler().with_live_points(128).with_seed(11)
+
+    nested = sampler.run(problem)
+
+    expected = -1.0 - 0.5 * math.log(4.0 * math.pi)
+    assert nested.log_evidence == pytest.approx(expected, abs=0.3)
+    assert nested.mean[0] == pytest.approx(2.0, abs=0.15)
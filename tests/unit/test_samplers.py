@@ -46,7 +46,7 @@ def test_dynamic_nested_sampler_runs_on_scalar_problem():
     problem = (
         chron.ScalarBuilder()
         .with_callable(quadratic_potential)
-        .with_parameter("x", 0.5)
+        .with_parameter("x", 0.5, bounds=(-5.0, 5.0))
         .build()
     )
 
@@ -69,7 +69,7 @@ def test_dynamic_nested_invalid_live_points_are_clamped():
     problem = (
         chron.ScalarBuilder()
         .with_callable(quadratic_potential)
-        .with_parameter("x", 1.0)
+        .with_parameter("x", 1.0, bounds=(-5.0, 5.0))
         .build()
     )
 
@@ -79,8 +79,316 @@ def test_dynamic_nested_invalid_live_points_are_clamped():
     assert nested.draws >= 0
 
 
+def test_dynamic_nested_requires_priors_or_bounds():
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(quadratic_potential)
+        .with_parameter("x", 1.0)
+        .build()
+    )
+
+    with pytest.raises(ValueError, match="prior or bounds"):
+        chron.sampler.DynamicNestedSampler().run(problem)
+
+
 def test_dynamic_nested_requires_problem_instance():
     sampler = chron.sampler.DynamicNestedSampler()
 
     with pytest.raises(TypeError):
         sampler.run(object())  # type: ignore[arg-type]
+
+
+def correlated_gaussian_problem():
+    covariance = np.array([[1.0, 0.9], [0.9, 1.0]])
+    precision = np.linalg.inv(covariance)
+    return (
+        chron.ScalarBuilder()
+        .with_callable(lambda x: np.asarray([0.5 * x @ precision @ x]))
+        .with_gradient(lambda x: precision @ np.asarray(x))
+        .with_parameter("x", 0.0)
+        .with_parameter("y", 0.0)
+        .build()
+    )
+
+
+@pytest.mark.parametrize("mass_matrix", ["diagonal", "dense"])
+def test_hamiltonian_monte_carlo_recovers_correlated_gaussian(mass_matrix):
+    sampler = (
+        chron.sampler.HamiltonianMonteCarlo()
+        .with_num_chains(2)
+        .with_warmup(300)
+        .with_iterations(600)
+        .with_mass_matrix(mass_matrix)
+        .with_seed(5)
+    )
+
+    samples = sampler.run(correlated_gaussian_problem(), [1.0, -1.0])
+
+    draws = np.concatenate([np.asarray(chain) for chain in samples.chains])
+    assert samples.draws == 2 * 600
+    assert all(len(chain) == 600 for chain in samples.chains)
+    assert np.allclose(draws.mean(axis=0), 0.0, atol=0.25)
+    assert np.corrcoef(draws.T)[0, 1] == pytest.approx(0.9, abs=0.08)
+
+
+def test_static_hamiltonian_monte_carlo_runs():
+    sampler = (
+        chron.HamiltonianMonteCarlo()
+        .with_leapfrog_steps(10)
+        .with_warmup(100)
+        .with_iterations(200)
+        .with_seed(2)
+    )
+
+    samples = sampler.run(correlated_gaussian_problem())
+
+    assert samples.draws == 200
+    assert np.all(np.isfinite(np.asarray(samples.chains[0])))
+
+
+def test_hamiltonian_monte_carlo_requires_gradient():
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(quadratic_potential)
+        .with_parameter("x", 1.0)
+        .build()
+    )
+
+    with pytest.raises(ValueError, match="gradient"):
+        chron.sampler.HamiltonianMonteCarlo().run(problem, [0.5])
+
+    with pytest.raises(ValueError):
+        chron.sampler.HamiltonianMonteCarlo().with_mass_matrix("full")
+
+
+def badly_scaled_problem():
+    scales = np.array([1e-3, 1e2])
+    return (
+        chron.ScalarBuilder()
+        .with_callable(
+            lambda x: np.asarray([0.5 * np.sum((np.asarray(x) / scales) ** 2)])
+        )
+        .with_parameter("x", 0.0)
+        .with_parameter("y", 0.0)
+        .build()
+    )
+
+
+def test_adaptive_metropolis_learns_parameter_scales():
+    sampler = (
+        chron.sampler.MetropolisHastings()
+        .with_num_chains(2)
+        .with_adaptation(2000)
+        .with_iterations(4000)
+        .with_target_acceptance(0.3)
+        .with_seed(7)
+    )
+
+    samples = sampler.run(badly_scaled_problem(), [0.0, 0.0])
+
+    draws = np.concatenate([np.asarray(chain) for chain in samples.chains])
+    assert all(len(chain) == 4001 for chain in samples.chains)
+    assert draws.std(axis=0) == pytest.approx([1e-3, 1e2], rel=0.25)
+
+
+def test_metropolis_hastings_accepts_optimiser_covariance():
+    problem = correlated_gaussian_problem()
+    fit = chron.CMAES().with_sigma0(0.5).with_seed(3).run(problem, [1.0, -1.0])
+    assert fit.covariance is not None
+
+    samples = (
+        chron.sampler.MetropolisHastings()
+        .with_proposal_covariance(fit.covariance)
+        .with_adaptation(200)
+        .with_iterations(200)
+        .with_seed(1)
+        .run(problem, fit.x)
+    )
+    assert samples.draws == 200
+
+    with pytest.raises(ValueError, match="covariance"):
+        chron.sampler.MetropolisHastings().with_proposal_covariance([[1.0]]).run(
+            problem, [0.0, 0.0]
+        )
+
+
+@pytest.mark.parametrize("differential_evolution", [0.0, 0.5])
+def test_ensemble_sampler_recovers_correlated_gaussian(differential_evolution):
+    sampler = (
+        chron.sampler.EnsembleSampler()
+        .with_walkers(16)
+        .with_iterations(1500)
+        .with_differential_evolution(differential_evolution)
+        .with_seed(3)
+    )
+
+    samples = sampler.run(correlated_gaussian_problem(), [0.0, 0.0])
+
+    assert len(samples.chains) == 16
+    assert all(len(chain) == 1500 for chain in samples.chains)
+    assert samples.draws == 16 * 1500
+
+    draws = np.concatenate([np.asarray(chain)[300:] for chain in samples.chains])
+    assert np.allclose(draws.mean(axis=0), 0.0, atol=0.15)
+    assert np.corrcoef(draws.T)[0, 1] == pytest.approx(0.9, abs=0.05)
+
+
+def test_ensemble_sampler_requires_enough_walkers():
+    with pytest.raises(ValueError, match="walkers"):
+        chron.EnsembleSampler().with_walkers(3).run(correlated_gaussian_problem())
+
+
+def test_samples_diagnostics_summarise_chains():
+    samples = (
+        chron.sampler.EnsembleSampler()
+        .with_walkers(8)
+        .with_iterations(2000)
+        .with_seed(4)
+        .run(correlated_gaussian_problem(), [0.0, 0.0])
+    )
+
+    kept = samples.burn_in(200).thin(2)
+    assert all(len(chain) == 900 for chain in kept.chains)
+    assert kept.draws == 8 * 900
+    assert kept.acceptance_rates == samples.acceptance_rates
+    assert all(0.0 < rate < 1.0 for rate in kept.acceptance_rates)
+
+    assert all(rhat < 1.1 for rhat in kept.rhat())
+    assert all(0.0 < ess < kept.draws for ess in kept.ess_bulk())
+    assert all(0.0 < ess for ess in kept.ess_tail())
+    assert all(tau >= 1.0 for tau in kept.autocorrelation_time())
+    assert all(0.0 < mcse < 0.2 for mcse in kept.mcse())
+
+    draws = np.concatenate([np.asarray(chain) for chain in kept.chains])
+    np.testing.assert_allclose(
+        kept.quantiles([0.05, 0.5, 0.95]),
+        np.quantile(draws, [0.05, 0.5, 0.95], axis=0),
+    )
+    np.testing.assert_allclose(kept.covariance(), np.cov(draws.T))
+
+
+def test_diagnostics_are_nan_for_short_chains():
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(quadratic_potential)
+        .with_parameter("x", 1.0)
+        .build()
+    )
+    samples = chron.sampler.MetropolisHastings().with_iterations(2).run(problem, [0.0])
+
+    assert math.isnan(samples.rhat()[0])
+    assert len(samples.acceptance_rates) == 1
+
+
+def sse_problem():
+    t_span = np.linspace(0.0, 1.0, 10)
+    return (
+        chron.VectorBuilder()
+        .with_objective(lambda x: x[0] * t_span)
+        .with_data(2.0 * t_span)
+        .with_parameter("a", 1.0)
+        .with_cost(chron.cost.SSE())
+        .build()
+    )
+
+
+@pytest.mark.parametrize(
+    "sampler",
+    [
+        chron.sampler.MetropolisHastings().with_iterations(10),
+        chron.sampler.HamiltonianMonteCarlo().with_iterations(10),
+        chron.sampler.EnsembleSampler().with_iterations(10),
+        chron.sampler.ParallelTempering().with_iterations(10),
+        chron.sampler.DynamicNestedSampler(),
+    ],
+)
+def test_samplers_reject_non_likelihood_costs(sampler):
+    with pytest.raises(ValueError, match="not a negative log-likelihood"):
+        sampler.run(sse_problem(), [1.0])
+
+
+def bimodal_problem():
+    # Equal mixture of N(-4, 0.5^2) and N(4, 0.5^2) under a Uniform(-10, 10) prior,
+    # so the evidence is 1 / 20.
+    def negative_log_likelihood(x):
+        densities = [
+            math.exp(-0.5 * ((x[0] - mu) / 0.5) ** 2) / (0.5 * math.sqrt(2.0 * math.pi))
+            for mu in (-4.0, 4.0)
+        ]
+        return -math.log(0.5 * sum(densities))
+
+    return (
+        chron.ScalarBuilder()
+        .with_callable(negative_log_likelihood)
+        .with_parameter("x", -4.0, prior=chron.Prior.uniform(-10.0, 10.0))
+        .build()
+    )
+
+
+def test_parallel_tempering_mixes_between_modes():
+    sampler = (
+        chron.sampler.ParallelTempering()
+        .with_num_temperatures(12)
+        .with_iterations(6000)
+        .with_warmup(1000)
+        .with_step_size(0.5)
+        .with_seed(7)
+    )
+
+    tempered = sampler.run(bimodal_problem(), [-4.0])
+
+    chain = np.asarray(tempered.samples.chains[0])
+    assert chain.shape == (6000, 1)
+    assert np.mean(chain[:, 0] > 0.0) == pytest.approx(0.5, abs=0.15)
+
+    assert len(tempered.swap_acceptance_rates) == 11
+    assert all(0.05 < rate < 1.0 for rate in tempered.swap_acceptance_rates)
+    assert tempered.temperatures[0] == 1.0
+    assert tempered.temperatures[-1] == pytest.approx(100.0)
+    assert tempered.log_evidence == pytest.approx(-math.log(20.0), abs=0.3)
+
+
+def test_parallel_tempering_rejects_invalid_ladder():
+    sampler = chron.sampler.ParallelTempering().with_temperatures([2.0, 4.0])
+
+    with pytest.raises(ValueError, match="start at 1"):
+        sampler.run(bimodal_problem(), [-4.0])
+
+
+def test_sequential_monte_carlo_estimates_evidence():
+    # Unit Gaussian likelihood for an observation at 1 under a N(3, 1) prior: the
+    # evidence is N(1; 3, sqrt(2)) and the posterior is N(2, 0.5).
+    def negative_log_likelihood(x):
+        return 0.5 * (x[0] - 1.0) ** 2 + 0.5 * math.log(2.0 * math.pi)
+
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(negative_log_likelihood)
+        .with_parameter("x", 0.0, prior=chron.Prior.normal(3.0, 1.0))
+        .build()
+    )
+
+    particles = chron.sampler.SequentialMonteCarlo().with_seed(4).run(problem)
+
+    expected = -1.0 - 0.5 * math.log(4.0 * math.pi)
+    assert particles.log_evidence == pytest.approx(expected, abs=0.1)
+    assert particles.mean[0] == pytest.approx(2.0, abs=0.1)
+    assert particles.schedule[0] == 0.0
+    assert particles.schedule[-1] == 1.0
+    assert sum(math.exp(w) for w in particles.log_weights) == pytest.approx(1.0)
+    assert particles.to_samples().draws == particles.draws == 512
+
+
+def test_sequential_monte_carlo_requires_priors_or_bounds():
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(quadratic_potential)
+        .with_parameter("x", 0.0)
+        .build()
+    )
+
+    with pytest.raises(ValueError, match="prior or bounds"):
+        chron.sampler.SequentialMonteCarlo().run(problem)
+
+    with pytest.raises(ValueError, match="not a negative log-likelihood"):
+        chron.sampler.SequentialMonteCarlo().run(sse_problem())
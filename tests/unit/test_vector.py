@@ -269,3 +269,78 @@ def test_vector_builder_config():
     config = problem.config()
     assert "custom_param" in config
     assert config["custom_param"] == 42.0
+
+
+def test_vector_problem_residuals_and_jacobian():
+    """Test residual and Jacobian access on vector problems"""
+    t = np.linspace(0.0, 1.0, 5)
+    data = 2.0 * t + 1.0
+
+    def model(params):
+        return params[0] * t + params[1]
+
+    problem = (
+        chron.VectorBuilder()
+        .with_objective(model)
+        .with_data(data)
+        .with_parameter("slope", 1.0)
+        .with_parameter("intercept", 0.0)
+        .build()
+    )
+
+    residuals = problem.residuals([1.0, 0.0])
+    assert np.allclose(residuals, -(t + 1.0))
+
+    residuals_j, jacobian = problem.residuals_with_jacobian([1.0, 0.0])
+    assert np.allclose(residuals_j, residuals)
+    jacobian = np.asarray(jacobian)
+    assert jacobian.shape == (5, 2)
+    assert np.allclose(jacobian[:, 0], t, atol=1e-6)
+    assert np.allclose(jacobian[:, 1], 1.0, atol=1e-6)
+
+
+def test_scalar_problem_residuals_unavailable():
+    """Scalar problems have no residual vector to expose"""
+    problem = (
+        chron.ScalarBuilder()
+        .with_callable(lambda x: np.asarray([x[0] ** 2]))
+        .with_parameter("x", 1.0)
+        .build()
+    )
+
+    with pytest.raises(ValueError, match="only available for Diffsol and Vector"):
+        problem.residuals([1.0])
+
+
+def test_vector_builder_missing_data_and_mask():
+    t_span = np.linspace(0, 1, 8)
+    data = 2.0 * t_span
+    data[2] = np.nan
+    mask = np.ones_like(t_span, dtype=bool)
+    mask[5] = False
+
+    problem = (
+        chron.VectorBuilder()
+        .with_objective(lambda params: params[0] * t_span)
+        .with_data(data)
+        .with_mask(mask)
+        .with_parameter("slope", 1.0)
+        .with_cost(chron.cost.RMSE())
+        .build()
+    )
+
+    observed = np.isfinite(data) & mask
+    residuals = np.asarray(problem.residuals([1.5]))
+    np.testing.assert_allclose(residuals, -0.5 * t_span[observed])
+    expected = np.sqrt(np.mean(residuals**2))
+    assert problem.evaluate([1.5]) == pytest.approx(expected, rel=1e-12)
+    assert problem.evaluate([2.0]) == pytest.approx(0.0, abs=1e-12)
+
+    with pytest.raises(ValueError, match="Mask length"):
+        (
+            chron.VectorBuilder()
+            .with_objective(lambda params: params[0] * t_span)
+            .with_data(data)
+            .with_mask(mask[:-1])
+            .build()
+        )